@@ -0,0 +1,76 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use prse::{try_parse, Parse};
+
+const NUMBERS: [&str; 8] = [
+    "0",
+    "7",
+    "42",
+    "1337",
+    "65535",
+    "123456",
+    "9999999",
+    "4294967295",
+];
+const LABELLED: [&str; 8] = [
+    "n=0",
+    "n=7",
+    "n=42",
+    "n=1337",
+    "n=65535",
+    "n=123456",
+    "n=9999999",
+    "n=4294967295",
+];
+
+// Each group parses the same input by hand and with the macro, so the difference is the cost of the macro.
+
+fn whole_input(c: &mut Criterion) {
+    let mut group = c.benchmark_group("whole input");
+
+    group.bench_function("from_str", |b| {
+        b.iter(|| {
+            for n in black_box(NUMBERS) {
+                black_box(u32::from_str(n).unwrap());
+            }
+        })
+    });
+
+    // A pattern that is a single capture is parsed directly.
+    group.bench_function("try_parse", |b| {
+        b.iter(|| {
+            for n in black_box(NUMBERS) {
+                let n: u32 = try_parse!(n, "{}").unwrap();
+                black_box(n);
+            }
+        })
+    });
+
+    group.finish();
+}
+
+fn with_literal(c: &mut Criterion) {
+    let mut group = c.benchmark_group("with literal");
+
+    group.bench_function("from_str", |b| {
+        b.iter(|| {
+            for n in black_box(LABELLED) {
+                black_box(u32::from_str(n.strip_prefix("n=").unwrap()).unwrap());
+            }
+        })
+    });
+
+    // A literal in the pattern goes through the general matching.
+    group.bench_function("try_parse", |b| {
+        b.iter(|| {
+            for n in black_box(LABELLED) {
+                let n: u32 = try_parse!(n, "n={}").unwrap();
+                black_box(n);
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, whole_input, with_literal);
+criterion_main!(benches);
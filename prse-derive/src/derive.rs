@@ -1,37 +1,80 @@
 use crate::instructions::{Instruction, Instructions};
+use crate::modifier::Radix;
 use crate::var::Var;
-use proc_macro2::{Ident, Span};
-use std::collections::HashSet;
+use proc_macro2::{Ident, Span, TokenStream};
 use syn::parse::{Parse, ParseStream};
 use syn::{
-    Attribute, Data, DeriveInput, Expr, Generics, Lit, LitStr, Meta, MetaNameValue, Variant,
+    Attribute, Data, DeriveInput, Expr, Generics, Lit, LitInt, LitStr, Meta, MetaNameValue, Variant,
 };
 
 #[derive(Clone)]
 pub(crate) enum Derive {
     NoAttributes(Generics, Ident),
-    Struct(Generics, Ident, Fields),
+    /// A struct with the fields of each of its patterns, which are tried in order.
+    Struct(Generics, Ident, Vec<Fields>),
     Enum(Generics, Ident, Vec<(Ident, Fields)>),
+    /// A fieldless enum parsed from its discriminant, with the integer type of its repr.
+    FromRepr(Generics, Ident, Ident, Vec<Ident>),
+    /// A fieldless enum parsed from an integer of its repr type, with the range of integers of each variant.
+    Range(Generics, Ident, Ident, Vec<(Ident, TokenStream)>),
+    /// A struct of bools parsed from a whitespace separated list of the flags that are set, and whether
+    /// unknown flags are ignored.
+    Flags(Generics, Ident, Vec<Ident>, bool),
 }
 
+/// A sub-pattern, a capture of the pattern that is parsed by its own instructions into several fields.
+pub(crate) type SubPattern = (Ident, Instructions);
+
 #[derive(Clone)]
 pub(crate) enum Fields {
-    Named(Instructions),
+    /// The instructions, the fields with a default (and the string it is parsed from), the fields
+    /// that collect every capture and the sub-patterns captured by the instructions.
+    Named(
+        Instructions,
+        Vec<(Ident, Option<LitStr>)>,
+        Vec<Ident>,
+        Vec<SubPattern>,
+    ),
     Unnamed(Instructions),
     Unit(String),
 }
 
 fn validate_fields(
     fields: syn::Fields,
-    instructions: Instructions,
+    mut instructions: Instructions,
     span: Span,
+    subs: &[SubPattern],
 ) -> syn::Result<Fields> {
+    if instructions.0.contains(&Instruction::GroupStart(true))
+        || subs
+            .iter()
+            .any(|(_, sub)| sub.0.contains(&Instruction::GroupStart(true)))
+    {
+        return Err(syn::Error::new(
+            span,
+            "Tuple groups cannot be used when deriving, consider using an optional group instead.",
+        ));
+    }
+    if !subs.is_empty() && !matches!(fields, syn::Fields::Named(_)) {
+        return Err(syn::Error::new(
+            span,
+            "Sub-patterns can only be used on structs with named fields.",
+        ));
+    }
     match fields {
         syn::Fields::Unit => {
             let mut iter = instructions.0.into_iter();
             match iter.next() {
                 None => Ok(Fields::Unit("".into())),
                 Some(Instruction::Lit(s)) if iter.next().is_none() => Ok(Fields::Unit(s)),
+                Some(Instruction::FlexLit(..)) => Err(syn::Error::new(
+                    span,
+                    "Pattern flags cannot be used on a unit field.",
+                )),
+                Some(Instruction::GroupStart(_)) => Err(syn::Error::new(
+                    span,
+                    "Optional groups cannot be used on a unit field.",
+                )),
                 _ => Err(syn::Error::new(
                     span,
                     "A unit field cannot contain variables",
@@ -39,28 +82,131 @@ fn validate_fields(
             }
         }
         syn::Fields::Named(fields) => {
-            let fields: Vec<_> = fields
-                .named
-                .into_iter()
-                .map(|f| (f.ident.unwrap(), f.ty))
+            let mut idents = vec![];
+            let mut defaults = vec![];
+            let mut multi = vec![];
+            let mut radixes = vec![];
+            for f in fields.named {
+                let ident = f.ident.unwrap();
+                let attributes = field_attributes(&f.attrs)?;
+                if attributes.default {
+                    defaults.push((ident.clone(), attributes.default_value));
+                }
+                if attributes.multi {
+                    if instructions.0.iter().any(|i| {
+                        !matches!(i, Instruction::Parse(_))
+                            && matches!(i.get_var(), Some(Var::Ident(v)) if v == &ident)
+                    }) {
+                        return Err(syn::Error::new(
+                            span,
+                            format!(
+                                "multi field `{ident}` can only be captured using plain captures."
+                            ),
+                        ));
+                    }
+                    instructions.capture_all(&ident);
+                    multi.push(ident.clone());
+                }
+                if let Some(radix) = attributes.radix {
+                    radixes.push((ident.clone(), radix));
+                }
+                idents.push(ident);
+            }
+            if let Some((name, _)) = subs.iter().find(|(name, _)| idents.contains(name)) {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format!("sub-pattern `{name}` has the same name as a field."),
+                ));
+            }
+            let optional = instructions.optional_indices();
+            let mut subs: Vec<SubPattern> = subs
+                .iter()
+                .filter(|(name, _)| {
+                    instructions
+                        .0
+                        .iter()
+                        .any(|i| matches!(i.get_var(), Some(Var::Ident(v)) if v == name))
+                })
+                .cloned()
                 .collect();
-            let mut seen_idents = HashSet::new();
+            for (ident, radix) in radixes {
+                let field = Var::Ident(ident.clone());
+                instructions
+                    .set_radix(&field, radix)
+                    .and_then(|()| {
+                        subs.iter_mut()
+                            .try_for_each(|(_, sub)| sub.set_radix(&field, radix))
+                    })
+                    .map_err(|e| syn::Error::new(span, format!("field `{ident}` {e}")))?;
+            }
+            for (name, sub) in subs.iter() {
+                let is_plain = instructions.0.iter().enumerate().all(|(idx, i)| match i {
+                    Instruction::Parse(Var::Ident(v)) if v == name => !optional.contains(&idx),
+                    Instruction::Modified(Var::Ident(v), m) if v == name => {
+                        m.radix.is_none()
+                            && !m.grouped
+                            && m.length.is_none()
+                            && !optional.contains(&idx)
+                    }
+                    Instruction::BackRef(v, _) => v != name,
+                    i => !matches!(i.get_var(), Some(Var::Ident(v)) if v == name),
+                });
+                if !is_plain {
+                    return Err(syn::Error::new(
+                        span,
+                        format!("sub-pattern `{name}` can only be captured once, by a plain capture outside of optional groups."),
+                    ));
+                }
+                for i in sub.0.iter() {
+                    match i.get_var() {
+                        Some(Var::Ident(field)) if multi.contains(field) => {
+                            return Err(syn::Error::new(
+                                span,
+                                format!(
+                                    "multi field `{field}` cannot be captured in a sub-pattern."
+                                ),
+                            ));
+                        }
+                        Some(Var::Ident(field)) if !idents.contains(field) => {
+                            return Err(syn::Error::new(
+                                span,
+                                format!("sub-pattern `{name}` references unknown field `{field}`"),
+                            ));
+                        }
+                        Some(Var::Ident(_)) | None => {}
+                        _ => {
+                            return Err(syn::Error::new(
+                                span,
+                                "Named fields can only be parsed by name.",
+                            ));
+                        }
+                    }
+                }
+            }
+            let captures = |instructions: &Instructions, field: &Ident| {
+                instructions
+                    .0
+                    .iter()
+                    .any(|i| matches!(i.get_var(), Some(Var::Ident(v)) if v == field))
+            };
+            if let Some(field) = idents.iter().find(|f| {
+                let outer = captures(&instructions, f) as usize;
+                outer + subs.iter().filter(|(_, sub)| captures(sub, f)).count() > 1
+            }) {
+                return Err(syn::Error::new(
+                    span,
+                    format!("field `{field}` is captured by more than one sub-pattern or by both the pattern and a sub-pattern."),
+                ));
+            }
             for i in instructions.0.iter() {
                 match i.get_var() {
                     None => {}
+                    Some(Var::Ident(ident)) if subs.iter().any(|(name, _)| name == ident) => {}
                     Some(Var::Ident(ident)) => {
-                        if fields.iter().any(|(i, _)| i == ident) {
-                            if seen_idents.contains(ident) {
-                                return Err(syn::Error::new(
-                                    span,
-                                    format!("Duplicated variable: {ident}"),
-                                ));
-                            }
-                            seen_idents.insert(ident);
-                        } else {
+                        if !idents.contains(ident) {
                             return Err(syn::Error::new(
                                 span,
-                                format!("Unexpected variable: {ident}"),
+                                format!("pattern references unknown field `{ident}`"),
                             ));
                         }
                     }
@@ -72,9 +218,57 @@ fn validate_fields(
                     }
                 }
             }
-            Ok(Fields::Named(instructions))
+            let is_parsed = |ident: &Ident| {
+                captures(&instructions, ident) || subs.iter().any(|(_, sub)| captures(sub, ident))
+            };
+            let has_default = |ident: &Ident| defaults.iter().any(|(d, _)| d == ident);
+            if let Some(field) = idents.iter().find(|f| !is_parsed(f) && !has_default(f)) {
+                return Err(syn::Error::new(
+                    field.span(),
+                    format!("field `{field}` is never parsed and has no default"),
+                ));
+            }
+            let optional = optional
+                .into_iter()
+                .map(|idx| &instructions.0[idx])
+                .chain(subs.iter().flat_map(|(_, sub)| {
+                    sub.optional_indices().into_iter().map(|idx| &sub.0[idx])
+                }));
+            for i in optional {
+                if let Some(Var::Ident(field)) = i.get_var() {
+                    if multi.contains(field) {
+                        return Err(syn::Error::new(
+                            span,
+                            format!(
+                                "multi field `{field}` cannot be captured in an optional group."
+                            ),
+                        ));
+                    }
+                    if !has_default(field) && i.default_value().is_none() {
+                        return Err(syn::Error::new(
+                            span,
+                            format!("field `{field}` is captured in an optional group and has no default"),
+                        ));
+                    }
+                }
+            }
+            Ok(Fields::Named(instructions, defaults, multi, subs))
         }
         syn::Fields::Unnamed(fields) => {
+            for (idx, f) in fields.unnamed.iter().enumerate() {
+                let attributes = field_attributes(&f.attrs)?;
+                if attributes.default || attributes.multi {
+                    return Err(syn::Error::new(
+                        span,
+                        "Only the radix field attribute can be used on unnamed fields.",
+                    ));
+                }
+                if let Some(radix) = attributes.radix {
+                    instructions
+                        .set_radix(&Var::Position(idx as u8), radix)
+                        .map_err(|e| syn::Error::new(span, format!("field {idx} {e}")))?;
+                }
+            }
             let max = fields.unnamed.iter().count() - 1;
             let mut count = 0;
             for i in instructions.0.iter() {
@@ -103,6 +297,20 @@ fn validate_fields(
                     _ => {}
                 }
             }
+            let found = instructions
+                .0
+                .iter()
+                .filter(|i| i.get_var().is_some())
+                .count();
+            if found != max + 1 {
+                return Err(syn::Error::new(
+                    span,
+                    format!(
+                        "Expected {} captures, one for each field, but found {found}.",
+                        max + 1
+                    ),
+                ));
+            }
             Ok(Fields::Unnamed(instructions))
         }
     }
@@ -113,22 +321,122 @@ impl Parse for Derive {
         let input: DeriveInput = stream.parse()?;
 
         match input.data {
-            Data::Struct(s) => {
+            Data::Struct(s) if input.attrs.iter().any(is_flags) => {
+                no_attributes(input.attrs.iter().filter(|a| !is_flags(a)))?;
                 no_attributes(s.fields.iter().flat_map(|f| f.attrs.iter()))?;
-                match attribute_instruction(input.attrs.into_iter())? {
-                    None => Ok(Derive::NoAttributes(input.generics, input.ident)),
-                    Some((instructions, span)) => Ok(Derive::Struct(
-                        input.generics,
-                        input.ident,
-                        validate_fields(s.fields, instructions, span)?,
-                    )),
+                let ignore_unknown = flags_ignore_unknown(&input.attrs)?;
+                let syn::Fields::Named(fields) = s.fields else {
+                    return Err(syn::Error::new(
+                        input.ident.span(),
+                        "flags can only be used on structs with named fields.",
+                    ));
+                };
+                let fields = fields.named.into_iter().map(|f| f.ident.unwrap()).collect();
+                Ok(Derive::Flags(
+                    input.generics,
+                    input.ident,
+                    fields,
+                    ignore_unknown,
+                ))
+            }
+            Data::Struct(s) => {
+                let subs = sub_patterns(&input.attrs)?;
+                let patterns =
+                    attribute_instructions(input.attrs.into_iter().filter(|a| !is_sub(a)))?;
+                if patterns.is_empty() && !subs.is_empty() {
+                    return Err(syn::Error::new(
+                        input.ident.span(),
+                        "Sub-patterns can only be used alongside a pattern.",
+                    ));
+                }
+                if patterns.is_empty() {
+                    no_attributes(s.fields.iter().flat_map(|f| f.attrs.iter()))?;
+                    Ok(Derive::NoAttributes(input.generics, input.ident))
+                } else {
+                    let fields = patterns
+                        .into_iter()
+                        .map(|(instructions, span)| {
+                            validate_fields(s.fields.clone(), instructions, span, &subs)
+                        })
+                        .collect::<syn::Result<_>>()?;
+                    Ok(Derive::Struct(input.generics, input.ident, fields))
                 }
             }
+            Data::Enum(e) if input.attrs.iter().any(is_from_repr) => {
+                no_attributes(input.attrs.iter().filter(|a| !is_from_repr(a)))?;
+                no_attributes(e.variants.iter().flat_map(|v| {
+                    v.attrs
+                        .iter()
+                        .chain(v.fields.iter().flat_map(|f| f.attrs.iter()))
+                }))?;
+                let repr = repr_type(&input.attrs)?;
+                let variants = e
+                    .variants
+                    .into_iter()
+                    .map(|v| match v.fields {
+                        syn::Fields::Unit => Ok(v.ident),
+                        _ => Err(syn::Error::new(
+                            v.ident.span(),
+                            format!(
+                                "from_repr can only be used on enums without fields, found fields on variant `{}`.",
+                                v.ident
+                            ),
+                        )),
+                    })
+                    .collect::<syn::Result<_>>()?;
+                Ok(Derive::FromRepr(
+                    input.generics,
+                    input.ident,
+                    repr,
+                    variants,
+                ))
+            }
+            Data::Enum(e) if input.attrs.iter().any(is_range) => {
+                no_attributes(input.attrs.iter().filter(|a| !is_range(a)))?;
+                no_attributes(
+                    e.variants
+                        .iter()
+                        .flat_map(|v| v.fields.iter().flat_map(|f| f.attrs.iter())),
+                )?;
+                let repr = repr_type(&input.attrs)?;
+                let variants = e
+                    .variants
+                    .into_iter()
+                    .map(|v| {
+                        if !matches!(v.fields, syn::Fields::Unit) {
+                            return Err(syn::Error::new(
+                                v.ident.span(),
+                                format!(
+                                    "range can only be used on enums without fields, found fields on variant `{}`.",
+                                    v.ident
+                                ),
+                            ));
+                        }
+                        let range = variant_range(&v.attrs)?.ok_or_else(|| {
+                            syn::Error::new(
+                                v.ident.span(),
+                                format!("variant `{}` needs a #[prse(range = \"...\")] attribute.", v.ident),
+                            )
+                        })?;
+                        Ok((v.ident, range))
+                    })
+                    .collect::<syn::Result<_>>()?;
+                Ok(Derive::Range(input.generics, input.ident, repr, variants))
+            }
             Data::Enum(e) => {
                 no_attributes(input.attrs.iter())?;
+                let has_variant_attributes = e
+                    .variants
+                    .iter()
+                    .flat_map(|v| v.attrs.iter())
+                    .any(|a| a.path().is_ident("prse"));
+                // Fields have their attributes validated alongside the variant's instructions.
                 no_attributes(
                     e.variants
                         .iter()
+                        .filter(|v| {
+                            !has_variant_attributes || matches!(v.fields, syn::Fields::Unit)
+                        })
                         .flat_map(|v| v.fields.iter().flat_map(|f| f.attrs.iter())),
                 )?;
 
@@ -147,27 +455,6 @@ impl Parse for Derive {
     }
 }
 
-fn attribute_instruction(
-    mut attrs: impl Iterator<Item = Attribute>,
-) -> syn::Result<Option<(Instructions, Span)>> {
-    if let Some(a) = attrs.next() {
-        if let Some(lit) = get_prse_lit(&a)? {
-            for a in attrs.by_ref() {
-                if get_prse_lit(&a)?.is_some() {
-                    return Err(syn::Error::new(
-                        a.bracket_token.span.join(),
-                        "Expected only a single prse attribute.",
-                    ));
-                }
-            }
-            let span = lit.span();
-            let lit_string = lit.value();
-            return Ok(Some((Instructions::new(&lit_string, span)?, span)));
-        }
-    }
-    Ok(None)
-}
-
 fn attribute_instructions(
     attrs: impl Iterator<Item = Attribute>,
 ) -> syn::Result<Vec<(Instructions, Span)>> {
@@ -192,9 +479,15 @@ fn get_prse_lit(a: &Attribute) -> syn::Result<Option<LitStr>> {
                     }),
                 ..
             }) => Ok(Some(l.clone())),
+            Meta::List(_) => a.parse_args::<LitStr>().map(Some).map_err(|_| {
+                syn::Error::new(
+                    a.bracket_token.span.join(),
+                    "prse attribute must be of the form #[prse = \"parse_string\"] or #[prse(\"parse_string\")]",
+                )
+            }),
             _ => Err(syn::Error::new(
                 a.bracket_token.span.join(),
-                "prse attribute must be of the form #[prse = \"parse_string\"]",
+                "prse attribute must be of the form #[prse = \"parse_string\"] or #[prse(\"parse_string\")]",
             )),
         }
     } else {
@@ -202,6 +495,229 @@ fn get_prse_lit(a: &Attribute) -> syn::Result<Option<LitStr>> {
     }
 }
 
+fn is_from_repr(a: &Attribute) -> bool {
+    a.path().is_ident("prse")
+        && matches!(a.meta, Meta::List(_))
+        && a.parse_args::<Ident>().is_ok_and(|i| i == "from_repr")
+}
+
+fn is_range(a: &Attribute) -> bool {
+    a.path().is_ident("prse")
+        && matches!(a.meta, Meta::List(_))
+        && a.parse_args::<Ident>().is_ok_and(|i| i == "range")
+}
+
+/// Returns the range given by the `range` attribute of a variant, as in `#[prse(range = "200..300")]`.
+///
+/// The range is turned into an expression, either bound can be left out and the end can be inclusive.
+fn variant_range(attrs: &[Attribute]) -> syn::Result<Option<TokenStream>> {
+    let mut range = None;
+    for a in attrs.iter().filter(|a| a.path().is_ident("prse")) {
+        let Meta::List(_) = a.meta else {
+            return Err(syn::Error::new(
+                a.bracket_token.span.join(),
+                "Unexpected prse attribute.",
+            ));
+        };
+        a.parse_nested_meta(|meta| {
+            if !meta.path.is_ident("range") {
+                return Err(meta.error("Unknown prse variant attribute, expected range."));
+            }
+            if range.is_some() {
+                return Err(meta.error("A variant can only have a single range."));
+            }
+            let lit: LitStr = meta.value()?.parse()?;
+            range = Some(parse_range(&lit)?);
+            Ok(())
+        })?;
+    }
+    Ok(range)
+}
+
+/// Parses a range of integer literals such as `200..300`, `200..=299`, `500..` or `..-1`.
+fn parse_range(lit: &LitStr) -> syn::Result<TokenStream> {
+    let value = lit.value();
+    let error = || {
+        syn::Error::new(
+            lit.span(),
+            "expected a range of integers such as \"200..300\", \"200..=299\" or \"500..\".",
+        )
+    };
+    let bound = |bound: &str| -> syn::Result<Option<TokenStream>> {
+        let bound = bound.trim();
+        if bound.is_empty() {
+            return Ok(None);
+        }
+        let (sign, digits) = match bound.strip_prefix('-') {
+            Some(digits) => (Some(quote!(-)), digits),
+            None => (None, bound),
+        };
+        let int = syn::parse_str::<LitInt>(digits)
+            .ok()
+            .filter(|int| int.suffix().is_empty())
+            .ok_or_else(error)?;
+        let int = LitInt::new(int.base10_digits(), lit.span());
+        Ok(Some(quote!(#sign #int)))
+    };
+    let (start, end, inclusive) = match value.split_once("..") {
+        Some((start, end)) => match end.strip_prefix('=') {
+            Some(end) => (bound(start)?, bound(end)?, true),
+            None => (bound(start)?, bound(end)?, false),
+        },
+        None => return Err(error()),
+    };
+    Ok(match (start, end, inclusive) {
+        (None, None, _) | (_, None, true) => return Err(error()),
+        (start, end, true) => quote!(#start ..= #end),
+        (start, end, false) => quote!(#start .. #end),
+    })
+}
+
+fn is_flags(a: &Attribute) -> bool {
+    a.path().is_ident("prse")
+        && matches!(&a.meta, Meta::List(l) if matches!(
+            l.tokens.clone().into_iter().next(),
+            Some(proc_macro2::TokenTree::Ident(i)) if i == "flags"
+        ))
+}
+
+fn is_sub(a: &Attribute) -> bool {
+    a.path().is_ident("prse")
+        && matches!(&a.meta, Meta::List(l) if matches!(
+            l.tokens.clone().into_iter().next(),
+            Some(proc_macro2::TokenTree::Ident(i)) if i == "sub"
+        ))
+}
+
+/// Returns the sub-patterns given by the `sub` attributes, as in `#[prse(sub(date = "{year}-{month}-{day}"))]`.
+fn sub_patterns(attrs: &[Attribute]) -> syn::Result<Vec<SubPattern>> {
+    let mut subs: Vec<SubPattern> = vec![];
+    for a in attrs.iter().filter(|a| is_sub(a)) {
+        a.parse_nested_meta(|meta| {
+            if !meta.path.is_ident("sub") {
+                return Err(meta.error("Unknown prse attribute, expected sub."));
+            }
+            meta.parse_nested_meta(|sub| {
+                let Some(name) = sub.path.get_ident().cloned() else {
+                    return Err(sub.error("Expected the name of the sub-pattern."));
+                };
+                if subs.iter().any(|(n, _)| n == &name) {
+                    return Err(
+                        sub.error(format!("sub-pattern `{name}` is defined more than once."))
+                    );
+                }
+                let lit: LitStr = sub.value()?.parse()?;
+                let instructions = Instructions::new(&lit.value(), lit.span())?;
+                subs.push((name, instructions));
+                Ok(())
+            })
+        })?;
+    }
+    Ok(subs)
+}
+
+/// Returns whether the `flags` attribute asks for unknown flags to be ignored, as in `#[prse(flags(ignore_unknown))]`.
+fn flags_ignore_unknown(attrs: &[Attribute]) -> syn::Result<bool> {
+    let mut ignore_unknown = false;
+    for a in attrs.iter().filter(|a| is_flags(a)) {
+        a.parse_nested_meta(|meta| {
+            if !meta.path.is_ident("flags") {
+                return Err(meta.error("Unknown prse attribute, expected flags."));
+            }
+            if meta.input.peek(syn::token::Paren) {
+                meta.parse_nested_meta(|option| {
+                    if option.path.is_ident("ignore_unknown") {
+                        ignore_unknown = true;
+                        Ok(())
+                    } else {
+                        Err(option.error("Unknown flags option, expected ignore_unknown."))
+                    }
+                })?;
+            }
+            Ok(())
+        })?;
+    }
+    Ok(ignore_unknown)
+}
+
+/// Returns the integer type of the enum's repr attribute, which defaults to `isize`.
+fn repr_type(attrs: &[Attribute]) -> syn::Result<Ident> {
+    const INTEGERS: [&str; 12] = [
+        "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+    ];
+    let mut repr = None;
+    for a in attrs.iter().filter(|a| a.path().is_ident("repr")) {
+        a.parse_nested_meta(|meta| {
+            if let Some(ident) = meta.path.get_ident() {
+                if INTEGERS.iter().any(|i| ident == i) {
+                    repr = Some(ident.clone());
+                }
+            }
+            // Skip the arguments of repr(align(..)) and repr(packed(..)).
+            if meta.input.peek(syn::token::Paren) {
+                let _content;
+                parenthesized!(_content in meta.input);
+            }
+            Ok(())
+        })?;
+    }
+    Ok(repr.unwrap_or_else(|| Ident::new("isize", Span::call_site())))
+}
+
+#[derive(Default)]
+struct FieldAttributes {
+    default: bool,
+    /// The string the default is parsed from, instead of using [`Default`].
+    default_value: Option<LitStr>,
+    multi: bool,
+    /// The radix every capture of the field is parsed with.
+    radix: Option<Radix>,
+}
+
+fn field_attributes(attrs: &[Attribute]) -> syn::Result<FieldAttributes> {
+    let mut field_attributes = FieldAttributes::default();
+    for a in attrs.iter().filter(|a| a.path().is_ident("prse")) {
+        match &a.meta {
+            Meta::List(_) => a.parse_nested_meta(|meta| {
+                if meta.path.is_ident("default") {
+                    if meta.input.peek(Token![=]) {
+                        field_attributes.default_value = Some(meta.value()?.parse()?);
+                    }
+                    field_attributes.default = true;
+                    Ok(())
+                } else if meta.path.is_ident("multi") {
+                    field_attributes.multi = true;
+                    Ok(())
+                } else if meta.path.is_ident("radix") {
+                    let radix: LitInt = meta.value()?.parse()?;
+                    field_attributes.radix = Some(match radix.base10_parse::<u32>()? {
+                        2 => Radix::Binary,
+                        8 => Radix::Octal,
+                        16 => Radix::Hex,
+                        _ => {
+                            return Err(syn::Error::new(
+                                radix.span(),
+                                "Unsupported radix, expected 2, 8 or 16.",
+                            ))
+                        }
+                    });
+                    Ok(())
+                } else {
+                    Err(meta
+                        .error("Unknown prse field attribute, expected default, multi or radix."))
+                }
+            })?,
+            _ => {
+                return Err(syn::Error::new(
+                    a.bracket_token.span.join(),
+                    "Unexpected prse attribute.",
+                ))
+            }
+        }
+    }
+    Ok(field_attributes)
+}
+
 fn no_attributes<'a>(attrs: impl Iterator<Item = &'a Attribute>) -> syn::Result<()> {
     #[allow(clippy::manual_try_fold)]
     attrs.fold(Ok(()), |i, a| {
@@ -257,7 +773,7 @@ fn get_variant_attributes(
             instructions.into_iter().map(move |(instr, span)| {
                 Ok((
                     v_ident.clone(),
-                    validate_fields(v_fields.clone(), instr, span)?,
+                    validate_fields(v_fields.clone(), instr, span, &[])?,
                 ))
             })
         })
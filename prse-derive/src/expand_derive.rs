@@ -1,24 +1,50 @@
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::ToTokens;
+use syn::ext::IdentExt;
 use syn::{
-    GenericParam, Generics, ImplGenerics, Lifetime, LifetimeParam, WhereClause, WherePredicate,
+    GenericParam, Generics, ImplGenerics, Lifetime, LifetimeParam, LitStr, WhereClause,
+    WherePredicate,
 };
 
-use crate::derive::{Derive, Fields};
-use crate::instructions::Instructions;
+use crate::derive::{Derive, Fields, SubPattern};
+use crate::instructions::{Anchor, Instruction, Instructions};
+use crate::modifier::LiteralFlags;
 
 impl Derive {
     pub fn into_token_stream(self) -> TokenStream {
         match self {
             Derive::NoAttributes(g, i) => expand_default(g, i),
-            Derive::Struct(mut g, name, f) => {
+            Derive::Struct(mut g, name, patterns) => {
                 let (impl_generics, ty_generics, where_clause) = split_for_impl(&mut g, []);
 
-                let tokens = match f {
-                    Fields::Named(instructions) => expand_field(instructions, quote!(Self), None),
-                    Fields::Unnamed(instructions) => expand_tuple(instructions, quote!(Self), None),
-                    Fields::Unit(s) => expand_unit(s, quote!(Self), None),
-                };
+                // With several patterns, the error of each one is kept in case none of them match.
+                let name_str = name.to_string();
+                let errors: Vec<_> = (0..patterns.len())
+                    .map(|i| format_ident!("__prse_error_{i}"))
+                    .collect();
+                let mut tokens = (patterns.len() > 1).then(|| {
+                    quote! {
+                        Err(::prse::__private::no_pattern_matched(s, #name_str, &[#(#errors),*]))
+                    }
+                });
+                for (f, error) in patterns.into_iter().zip(errors).rev() {
+                    let next = tokens.map(|next| {
+                        let e = match &f {
+                            Fields::Unit(l) => unit_error(l),
+                            _ => quote!(e),
+                        };
+                        quote!({ let #error = #e; #next })
+                    });
+                    tokens = Some(match f {
+                        Fields::Named(instructions, defaults, multi, subs) => {
+                            expand_field(instructions, defaults, multi, subs, quote!(Self), next)
+                        }
+                        Fields::Unnamed(instructions) => {
+                            expand_tuple(instructions, quote!(Self), next)
+                        }
+                        Fields::Unit(s) => expand_unit(s, quote!(Self), next),
+                    });
+                }
 
                 quote! {
                     #[automatically_derived]
@@ -32,13 +58,35 @@ impl Derive {
             Derive::Enum(mut g, name, v) => {
                 let (impl_generics, ty_generics, where_clause) = split_for_impl(&mut g, []);
 
-                let mut result = None;
+                let name_str = name.to_string();
+                let mut result = Some(quote! {
+                    Err(__prse_error.unwrap_or_else(|| ::prse::__private::unknown_variant(s, #name_str)))
+                });
 
                 for (variant, f) in v.into_iter().rev() {
-                    result = Some(match f {
-                        Fields::Named(instructions) => {
-                            expand_field(instructions, quote!(Self::#variant), result)
+                    // Keep the error of the first variant whose leading literal matched since it is most likely
+                    // the variant that the input was meant to be.
+                    let record = match &f {
+                        Fields::Named(instructions, ..) | Fields::Unnamed(instructions) => {
+                            let matches = leading_literal_matches(instructions);
+                            Some(quote! {
+                                if __prse_error.is_none() && #matches {
+                                    __prse_error = Some(e);
+                                }
+                            })
                         }
+                        Fields::Unit(_) => None,
+                    };
+                    result = result.map(|next| quote!({ #record #next }));
+                    result = Some(match f {
+                        Fields::Named(instructions, defaults, multi, subs) => expand_field(
+                            instructions,
+                            defaults,
+                            multi,
+                            subs,
+                            quote!(Self::#variant),
+                            result,
+                        ),
                         Fields::Unnamed(instructions) => {
                             expand_tuple(instructions, quote!(Self::#variant), result)
                         }
@@ -50,21 +98,92 @@ impl Derive {
                     #[automatically_derived]
                     impl #impl_generics ::prse::Parse<'__prse_a> for #name #ty_generics #where_clause {
                         fn from_str(s: &'__prse_a str) -> Result<Self, ::prse::ParseError> {
+                            #[allow(unused_mut)]
+                            let mut __prse_error: Option<::prse::ParseError> = None;
                             #result
                         }
                     }
                 }
             }
+            Derive::FromRepr(mut g, name, repr, variants) => {
+                let (impl_generics, ty_generics, where_clause) = split_for_impl(&mut g, []);
+                let consts: Vec<_> = (0..variants.len())
+                    .map(|i| format_ident!("__PRSE_DISCRIMINANT_{i}"))
+                    .collect();
+                let name_str = name.to_string();
+
+                quote! {
+                    #[automatically_derived]
+                    impl #impl_generics ::prse::Parse<'__prse_a> for #name #ty_generics #where_clause {
+                        fn from_str(s: &'__prse_a str) -> Result<Self, ::prse::ParseError> {
+                            #(const #consts: #repr = #name::#variants as #repr;)*
+                            let value: #repr = ::prse::ExtParseStr::lending_parse(s)?;
+                            match value {
+                                #(#consts => Ok(#name::#variants),)*
+                                _ => Err(::prse::__private::unknown_discriminant(value, #name_str)),
+                            }
+                        }
+                    }
+                }
+            }
+            Derive::Range(mut g, name, repr, variants) => {
+                let (impl_generics, ty_generics, where_clause) = split_for_impl(&mut g, []);
+                let (variants, ranges): (Vec<_>, Vec<_>) = variants.into_iter().unzip();
+                let name_str = name.to_string();
+
+                quote! {
+                    #[automatically_derived]
+                    impl #impl_generics ::prse::Parse<'__prse_a> for #name #ty_generics #where_clause {
+                        fn from_str(s: &'__prse_a str) -> Result<Self, ::prse::ParseError> {
+                            let value: #repr = ::prse::ExtParseStr::lending_parse(s)?;
+                            #(
+                                if ::core::ops::RangeBounds::<#repr>::contains(&(#ranges), &value) {
+                                    return Ok(#name::#variants);
+                                }
+                            )*
+                            Err(::prse::__private::out_of_ranges(value, #name_str))
+                        }
+                    }
+                }
+            }
+            Derive::Flags(mut g, name, fields, ignore_unknown) => {
+                let (impl_generics, ty_generics, where_clause) = split_for_impl(&mut g, []);
+                let flags: Vec<_> = fields.iter().map(|f| f.unraw().to_string()).collect();
+                let name_str = name.to_string();
+                let unknown = if ignore_unknown {
+                    quote!({})
+                } else {
+                    quote!(return Err(::prse::__private::unknown_flag(flag, #name_str)))
+                };
+
+                quote! {
+                    #[automatically_derived]
+                    impl #impl_generics ::prse::Parse<'__prse_a> for #name #ty_generics #where_clause {
+                        fn from_str(s: &'__prse_a str) -> Result<Self, ::prse::ParseError> {
+                            #(let mut #fields = false;)*
+                            for flag in s.split_whitespace() {
+                                match flag {
+                                    #(#flags => #fields = true,)*
+                                    _ => #unknown,
+                                }
+                            }
+                            Ok(Self { #(#fields),* })
+                        }
+                    }
+                }
+            }
         }
     }
 }
 
 fn expand_field(
     instructions: Instructions,
+    defaults: Vec<(Ident, Option<LitStr>)>,
+    multi: Vec<Ident>,
+    subs: Vec<SubPattern>,
     to_return: TokenStream,
     error: Option<TokenStream>,
 ) -> TokenStream {
-    let func_name = format_ident!("__prse_func");
     let mut renames = vec![];
     let mut return_idents = vec![];
     let mut func_idents = vec![];
@@ -72,24 +191,83 @@ fn expand_field(
 
     instructions.gen_return_idents(&mut return_idents, &mut func_idents, &mut renames);
 
-    let mut body = quote! {
-        let mut __prse_parse: &str;
-        let mut __prse_remaining = __prse_input;
+    let mut parse = instructions.gen_parse(quote!(s), Anchor::Whole);
+    let mut optional = instructions.optional_idents();
+
+    // Each sub-pattern parses the text of its capture once the pattern matched, and its fields are
+    // returned alongside the other captures.
+    if !subs.is_empty() {
+        let outer_idents = func_idents.clone();
+        let mut sub_parses = vec![];
+        for (k, (name, sub)) in subs.iter().enumerate() {
+            let rename = |ident: &Ident| {
+                format_ident!(
+                    "__prse_sub{k}{}",
+                    ident.to_string().trim_start_matches("__prse")
+                )
+            };
+            let mut sub_renames = vec![];
+            let mut sub_idents = vec![];
+            sub.gen_return_idents(&mut vec![], &mut sub_idents, &mut sub_renames);
+            let sub_idents: Vec<Ident> = sub_idents.iter().map(rename).collect();
+            let position = renames.iter().position(|(l, _)| l == name).unwrap();
+            let (_, capture) = renames.remove(position);
+            func_idents.retain(|i| i != &capture);
+            func_idents.extend(sub_idents.iter().cloned());
+            renames.extend(sub_renames.iter().map(|(l, r)| (l.clone(), rename(r))));
+            optional.extend(sub.optional_idents().iter().map(rename));
+            let sub_parse = sub.gen_parse(capture.to_token_stream(), Anchor::Whole);
+            sub_parses.push(quote!(let ( #(#sub_idents),* ) = #sub_parse?;));
+        }
+        parse = quote! {
+            #parse.and_then(|( #(#outer_idents),* )| {
+                #(#sub_parses)*
+                Ok(( #(#func_idents),* ))
+            })
+        };
+    }
+    let default_value = |field: &Ident| match defaults.iter().find(|(d, _)| d == field) {
+        Some((_, Some(value))) => quote!(::prse::ExtParseStr::lending_parse(#value)?),
+        _ => quote!(::core::default::Default::default()),
     };
 
-    instructions.gen_body(&mut body);
-
-    let function = instructions.gen_function(body, func_name.clone());
+    let multi_fields = multi.iter().map(|m| {
+        let captures = renames.iter().filter(|(l, _)| l == m).map(|(_, r)| r);
+        quote!(#m: [#(#captures),*].into_iter().collect())
+    });
 
-    let fields = renames.iter().map(|(l, r)| quote!(#l: #r));
+    let fields = renames
+        .iter()
+        .filter(|(l, _)| !multi.contains(l))
+        .map(|(l, r)| {
+            if optional.contains(r) {
+                let default = default_value(l);
+                quote! {
+                    #l: match #r {
+                        ::core::option::Option::Some(__prse_value) => __prse_value,
+                        ::core::option::Option::None => #default,
+                    }
+                }
+            } else {
+                quote!(#l: #r)
+            }
+        })
+        .chain(multi_fields)
+        .chain(
+            defaults
+                .iter()
+                .filter(|(d, _)| !renames.iter().any(|(l, _)| l == d))
+                .map(|(d, _)| {
+                    let default = default_value(d);
+                    quote!(#d: #default)
+                }),
+        );
 
     quote! {
         {
             use ::prse::Parse;
 
-            #function
-
-            match #func_name (s) {
+            match #parse {
                 Ok(( #(#func_idents),* )) => {
                     Ok(( #to_return { #(#fields),* }))
                 }
@@ -104,7 +282,6 @@ fn expand_tuple(
     to_return: TokenStream,
     error: Option<TokenStream>,
 ) -> TokenStream {
-    let func_name = format_ident!("__prse_func");
     let mut _renames = vec![];
     let mut return_idents = vec![];
     let mut func_idents = vec![];
@@ -112,22 +289,13 @@ fn expand_tuple(
 
     instructions.gen_return_idents(&mut return_idents, &mut func_idents, &mut _renames);
 
-    let mut body = quote! {
-        let mut __prse_parse: &str;
-        let mut __prse_remaining = __prse_input;
-    };
-
-    instructions.gen_body(&mut body);
-
-    let function = instructions.gen_function(body, func_name.clone());
+    let parse = instructions.gen_parse(quote!(s), Anchor::Whole);
 
     quote! {
         {
             use ::prse::Parse;
 
-            #function
-
-            match #func_name (s) {
+            match #parse {
                 Ok(( #(#func_idents),* )) => {
                     Ok(( #to_return ( #(#return_idents),* )))
                 }
@@ -140,13 +308,8 @@ fn expand_tuple(
 fn expand_unit(s: String, to_return: TokenStream, error: Option<TokenStream>) -> TokenStream {
     let l_string = s.to_token_stream();
     let error = error.unwrap_or_else(|| {
-        if cfg!(feature = "alloc") {
-            quote! {
-                Err(::prse::ParseError::Literal {expected: (#l_string).into(), found: s.into()})
-            }
-        } else {
-            quote!(Err(::prse::ParseError::Literal))
-        }
+        let unit_error = unit_error(&s);
+        quote!(Err(#unit_error))
     });
     quote! {
         match s {
@@ -156,6 +319,33 @@ fn expand_unit(s: String, to_return: TokenStream, error: Option<TokenStream>) ->
     }
 }
 
+/// Generates the error returned when `s` is not the literal of a unit field.
+fn unit_error(s: &str) -> TokenStream {
+    if cfg!(feature = "alloc") {
+        quote!(::prse::__private::literal_error(#s, s))
+    } else {
+        quote!(::prse::ParseError::Literal)
+    }
+}
+
+/// Generates a condition checking whether `s` starts with the literal at the start of the instructions.
+fn leading_literal_matches(instructions: &Instructions) -> TokenStream {
+    match instructions.0.first() {
+        Some(Instruction::Lit(l)) => quote!(s.starts_with(#l)),
+        Some(Instruction::FlexLit(l, flags)) => {
+            let LiteralFlags {
+                case_insensitive,
+                flexible_whitespace,
+                ..
+            } = flags;
+            quote! {
+                ::prse::__private::strip_literal(s, #l, #case_insensitive, #flexible_whitespace).is_some()
+            }
+        }
+        _ => quote!(true),
+    }
+}
+
 fn expand_default(mut generics: Generics, name: Ident) -> TokenStream {
     let (impl_generics, ty_generics, where_clause) = split_for_impl(
         &mut generics,
@@ -180,7 +370,7 @@ fn expand_default(mut generics: Generics, name: Ident) -> TokenStream {
 fn split_for_impl(
     generics: &mut Generics,
     extra_predicates: impl IntoIterator<Item = WherePredicate>,
-) -> (ImplGenerics, TokenStream, Option<&WhereClause>) {
+) -> (ImplGenerics<'_>, TokenStream, Option<&WhereClause>) {
     let ty_generics = generics.split_for_impl().1.to_token_stream();
 
     generics.params.push(GenericParam::Lifetime(LifetimeParam {
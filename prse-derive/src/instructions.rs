@@ -1,4 +1,5 @@
 use crate::invocation::string_to_tokens;
+use crate::modifier::{LiteralFlags, Modifiers, PatternFlags, Radix, Trim};
 use crate::var;
 use crate::var::Var;
 use itertools::Itertools;
@@ -6,70 +7,258 @@ use proc_macro2::Span;
 use proc_macro2::{Ident, TokenStream};
 use quote::{ToTokens, TokenStreamExt};
 
+/// How much of the input a pattern has to match.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum Anchor {
+    /// The pattern must match the whole input.
+    Whole,
+    /// Any input left after the last literal is ignored.
+    Prefix,
+    /// Like `Prefix`, but the input left after the pattern is returned after the captures.
+    Advance,
+}
+
 #[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Debug)]
 pub enum Instruction {
     Lit(String),
+    /// A literal matched according to the pattern flags.
+    FlexLit(String, LiteralFlags),
     Parse(Var),
-    VecParse(Var, String, bool),
-    IterParse(Var, String, bool),
-    MultiParse(Var, String, u8, bool),
+    Modified(Var, Modifiers),
+    BackRef(Ident, usize),
+    /// The repetitions hold their separator, whether it can be repeated and the modifiers of each element.
+    VecParse(Var, String, bool, Modifiers),
+    IterParse(Var, String, bool, Modifiers),
+    MultiParse(Var, String, u8, bool, Modifiers),
+    /// Collects `key=value` entries into a map, holding the separator between entries and whether it can be repeated.
+    MapParse(Var, String, bool, Option<usize>),
+    /// Counts how many times the literal is repeated at the current position.
+    Count(Var, String),
+    /// Captures the text inside a balanced pair of brackets, holding the opening and closing bracket.
+    Balanced(Var, char, char),
+    /// Captures exactly one char at the current position.
+    SingleChar(Var),
+    /// Captures the rest of the current line, up to but not including the next `\n` and the `\r` before it.
+    Line(Var),
+    /// Captures a quoted string with backslash escapes, the following literal is only searched for after the quotes.
+    Quoted(Var),
+    /// Skips any whitespace at the current position, used at both ends of a pattern with the c flag.
+    SkipWhitespace,
+    /// The start and end of an optional group, `{[...]}`, or of a tuple group, `{(...)}`,
+    /// which holds whether it is a tuple group.
+    GroupStart(bool),
+    GroupEnd,
 }
 
 impl Instruction {
     pub(crate) fn get_var(&self) -> Option<&Var> {
         match self {
-            Instruction::Lit(_) => None,
+            Instruction::Lit(_)
+            | Instruction::FlexLit(..)
+            | Instruction::BackRef(..)
+            | Instruction::SkipWhitespace
+            | Instruction::GroupStart(_)
+            | Instruction::GroupEnd => None,
             Instruction::Parse(v)
+            | Instruction::Modified(v, _)
             | Instruction::VecParse(v, ..)
             | Instruction::IterParse(v, ..)
-            | Instruction::MultiParse(v, ..) => Some(v),
+            | Instruction::MultiParse(v, ..)
+            | Instruction::MapParse(v, ..)
+            | Instruction::Count(v, _)
+            | Instruction::Balanced(v, ..)
+            | Instruction::SingleChar(v)
+            | Instruction::Line(v)
+            | Instruction::Quoted(v) => Some(v),
+        }
+    }
+
+    /// The text a capture is parsed from when its optional group is absent, given by its default modifier.
+    pub(crate) fn default_value(&self) -> Option<&str> {
+        match self {
+            Instruction::Modified(_, modifiers) => modifiers.default.as_deref(),
+            _ => None,
         }
     }
 
     pub(crate) fn gen_iter(&self) -> Option<TokenStream> {
         match self {
-            Instruction::VecParse(_, sep, _)
-            | Instruction::IterParse(_, sep, _)
-            | Instruction::MultiParse(_, sep, ..)
+            // Every element is a row that is split again into an array, the error of a row is given its index.
+            Instruction::VecParse(_, sep, is_multi, modifiers)
+            | Instruction::MultiParse(_, sep, _, is_multi, modifiers)
+                if modifiers.row.is_some() =>
+            {
+                let (row_sep, columns, is_multi_row) = modifiers.row.clone().unwrap();
+                // The max modifier limits the number of rows, the row itself is limited by its columns.
+                let rows = Instruction::IterParse(
+                    Var::Implied,
+                    sep.clone(),
+                    *is_multi,
+                    Modifiers {
+                        max: modifiers.max,
+                        ..Modifiers::default()
+                    },
+                )
+                .gen_iter()?;
+                let row = Instruction::MultiParse(
+                    Var::Implied,
+                    row_sep,
+                    columns,
+                    is_multi_row,
+                    Modifiers {
+                        row: None,
+                        max: None,
+                        ..modifiers.clone()
+                    },
+                );
+                let array = gen_array(quote!(__prse_row), row.gen_iter()?, columns);
+                Some(quote! {{
+                    let __prse_rows: &str = __prse_parse;
+                    #rows.enumerate().map(move |(__prse_index, __prse_slice)| {
+                        let __prse_parse: &str = __prse_slice?;
+                        let __prse_row = (|| {
+                            #array
+                            ::core::result::Result::<_, ::prse::ParseError>::Ok(__prse_row)
+                        })();
+                        ::prse::__private::add_row_context(__prse_row, __prse_parse, __prse_rows, __prse_index)
+                    })
+                }})
+            }
+            Instruction::VecParse(_, sep, _, modifiers)
+            | Instruction::MultiParse(_, sep, .., modifiers)
+                if modifiers.doubled =>
+            {
+                let parse = modifiers.gen_parse();
+                let max = modifiers.max.unwrap_or(usize::MAX);
+                let sep = literal_separator(sep);
+                Some(quote! {{
+                    let __prse_multi = __prse_parse;
+                    ::prse::__private::split_doubled(__prse_multi, #sep, #max)?
+                        .into_iter()
+                        .map(move |__prse_element| {
+                            let __prse_parse: &str = &__prse_element;
+                            ::prse::__private::add_err_multi_context(#parse, __prse_multi, __prse_parse)
+                        })
+                }})
+            }
+            Instruction::VecParse(_, sep, _, modifiers)
+            | Instruction::IterParse(_, sep, _, modifiers)
+            | Instruction::MultiParse(_, sep, .., modifiers)
                 if sep.is_empty() =>
             {
-                Some(quote! {
-                    ::prse::ParseChars::new(__prse_parse)
-                })
+                let iter = quote!(::prse::ParseChars::new(__prse_parse));
+                Some(with_modifiers(iter, modifiers))
+            }
+            Instruction::VecParse(_, sep, _, modifiers)
+            | Instruction::IterParse(_, sep, _, modifiers)
+            | Instruction::MultiParse(_, sep, .., modifiers)
+                if separator_class(sep).is_some() =>
+            {
+                let class = separator_class(sep);
+                let iter = quote!(::prse::ParseIter::new_class(__prse_parse, #class));
+                Some(with_modifiers(iter, modifiers))
+            }
+            Instruction::VecParse(_, sep, is_multi, modifiers)
+            | Instruction::IterParse(_, sep, is_multi, modifiers)
+            | Instruction::MultiParse(_, sep, _, is_multi, modifiers)
+                if separator_set(sep).is_some() =>
+            {
+                let set = separator_set(sep);
+                let iter = quote!(::prse::ParseIter::new_set(__prse_parse, #set, #is_multi));
+                Some(with_modifiers(iter, modifiers))
+            }
+            Instruction::VecParse(_, sep, is_multi, modifiers)
+            | Instruction::IterParse(_, sep, is_multi, modifiers)
+            | Instruction::MultiParse(_, sep, _, is_multi, modifiers) => {
+                let sep = literal_separator(sep);
+                let iter = quote!(::prse::ParseIter::new(__prse_parse, #sep, #is_multi));
+                Some(with_modifiers(iter, modifiers))
             }
-            Instruction::VecParse(_, sep, is_multi)
-            | Instruction::IterParse(_, sep, is_multi)
-            | Instruction::MultiParse(_, sep, _, is_multi) => Some(quote! {
-                ::prse::ParseIter::new(__prse_parse, #sep, #is_multi)
-            }),
             _ => None,
         }
     }
 }
 
+/// Returns the chars of a separator class such as `[, ]+`, which matches any run of those chars.
+pub(crate) fn separator_class(sep: &str) -> Option<&str> {
+    sep.strip_prefix('[')?
+        .strip_suffix("]+")
+        .filter(|class| !class.is_empty())
+}
+
+/// Returns the chars of a separator set such as `[,;]`, which matches any one of those chars.
+pub(crate) fn separator_set(sep: &str) -> Option<&str> {
+    sep.strip_prefix('[')?
+        .strip_suffix(']')
+        .filter(|set| !set.is_empty())
+}
+
+/// Returns a separator that is matched as is, where `\[` stands for a `[` so that a separator starting
+/// with a bracket is not mistaken for a separator class, a set or a row.
+pub(crate) fn literal_separator(sep: &str) -> String {
+    sep.replace(r"\[", "[")
+}
+
+/// The number of optional groups a pattern can contain, as every combination of them is generated.
+const MAX_GROUPS: usize = 4;
+
 #[derive(Ord, PartialOrd, Eq, PartialEq, Debug, Clone)]
 pub(crate) struct Instructions(pub Vec<Instruction>);
 
 impl Instructions {
     pub fn new(input: &str, input_span: Span) -> syn::Result<Instructions> {
+        let (flags, input) = PatternFlags::parse(input, input_span)?;
         let mut i = input.chars().multipeek();
         let mut var_mode = false;
+        // Whether the current group is a tuple group, if inside one.
+        let mut in_group = None;
         let mut val = String::new();
         let mut instructions = vec![];
         while let Some(c) = i.next() {
             match (c, var_mode) {
-                ('{', false) => {
+                ('{', false) => match i.peek() {
                     // Character has been escaped.
-                    if let Some('{') = i.peek() {
+                    Some('{') => {
                         val.push(c);
                         i.next().unwrap();
-                    } else {
+                    }
+                    Some(&open @ ('[' | '(')) => {
+                        i.next().unwrap();
+                        if in_group.is_some() {
+                            return Err(syn::Error::new(
+                                input_span,
+                                "Optional groups cannot be nested.",
+                            ));
+                        }
+                        if !val.is_empty() {
+                            instructions.push(Instruction::Lit(val));
+                        }
+                        val = String::new();
+                        instructions.push(Instruction::GroupStart(open == '('));
+                        in_group = Some(open == '(');
+                    }
+                    _ => {
                         if !val.is_empty() {
                             instructions.push(Instruction::Lit(val));
                         }
                         val = String::new();
                         var_mode = true;
                     }
+                },
+                (']' | ')', false) if in_group == Some(c == ')') && i.peek() == Some(&'}') => {
+                    i.next().unwrap();
+                    if !val.is_empty() {
+                        instructions.push(Instruction::Lit(val));
+                    }
+                    val = String::new();
+                    if matches!(instructions.last(), Some(Instruction::GroupStart(_))) {
+                        return Err(syn::Error::new(
+                            input_span,
+                            "Optional groups cannot be empty.",
+                        ));
+                    }
+                    instructions.push(Instruction::GroupEnd);
+                    in_group = None;
                 }
                 ('}', false) => {
                     if let Some('}') = i.peek() {
@@ -101,13 +290,29 @@ impl Instructions {
                             continue;
                         }
                     }
-                    if !matches!(instructions.last(), Some(Instruction::Lit(_)) | None) {
+                    // Captures next to an optional group are checked once the groups are expanded,
+                    // and counts, brackets and single chars end on their own so they can be directly followed by another capture.
+                    // Brackets also start at their opening bracket, so they can directly follow a capture.
+                    let instruction = var::parse_var(val, input_span)?;
+                    if !matches!(instruction, Instruction::Balanced(..))
+                        && !matches!(
+                            instructions.last(),
+                            Some(
+                                Instruction::Lit(_)
+                                    | Instruction::Count(..)
+                                    | Instruction::Balanced(..)
+                                    | Instruction::SingleChar(_)
+                                    | Instruction::GroupStart(_)
+                                    | Instruction::GroupEnd
+                            ) | None
+                        )
+                    {
                         return Err(syn::Error::new(
                             input_span,
                             "Cannot have two captures without a string in between.",
                         ));
                     }
-                    instructions.push(var::parse_var(val, input_span)?);
+                    instructions.push(instruction);
                     val = String::new();
                     var_mode = false;
                 }
@@ -120,11 +325,592 @@ impl Instructions {
                 "Expected to find } bracket. Consider adding a } bracket to close the open { bracket.",
             ));
         }
+        if let Some(tuple) = in_group {
+            return Err(syn::Error::new(
+                input_span,
+                if tuple {
+                    "Expected to find )} to close the tuple group."
+                } else {
+                    "Expected to find ]} to close the optional group."
+                },
+            ));
+        }
         if !val.is_empty() {
             instructions.push(Instruction::Lit(val));
         }
+        if flags.columns {
+            instructions.insert(0, Instruction::SkipWhitespace);
+            instructions.push(Instruction::SkipWhitespace);
+        }
+
+        let instructions = Self::resolve_back_references(instructions, input_span)?;
+        let instructions = Self::apply_flags(instructions, flags);
+        let instructions = Self::validate_instructions(instructions, input_span)?;
+        instructions.validate_groups(input_span)?;
+        instructions.validate_defaults(input_span)?;
+        Ok(instructions)
+    }
+
+    /// Turns the single repetition of a `parse_for_each!` or `parse_padded!` pattern into an iterator repetition,
+    /// so that no collection is built.
+    pub fn into_for_each(
+        mut self,
+        input_span: Span,
+        macro_name: &str,
+    ) -> syn::Result<Instructions> {
+        let mut captures = self.0.iter_mut().filter(|i| {
+            i.get_var().is_some()
+                || matches!(i, Instruction::GroupStart(_) | Instruction::BackRef(..))
+        });
+        let repetition = match (captures.next(), captures.next()) {
+            (
+                Some(
+                    i @ (Instruction::VecParse(Var::Implied, ..)
+                    | Instruction::IterParse(Var::Implied, ..)),
+                ),
+                None,
+            ) => i,
+            _ => {
+                return Err(syn::Error::new(
+                    input_span,
+                    format!("{macro_name} expects a pattern with a single unnamed Vec or Iterator repetition, such as {{:,:}}."),
+                ));
+            }
+        };
+        if let Instruction::VecParse(var, sep, is_multi, modifiers) = repetition {
+            if modifiers.row.is_some() {
+                return Err(syn::Error::new(
+                    input_span,
+                    format!("rows cannot be used with {macro_name}."),
+                ));
+            }
+            if modifiers.doubled {
+                return Err(syn::Error::new(
+                    input_span,
+                    format!("the dd modifier cannot be used with {macro_name}."),
+                ));
+            }
+            *repetition = Instruction::IterParse(
+                var.clone(),
+                core::mem::take(sep),
+                *is_multi,
+                modifiers.clone(),
+            );
+        }
+        Ok(self)
+    }
+
+    /// The identifiers that the captures inside an optional group are returned as, which are an `Option`
+    /// unless the capture has a default.
+    pub fn optional_idents(&self) -> Vec<Ident> {
+        self.optional_indices()
+            .into_iter()
+            .filter(|idx| {
+                self.0[*idx].get_var().is_some() && self.0[*idx].default_value().is_none()
+            })
+            .map(|idx| format_ident!("__prse_{idx}"))
+            .collect()
+    }
+
+    /// The indices of the instructions that are inside an optional group.
+    pub fn optional_indices(&self) -> Vec<usize> {
+        let mut in_group = false;
+        let mut indices = vec![];
+        for (idx, i) in self.0.iter().enumerate() {
+            match i {
+                Instruction::GroupStart(_) => in_group = true,
+                Instruction::GroupEnd => in_group = false,
+                _ if in_group => indices.push(idx),
+                _ => {}
+            }
+        }
+        indices
+    }
+
+    /// Expands the optional groups into the instructions of every combination of present and absent groups,
+    /// along with the indices of the captures each combination contains.
+    ///
+    /// The combinations are ordered so that the earlier groups are preferred to be present.
+    fn alternatives(&self) -> Vec<(Instructions, Vec<usize>)> {
+        let groups = self
+            .0
+            .iter()
+            .filter(|i| matches!(i, Instruction::GroupStart(_)))
+            .count();
+        (0..1_usize << groups)
+            .map(|absent| {
+                let mut group = 0;
+                let mut present = true;
+                let mut instructions: Vec<Instruction> = vec![];
+                let mut vars = vec![];
+                let mut local_indices = vec![None; self.0.len()];
+                for (idx, i) in self.0.iter().enumerate() {
+                    match i {
+                        Instruction::GroupStart(_) => {
+                            present = absent & (1 << (groups - 1 - group)) == 0;
+                            group += 1;
+                        }
+                        Instruction::GroupEnd => present = true,
+                        _ if !present => {}
+                        Instruction::Lit(l) if matches!(instructions.last(), Some(Instruction::Lit(_))) => {
+                            if let Some(Instruction::Lit(last)) = instructions.last_mut() {
+                                last.push_str(l);
+                            }
+                        }
+                        Instruction::FlexLit(l, flags) if matches!(instructions.last(), Some(Instruction::FlexLit(_, f)) if f == flags) => {
+                            if let Some(Instruction::FlexLit(last, _)) = instructions.last_mut() {
+                                last.push_str(l);
+                            }
+                        }
+                        Instruction::BackRef(ident, first_idx) => {
+                            let first_idx = local_indices[*first_idx]
+                                .expect("back-references cannot be used with optional groups");
+                            instructions.push(Instruction::BackRef(ident.clone(), first_idx));
+                        }
+                        i => {
+                            if i.get_var().is_some() {
+                                vars.push(idx);
+                            }
+                            local_indices[idx] = Some(instructions.len());
+                            instructions.push(i.clone());
+                        }
+                    }
+                }
+                (Instructions(instructions), vars)
+            })
+            .collect()
+    }
+
+    /// Checks that the default modifier is only used on the captures of optional groups, as those are the only
+    /// captures that can be absent.
+    fn validate_defaults(&self, input_span: Span) -> syn::Result<()> {
+        let mut group = None;
+        for i in &self.0 {
+            match (i, group) {
+                (Instruction::GroupStart(tuple), _) => group = Some(*tuple),
+                (Instruction::GroupEnd, _) => group = None,
+                (i, _) if i.default_value().is_none() => {}
+                (_, Some(false)) => {}
+                (_, Some(true)) => {
+                    return Err(syn::Error::new(
+                        input_span,
+                        "the default modifier cannot be used in a tuple group, use an optional group instead.",
+                    ));
+                }
+                (_, None) => {
+                    return Err(syn::Error::new(
+                        input_span,
+                        "the default modifier can only be used on captures in an optional group.",
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether any capture has a default modifier.
+    pub fn has_defaults(&self) -> bool {
+        self.0.iter().any(|i| i.default_value().is_some())
+    }
+
+    fn validate_groups(&self, input_span: Span) -> syn::Result<()> {
+        if !self
+            .0
+            .iter()
+            .any(|i| matches!(i, Instruction::GroupStart(_)))
+        {
+            return Ok(());
+        }
+        let groups = self
+            .0
+            .iter()
+            .filter(|i| matches!(i, Instruction::GroupStart(_)))
+            .count();
+        if groups > MAX_GROUPS {
+            return Err(syn::Error::new(
+                input_span,
+                format!("A pattern can contain at most {MAX_GROUPS} optional groups."),
+            ));
+        }
+        let mut tuple = None;
+        for i in &self.0 {
+            match i {
+                Instruction::GroupStart(true) => tuple = Some(0),
+                Instruction::GroupEnd if tuple == Some(0) => {
+                    return Err(syn::Error::new(
+                        input_span,
+                        "Tuple groups must contain a capture, consider using an optional group instead.",
+                    ));
+                }
+                Instruction::GroupEnd => tuple = None,
+                i => match (i.get_var(), &mut tuple) {
+                    (Some(Var::Implied), Some(captures)) => *captures += 1,
+                    (Some(_), Some(_)) => {
+                        return Err(syn::Error::new(
+                            input_span,
+                            "Tuple groups can only contain unnamed captures.",
+                        ));
+                    }
+                    _ => {}
+                },
+            }
+        }
+        let optional = self.optional_indices();
+        if self.0.iter().enumerate().any(|(idx, i)| {
+            matches!(i, Instruction::BackRef(_, first_idx) if optional.contains(&idx) || optional.contains(first_idx))
+        }) {
+            return Err(syn::Error::new(
+                input_span,
+                "Back-references cannot be used with captures in optional groups.",
+            ));
+        }
+        for (instructions, _) in self.alternatives() {
+            if instructions.0.windows(2).any(|w| {
+                w[0].get_var().is_some()
+                    && !matches!(
+                        w[0],
+                        Instruction::Count(..)
+                            | Instruction::Balanced(..)
+                            | Instruction::SingleChar(_)
+                    )
+                    && w[1].get_var().is_some()
+                    && !matches!(w[1], Instruction::Balanced(..))
+            }) {
+                return Err(syn::Error::new(
+                    input_span,
+                    "Cannot have two captures without a string in between.",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Generates an expression that parses `input` into a `Result` containing a tuple of every capture.
+    ///
+    /// Every combination of optional groups gets its own function, which are tried in turn until one matches,
+    /// the captures in the optional groups are returned as an `Option`.
+    /// If none of them match, the error of the combination with every group present is returned.
+    pub fn gen_parse(&self, input: TokenStream, anchor: Anchor) -> TokenStream {
+        self.gen_parse_with(input, anchor, false)
+    }
+
+    /// Like [`gen_parse`](Self::gen_parse) for the whole input, but every capture is returned
+    /// together with its `Location` in the input.
+    pub fn gen_parse_located(&self, input: TokenStream) -> TokenStream {
+        self.gen_parse_with(input, Anchor::Whole, true)
+    }
+
+    fn gen_parse_with(&self, input: TokenStream, anchor: Anchor, located: bool) -> TokenStream {
+        let single_parse = match anchor {
+            Anchor::Advance => None,
+            _ if located => None,
+            _ => self.gen_single_parse(),
+        };
+        if let Some(parse) = single_parse {
+            return quote! {
+                {
+                    let __prse_parse: &str = #input;
+                    #parse
+                }
+            };
+        }
+
+        let alternatives = self.alternatives();
+        let optional = self.optional_indices();
+        let all_vars: Vec<usize> = self
+            .0
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| i.get_var().is_some())
+            .map(|(idx, _)| idx)
+            .collect();
+
+        let mut functions = vec![];
+        let mut result = None;
+        for (n, (instructions, vars)) in alternatives.iter().enumerate().rev() {
+            let func_name = if alternatives.len() == 1 {
+                format_ident!("__prse_func")
+            } else {
+                format_ident!("__prse_func_{n}")
+            };
+            let mut body = quote! {
+                let mut __prse_parse: &str;
+                let mut __prse_remaining = __prse_input;
+            };
+            instructions.gen_body(&mut body, anchor, located);
+            functions.push(instructions.gen_function(body, func_name.clone(), anchor, located));
+
+            if alternatives.len() == 1 {
+                result = Some(quote!(#func_name (#input)));
+                break;
+            }
+
+            let rest = (anchor == Anchor::Advance).then(|| format_ident!("__prse_rest"));
+            let idents = vars
+                .iter()
+                .map(|idx| format_ident!("__prse_{idx}"))
+                .chain(rest.clone());
+            // The default of an absent capture is only parsed once its combination matched.
+            let mut any_default = false;
+            let values: Vec<_> = all_vars
+                .iter()
+                .map(|idx| {
+                    let ident = format_ident!("__prse_{idx}");
+                    let instruction = &self.0[*idx];
+                    match (vars.contains(idx), instruction.default_value()) {
+                        (true, None) if optional.contains(idx) => {
+                            quote!(::core::option::Option::Some(#ident))
+                        }
+                        (true, _) => ident.to_token_stream(),
+                        (false, Some(default)) => {
+                            any_default = true;
+                            gen_default(instruction, default)
+                        }
+                        (false, None) => quote!(::core::option::Option::None),
+                    }
+                })
+                .chain(rest.map(|rest| rest.to_token_stream()))
+                .collect();
+            let values = if any_default {
+                quote! {
+                    (|| ::core::result::Result::<_, ::prse::ParseError>::Ok(( #(#values),* )))()
+                }
+            } else {
+                quote!(Ok(( #(#values),* )))
+            };
+            let error = match (n, result) {
+                (0, None) => quote!(Err(__prse_error) => Err(__prse_error)),
+                (0, Some(next)) => quote!(Err(__prse_error) => #next),
+                (_, None) => quote!(Err(_) => Err(__prse_error)),
+                (_, Some(next)) => quote!(Err(_) => #next),
+            };
+            result = Some(quote! {
+                match #func_name (#input) {
+                    Ok(( #(#idents),* )) => #values,
+                    #error,
+                }
+            });
+        }
+
+        let result = self.gen_mapped(result, anchor, located);
+        quote! {
+            {
+                #(#functions)*
+
+                #result
+            }
+        }
+    }
+
+    /// Replaces the key index returned for every mapped capture in `result` by the value of that key.
+    fn gen_mapped(
+        &self,
+        result: Option<TokenStream>,
+        anchor: Anchor,
+        located: bool,
+    ) -> Option<TokenStream> {
+        let optional = self.optional_indices();
+        let mut any_mapped = false;
+        let (idents, values): (Vec<_>, Vec<_>) = self
+            .0
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| i.get_var().is_some())
+            .map(|(idx, i)| {
+                let ident = format_ident!("__prse_{idx}");
+                let Instruction::Modified(
+                    _,
+                    Modifiers {
+                        mapping: Some(mapping),
+                        ..
+                    },
+                ) = i
+                else {
+                    return (ident.clone(), ident.to_token_stream());
+                };
+                any_mapped = true;
+                let indices = 0..mapping.len();
+                let values = mapping.iter().map(|(_, value)| {
+                    value
+                        .parse::<TokenStream>()
+                        .expect("the values are checked when parsing the modifier")
+                });
+                let value = quote! {
+                    match __prse_index {
+                        #(#indices => #values,)*
+                        _ => ::core::unreachable!(),
+                    }
+                };
+                let map = if located {
+                    quote!(|(__prse_index, __prse_location)| (#value, __prse_location))
+                } else {
+                    quote!(|__prse_index| #value)
+                };
+                let value = if optional.contains(&idx) && i.default_value().is_none() {
+                    quote!(#ident.map(#map))
+                } else {
+                    quote!((#map)(#ident))
+                };
+                (ident, value)
+            })
+            .chain((anchor == Anchor::Advance).then(|| {
+                let rest = format_ident!("__prse_rest");
+                (rest.clone(), rest.to_token_stream())
+            }))
+            .unzip();
+        if !any_mapped {
+            return result;
+        }
+        Some(quote! {
+            (#result).map(|( #(#idents),* )| ( #(#values),* ))
+        })
+    }
+
+    /// Generates the parse of a pattern that is a single plain capture without any literals, such as `{}` or `{:x}`.
+    ///
+    /// The whole input is the capture, so it is parsed directly without generating a function to
+    /// match the pattern, while keeping the same errors.
+    fn gen_single_parse(&self) -> Option<TokenStream> {
+        match self.0.as_slice() {
+            [Instruction::Parse(v)] => {
+                let field_name = v.field_name();
+                Some(quote! {
+                    ::prse::__private::try_parse_context(__prse_parse, __prse_parse, #field_name)
+                })
+            }
+            [Instruction::Modified(v, modifiers)] if modifiers.mapping.is_none() => {
+                let field_name = v.field_name();
+                let parse = modifiers.gen_parse();
+                Some(quote! {
+                    ::prse::__private::add_context(#parse, __prse_parse, __prse_parse, #field_name)
+                })
+            }
+            _ => None,
+        }
+    }
 
-        Self::validate_instructions(instructions, input_span)
+    /// Parses every occurrence of `ident` separately instead of treating the later ones as back-references.
+    pub fn capture_all(&mut self, ident: &Ident) {
+        for i in self.0.iter_mut() {
+            if matches!(i, Instruction::BackRef(back_ref, _) if back_ref == ident) {
+                *i = Instruction::Parse(Var::Ident(ident.clone()));
+            }
+        }
+    }
+
+    /// Parses every capture of `field` with the given radix, where a position also matches the implied capture at that index.
+    ///
+    /// Returns an error message if the field is captured in a way that cannot be given a radix.
+    pub fn set_radix(&mut self, field: &Var, radix: Radix) -> Result<(), &'static str> {
+        let mut implied = 0;
+        for i in self.0.iter_mut() {
+            let is_field = match (i.get_var(), field) {
+                (Some(Var::Implied), Var::Position(pos)) => {
+                    implied += 1;
+                    implied - 1 == *pos as usize
+                }
+                (Some(var), field) => var == field,
+                (None, _) => false,
+            };
+            if !is_field {
+                continue;
+            }
+            match i {
+                Instruction::Parse(v) => {
+                    let modifiers = Modifiers {
+                        radix: Some(radix),
+                        ..Modifiers::default()
+                    };
+                    *i = Instruction::Modified(v.clone(), modifiers);
+                }
+                Instruction::Modified(_, modifiers)
+                | Instruction::VecParse(_, _, _, modifiers)
+                | Instruction::IterParse(_, _, _, modifiers)
+                | Instruction::MultiParse(.., modifiers) => {
+                    if modifiers.radix.is_some() {
+                        return Err("has both a radix attribute and a radix modifier.");
+                    }
+                    if modifiers.length.is_some() {
+                        return Err("cannot have a radix attribute as it is captured by its length.");
+                    }
+                    modifiers.radix = Some(radix);
+                }
+                _ => {
+                    return Err("can only have a radix attribute if it is captured by plain captures or repetitions.")
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies the pattern flags to every literal and plain capture.
+    ///
+    /// The trim flag does not override a capture that has its own trim modifier,
+    /// and char repetitions and iterators are never trimmed.
+    fn apply_flags(instructions: Vec<Instruction>, flags: PatternFlags) -> Vec<Instruction> {
+        instructions
+            .into_iter()
+            .map(|i| match i {
+                Instruction::Lit(l) if flags.literals != LiteralFlags::default() => {
+                    Instruction::FlexLit(l, flags.literals)
+                }
+                Instruction::Parse(v) if flags.trim => Instruction::Modified(
+                    v,
+                    Modifiers {
+                        trim: Some(Trim::Both),
+                        ..Modifiers::default()
+                    },
+                ),
+                Instruction::Modified(v, mut modifiers) if flags.trim => {
+                    modifiers.trim.get_or_insert(Trim::Both);
+                    Instruction::Modified(v, modifiers)
+                }
+                Instruction::VecParse(v, sep, is_multi, mut modifiers)
+                    if flags.trim && !element_sep(&sep, &modifiers).is_empty() =>
+                {
+                    modifiers.trim.get_or_insert(Trim::Both);
+                    Instruction::VecParse(v, sep, is_multi, modifiers)
+                }
+                Instruction::MultiParse(v, sep, count, is_multi, mut modifiers)
+                    if flags.trim && !element_sep(&sep, &modifiers).is_empty() =>
+                {
+                    modifiers.trim.get_or_insert(Trim::Both);
+                    Instruction::MultiParse(v, sep, count, is_multi, modifiers)
+                }
+                i => i,
+            })
+            .collect()
+    }
+
+    /// Turns every repeated occurrence of a named capture into a back-reference to its first occurrence.
+    fn resolve_back_references(
+        instructions: Vec<Instruction>,
+        input_span: Span,
+    ) -> syn::Result<Vec<Instruction>> {
+        let mut first_seen: Vec<(Ident, usize, bool)> = vec![];
+        instructions
+            .into_iter()
+            .enumerate()
+            .map(|(idx, i)| {
+                let Some(Var::Ident(ident)) = i.get_var() else {
+                    return Ok(i);
+                };
+                let is_plain = matches!(i, Instruction::Parse(_));
+                match first_seen.iter().find(|(seen, ..)| seen == ident) {
+                    Some((_, first_idx, true)) if is_plain => {
+                        Ok(Instruction::BackRef(ident.clone(), *first_idx))
+                    }
+                    Some(_) => Err(syn::Error::new(
+                        input_span,
+                        format!("Back-references can only be used with plain captures, {ident} is captured more than once."),
+                    )),
+                    None => {
+                        first_seen.push((ident.clone(), idx, is_plain));
+                        Ok(i)
+                    }
+                }
+            })
+            .collect()
     }
 
     fn validate_instructions(
@@ -164,42 +950,110 @@ impl Instructions {
         }
     }
 
-    pub fn gen_function(&self, body: TokenStream, func_name: Ident) -> TokenStream {
+    pub fn gen_function(
+        &self,
+        body: TokenStream,
+        func_name: Ident,
+        anchor: Anchor,
+        located: bool,
+    ) -> TokenStream {
         let mut return_types = vec![];
         let mut generics = vec![];
+        let mut bounds = vec![];
         for (idx, i) in self
             .0
             .iter()
             .enumerate()
-            .filter(|(_, i)| !matches!(i, Instruction::Lit(_)))
+            .filter(|(_, i)| i.get_var().is_some())
         {
+            if let Instruction::Count(..) = i {
+                return_types.push(quote!(usize));
+                continue;
+            }
+            if let Instruction::Modified(_, modifiers) = i {
+                // Mapped captures return the index of their key, which is mapped once the pattern matched.
+                if modifiers.length.is_some() || modifiers.mapping.is_some() {
+                    return_types.push(quote!(usize));
+                    continue;
+                }
+            }
             let type_ident = format_ident!("T{idx}");
+            if let Instruction::MapParse(..) = i {
+                let key_ident = format_ident!("K{idx}");
+                let value_ident = format_ident!("V{idx}");
+                bounds.push(quote!(::core::iter::FromIterator<(#key_ident, #value_ident)>));
+                bounds.push(quote!(Parse<'a>));
+                bounds.push(quote!(Parse<'a>));
+                return_types.push(type_ident.to_token_stream());
+                generics.extend([type_ident, key_ident, value_ident]);
+                continue;
+            }
+            bounds.push(match i {
+                Instruction::Modified(_, modifiers) => modifiers.bound(),
+                // Doubled separators do not use the iterators.
+                Instruction::VecParse(.., modifiers) | Instruction::MultiParse(.., modifiers)
+                    if modifiers.doubled =>
+                {
+                    modifiers.bound()
+                }
+                // The iterators always require the type to implement Parse.
+                Instruction::VecParse(.., modifiers)
+                | Instruction::IterParse(.., modifiers)
+                | Instruction::MultiParse(.., modifiers)
+                    if modifiers.radix.is_some() || modifiers.grouped =>
+                {
+                    let bound = modifiers.bound();
+                    quote!(Parse<'a> + #bound)
+                }
+                // Un-escaped strings are parsed from a temporary string, so they cannot borrow from the input.
+                Instruction::Quoted(_) => quote!(for<'b> Parse<'b>),
+                _ => quote!(Parse<'a>),
+            });
             return_types.push(match i {
-                Instruction::Parse(_) => type_ident.to_token_stream(),
-                Instruction::VecParse(..) => {
+                Instruction::Parse(_)
+                | Instruction::Modified(..)
+                | Instruction::Balanced(..)
+                | Instruction::SingleChar(_)
+                | Instruction::Line(_)
+                | Instruction::Quoted(_) => type_ident.to_token_stream(),
+                Instruction::VecParse(.., modifiers) => {
+                    let element = row_type(&type_ident, modifiers);
                     if cfg!(feature = "std") {
-                        quote!(::std::vec::Vec<#type_ident>)
+                        quote!(::std::vec::Vec<#element>)
                     } else {
-                        quote!(::alloc::vec::Vec<#type_ident>)
+                        quote!(::alloc::vec::Vec<#element>)
                     }
                 }
-                Instruction::IterParse(_, sep, _) if sep.is_empty() => quote! {
+                // The iterator of a parse_for_each! or parse_padded! repetition only needs to be iterated.
+                Instruction::IterParse(.., modifiers) if has_element_modifiers(modifiers) => quote! {
+                   impl ::core::iter::Iterator<Item = ::core::result::Result<#type_ident, ::prse::ParseError>> + 'a
+                },
+                Instruction::IterParse(_, sep, ..) if sep.is_empty() => quote! {
                    ::prse::ParseChars<'a, #type_ident>
                 },
                 Instruction::IterParse(..) => quote! {
                    ::prse::ParseIter<'a, #type_ident>
                 },
-                Instruction::MultiParse(_, _, count, _) => {
+                Instruction::MultiParse(_, _, count, .., modifiers) => {
                     let count = *count as usize;
-                    quote! ([ #type_ident ; #count])
+                    let element = row_type(&type_ident, modifiers);
+                    quote! ([ #element ; #count])
                 }
                 _ => unreachable!(),
             });
             generics.push(type_ident);
         }
+        if located {
+            for return_type in return_types.iter_mut() {
+                *return_type = quote!((#return_type, ::prse::Location));
+            }
+        }
+        if anchor == Anchor::Advance {
+            return_types.push(quote!(&'a str));
+        }
 
         quote! {
-            fn #func_name <'a, #(#generics: Parse<'a>),* >(
+            fn #func_name <'a, #(#generics: #bounds),* >(
                 __prse_input: &'a str,
             ) -> ::core::result::Result<( #(#return_types),* ), ::prse::ParseError> {
                 #body
@@ -207,40 +1061,171 @@ impl Instructions {
         }
     }
 
-    pub fn gen_body(&self, result: &mut TokenStream) {
+    /// Generates the body of the parsing function.
+    ///
+    /// Unless the whole input must match, any input left over after the last literal is ignored
+    /// instead of returning an error, and it is returned after the captures when advancing.
+    /// When `located`, every capture is paired with the location of the slice it was parsed from.
+    pub fn gen_body(&self, result: &mut TokenStream, anchor: Anchor, located: bool) {
         let mut store_token = None;
+        // Whether the stored capture is quoted, so the next literal is searched for after its quotes.
+        let mut quoted = false;
         let alloc_crate: TokenStream = if cfg!(feature = "std") {
             quote!(std)
         } else {
             quote!(alloc)
         };
 
+        let back_referenced: Vec<usize> = self
+            .0
+            .iter()
+            .filter_map(|i| match i {
+                Instruction::BackRef(_, first_idx) => Some(*first_idx),
+                _ => None,
+            })
+            .collect();
+
         for (idx, i) in self.0.iter().enumerate() {
             let var = format_ident!("__prse_{idx}");
+            let locate = |slice: TokenStream| {
+                located.then(
+                    || quote!(let #var = (#var, ::prse::__private::locate(__prse_input, #slice));),
+                )
+            };
             match i {
-                Instruction::Lit(l_string) => {
-                    let l_string = string_to_tokens(l_string);
+                Instruction::Lit(literal) => {
+                    let strip_cr = strip_cr(literal);
+                    let l_string = string_to_tokens(literal);
+                    let error = if cfg!(feature = "alloc") {
+                        quote!(::prse::__private::literal_error(#l_string, __prse_remaining))
+                    } else {
+                        quote!(::prse::ParseError::Literal)
+                    };
 
-                    result.append_all(if cfg!(feature = "alloc") {
+                    // Any text before the pattern is skipped, but a literal that follows something other
+                    // than a capture (such as a count) must be found right where it ends.
+                    result.append_all(if quoted {
+                        quote! {
+                            (__prse_parse, __prse_remaining) = ::prse::__private::split_quoted(__prse_remaining, #literal, false, false)
+                                .ok_or_else(|| #error)?;
+                            #strip_cr
+                        }
+                    } else if store_token.is_some() || idx == 0 {
                         quote! {
                             (__prse_parse, __prse_remaining) = __prse_remaining.split_once(#l_string)
-                                .ok_or_else(|| ::prse::ParseError::Literal {expected: (#l_string).into(), found: __prse_remaining.into()})?;
+                                .ok_or_else(|| #error)?;
+                            #strip_cr
                         }
                     } else {
                         quote! {
-                            (__prse_parse, __prse_remaining) = __prse_remaining.split_once(#l_string)
-                                .ok_or_else(|| ::prse::ParseError::Literal)?;
+                            __prse_remaining = __prse_remaining.strip_prefix(#l_string)
+                                .ok_or_else(|| #error)?;
+                        }
+                    });
+
+                    if let Some(t) = store_token {
+                        store_token = None;
+                        quoted = false;
+                        result.append_all(t);
+                    }
+                }
+                Instruction::FlexLit(l_string, flags) => {
+                    let strip_cr = strip_cr(l_string);
+                    let error = if cfg!(feature = "alloc") {
+                        quote!(::prse::__private::literal_error(#l_string, __prse_remaining))
+                    } else {
+                        quote!(::prse::ParseError::Literal)
+                    };
+                    let LiteralFlags {
+                        case_insensitive,
+                        flexible_whitespace,
+                        right_greedy,
+                    } = flags;
+
+                    let split = if quoted {
+                        quote!(split_quoted)
+                    } else if *right_greedy {
+                        quote!(rsplit_literal)
+                    } else {
+                        quote!(split_literal)
+                    };
+                    result.append_all(if store_token.is_some() || idx == 0 {
+                        quote! {
+                            (__prse_parse, __prse_remaining) = ::prse::__private::#split(__prse_remaining, #l_string, #case_insensitive, #flexible_whitespace)
+                                .ok_or_else(|| #error)?;
+                            #strip_cr
+                        }
+                    } else {
+                        quote! {
+                            __prse_remaining = ::prse::__private::strip_literal(__prse_remaining, #l_string, #case_insensitive, #flexible_whitespace)
+                                .ok_or_else(|| #error)?;
                         }
                     });
 
                     if let Some(t) = store_token {
                         store_token = None;
+                        quoted = false;
                         result.append_all(t);
                     }
                 }
-                Instruction::Parse(_) => {
+                Instruction::Parse(v) => {
+                    let field_name = v.field_name();
+                    let slice = back_referenced.contains(&idx).then(|| {
+                        let slice = format_ident!("__prse_slice_{idx}");
+                        quote!(let #slice = __prse_parse;)
+                    });
+                    store_token = Some(quote! {
+                        #slice
+                        let #var = ::prse::__private::try_parse_context(__prse_parse, __prse_input, #field_name)?;
+                    });
+                }
+                Instruction::Modified(v, modifiers) => {
+                    let field_name = v.field_name();
+                    let slice = back_referenced.contains(&idx).then(|| {
+                        let slice = format_ident!("__prse_slice_{idx}");
+                        let trimmed = trim_slice(quote!(__prse_parse), modifiers.trim);
+                        quote!(let #slice = #trimmed;)
+                    });
+                    let parse = modifiers.gen_parse();
+                    store_token = Some(quote! {
+                        #slice
+                        let #var = ::prse::__private::add_context(#parse, __prse_parse, __prse_input, #field_name)?;
+                    });
+                }
+                Instruction::Quoted(v) => {
+                    let field_name = v.field_name();
+                    let slice = back_referenced.contains(&idx).then(|| {
+                        let slice = format_ident!("__prse_slice_{idx}");
+                        quote!(let #slice = __prse_parse;)
+                    });
+                    quoted = true;
                     store_token = Some(quote! {
-                        let #var = ::prse::__private::try_parse_context(__prse_parse, __prse_input)?;
+                        #slice
+                        let __prse_unquoted = ::prse::__private::unquote(__prse_parse)?;
+                        let #var = ::prse::__private::try_parse_context(&__prse_unquoted, __prse_input, #field_name)?;
+                    });
+                }
+                Instruction::BackRef(_, first_idx) => {
+                    let slice = format_ident!("__prse_slice_{first_idx}");
+                    // The back-reference is trimmed like the capture it refers to, such as with the t flag.
+                    let trim = match &self.0[*first_idx] {
+                        Instruction::Modified(_, modifiers) => modifiers.trim,
+                        _ => None,
+                    };
+                    let found = trim_slice(quote!(__prse_parse), trim);
+                    store_token = Some(if cfg!(feature = "alloc") {
+                        quote! {
+                            let __prse_found: &str = #found;
+                            if __prse_found != #slice {
+                                return Err(::prse::ParseError::BackReference {expected: #slice.into(), found: __prse_found.into()});
+                            }
+                        }
+                    } else {
+                        quote! {
+                            if #found != #slice {
+                                return Err(::prse::ParseError::BackReference);
+                            }
+                        }
                     });
                 }
                 Instruction::VecParse(..) => {
@@ -255,70 +1240,201 @@ impl Instructions {
                         let #var = #iter;
                     });
                 }
-                Instruction::MultiParse(_, _, count, _) => {
-                    let idx = 0..*count;
-                    let iter = i.gen_iter().unwrap();
+                Instruction::MultiParse(_, _, count, ..) => {
+                    store_token = Some(gen_array(
+                        var.to_token_stream(),
+                        i.gen_iter().unwrap(),
+                        *count,
+                    ));
+                }
+                Instruction::MapParse(_, sep, is_multi, max) => {
+                    let iter = match (separator_class(sep), separator_set(sep)) {
+                        (Some(class), _) => quote!(new_class(__prse_parse, #class)),
+                        (_, Some(set)) => quote!(new_set(__prse_parse, #set, #is_multi)),
+                        _ => {
+                            let sep = literal_separator(sep);
+                            quote!(new(__prse_parse, #sep, #is_multi))
+                        }
+                    };
+                    let iter = match max {
+                        Some(max) => quote!(#iter.with_max(#max)),
+                        None => iter,
+                    };
                     store_token = Some(quote! {
-                        let mut __prse_iter = #iter;
-                        let #var = [ #(
-                            __prse_iter.next()
-                            .ok_or_else(|| ::prse::ParseError::Array {
-                                expected: #count,
-                                found: #idx,
-                            })??
-                        ),* ];
-                        let __prse_count_left = __prse_iter.count();
-                        if __prse_count_left != 0 {
-                            return Err(::prse::ParseError::Array {
-                                expected: #count,
-                                found: #count + __prse_count_left as u8,
-                            });
+                        let #var = ::prse::ParseIter::<::prse::__private::MapEntry<_, _>>::#iter
+                            .map(|__prse_entry| __prse_entry.map(|::prse::__private::MapEntry(__prse_key, __prse_value)| (__prse_key, __prse_value)))
+                            .collect::<::core::result::Result<_, ::prse::ParseError>>()?;
+                    });
+                }
+                Instruction::Balanced(v, open, close) => {
+                    let field_name = v.field_name();
+                    // After a capture the brackets are searched for, otherwise they must be at the very start.
+                    result.append_all(if store_token.is_some() {
+                        quote! {
+                            let (__prse_before, __prse_group, __prse_rest) = ::prse::__private::split_balanced(__prse_remaining, #open, #close)?;
+                            __prse_parse = __prse_before;
+                            __prse_remaining = __prse_rest;
+                        }
+                    } else {
+                        quote! {
+                            let (__prse_group, __prse_rest) = ::prse::__private::strip_balanced(__prse_remaining, #open, #close)?;
+                            __prse_remaining = __prse_rest;
                         }
                     });
+                    if let Some(t) = store_token {
+                        store_token = None;
+                        quoted = false;
+                        result.append_all(t);
+                    }
+                    let location = locate(quote!(__prse_group));
+                    result.append_all(quote! {
+                        let #var = ::prse::__private::try_parse_context(__prse_group, __prse_input, #field_name)?;
+                        #location
+                    });
+                }
+                // A single char can never directly follow a capture, so it is always at the current position.
+                Instruction::SingleChar(v) => {
+                    let field_name = v.field_name();
+                    let location = locate(quote!(__prse_parse));
+                    result.append_all(quote! {
+                        (__prse_parse, __prse_remaining) = ::prse::__private::split_char(__prse_remaining)?;
+                        let #var = ::prse::__private::try_parse_context(__prse_parse, __prse_input, #field_name)?;
+                        #location
+                    });
+                }
+                // Like a single char, a line can never directly follow a capture.
+                Instruction::Line(v) => {
+                    let field_name = v.field_name();
+                    let location = locate(quote!(__prse_parse));
+                    result.append_all(quote! {
+                        (__prse_parse, __prse_remaining) = ::prse::__private::split_line(__prse_remaining);
+                        let #var = ::prse::__private::try_parse_context(__prse_parse, __prse_input, #field_name)?;
+                        #location
+                    });
+                }
+                Instruction::Count(_, l_string) => {
+                    let l_string = string_to_tokens(l_string);
+                    let start = located.then(|| quote!(let __prse_count_start = __prse_remaining;));
+                    let location = locate(quote!(__prse_count_start));
+                    result.append_all(quote! {
+                        #start
+                        let mut #var = 0_usize;
+                        while let ::core::option::Option::Some(__prse_rest) = __prse_remaining.strip_prefix(#l_string) {
+                            __prse_remaining = __prse_rest;
+                            #var += 1;
+                        }
+                        #location
+                    });
+                }
+                // A capture before it is trimmed instead, as it takes the rest of the input.
+                Instruction::SkipWhitespace if store_token.is_none() => {
+                    result.append_all(quote! {
+                        __prse_remaining = __prse_remaining.trim_start();
+                    });
+                }
+                Instruction::SkipWhitespace => {}
+                Instruction::GroupStart(_) | Instruction::GroupEnd => {
+                    unreachable!("optional groups are expanded before generating the body")
                 }
             };
+            // The stored captures are parsed once the literal after them is found, from the slice before it.
+            if let (
+                Instruction::Parse(_)
+                | Instruction::Modified(..)
+                | Instruction::Quoted(_)
+                | Instruction::VecParse(..)
+                | Instruction::IterParse(..)
+                | Instruction::MultiParse(..)
+                | Instruction::MapParse(..),
+                Some(t),
+            ) = (i, store_token.as_mut())
+            {
+                t.append_all(locate(quote!(__prse_parse)));
+            }
         }
-        result.append_all(store_token.map_or_else(|| if cfg!(feature = "alloc") {
-            quote! {
-                if !__prse_remaining.is_empty() {
-                    return Err(::prse::ParseError::Literal {expected: "".into(), found: __prse_remaining.into()})
+        result.append_all(store_token.map_or_else(
+            || {
+                if anchor != Anchor::Whole {
+                    quote!()
+                } else if cfg!(feature = "alloc") {
+                    quote! {
+                        if !__prse_remaining.is_empty() {
+                            return Err(::prse::__private::literal_error("", __prse_remaining))
+                        }
+                    }
+                } else {
+                    quote! {
+                        if !__prse_remaining.is_empty() {
+                            return Err(::prse::ParseError::Literal)
+                        }
+                    }
                 }
-            }
-        } else {
-            quote! {
-                if !__prse_remaining.is_empty() {
-                    return Err(::prse::ParseError::Literal)
+            },
+            |t| {
+                // A capture at the end of the pattern consumes the rest of the input.
+                let consume = (anchor == Anchor::Advance).then(
+                    || quote!(__prse_remaining = &__prse_remaining[__prse_remaining.len()..];),
+                );
+                quote! {
+                    __prse_parse = __prse_remaining;
+                    #consume
+                    #t
                 }
-            }
-        }, |t| quote! { __prse_parse = __prse_remaining; #t }));
+            },
+        ));
 
-        let return_idents = self.0.iter().enumerate().filter_map(|(idx, i)| {
-            i.get_var()?;
-            Some(format_ident!("__prse_{idx}"))
-        });
+        let return_idents = self
+            .0
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, i)| {
+                i.get_var()?;
+                Some(format_ident!("__prse_{idx}"))
+            })
+            .chain((anchor == Anchor::Advance).then(|| format_ident!("__prse_remaining")));
         result.append_all(quote! { Ok(( #(#return_idents),* )) });
     }
 
     pub fn gen_return_idents(
         &self,
-        return_idents: &mut Vec<Ident>,
+        return_idents: &mut Vec<TokenStream>,
         func_idents: &mut Vec<Ident>,
         renames: &mut Vec<(Ident, Ident)>,
     ) {
         let mut num_positions = 0;
+        // The captures of the current tuple group, which are returned together as a single `Option`.
+        let mut tuple: Option<Vec<Ident>> = None;
 
-        for (idx, instruction) in self
-            .0
-            .iter()
-            .enumerate()
-            .filter(|(_, i)| !matches!(i, Instruction::Lit(_)))
-        {
-            let var = instruction.get_var().unwrap();
+        for (idx, instruction) in self.0.iter().enumerate() {
+            let var = match instruction {
+                Instruction::GroupStart(true) => {
+                    tuple = Some(vec![]);
+                    continue;
+                }
+                Instruction::GroupEnd => {
+                    if let Some(idents) = tuple.take() {
+                        return_idents.push(quote! {
+                            match ( #(#idents,)* ) {
+                                ( #(::core::option::Option::Some(#idents),)* ) => ::core::option::Option::Some(( #(#idents),* )),
+                                _ => ::core::option::Option::None,
+                            }
+                        });
+                    }
+                    continue;
+                }
+                i => match i.get_var() {
+                    Some(var) => var,
+                    None => continue,
+                },
+            };
             let ident = format_ident!("__prse_{idx}");
             match var {
                 Var::Implied => {
                     func_idents.push(ident.clone());
-                    return_idents.push(ident);
+                    match &mut tuple {
+                        Some(idents) => idents.push(ident),
+                        None => return_idents.push(ident.to_token_stream()),
+                    }
                 }
                 Var::Ident(i) => {
                     func_idents.push(ident.clone());
@@ -326,10 +1442,126 @@ impl Instructions {
                 }
                 Var::Position(p) => {
                     func_idents.push(format_ident!("__prse_pos_{p}"));
-                    return_idents.push(format_ident!("__prse_pos_{num_positions}"));
+                    return_idents
+                        .push(format_ident!("__prse_pos_{num_positions}").to_token_stream());
                     num_positions += 1;
                 }
             };
         }
     }
 }
+
+/// Generates the parse of the default of a capture, in the same way as the capture itself.
+fn gen_default(instruction: &Instruction, default: &str) -> TokenStream {
+    let Instruction::Modified(var, modifiers) = instruction else {
+        unreachable!("only modified captures have a default")
+    };
+    let field_name = var.field_name();
+    let parse = modifiers.gen_parse();
+    quote!({
+        let __prse_parse: &str = #default;
+        ::prse::__private::add_context(#parse, __prse_parse, __prse_parse, #field_name)?
+    })
+}
+
+/// Applies the trailing and max modifiers of a repetition to its iterator.
+fn with_limits(iter: TokenStream, modifiers: &Modifiers) -> TokenStream {
+    let iter = if modifiers.trailing {
+        quote!(#iter.with_trailing())
+    } else {
+        iter
+    };
+    match modifiers.max {
+        Some(max) => quote!(#iter.with_max(#max)),
+        None => iter,
+    }
+}
+
+/// Trims `slice` like a capture with the given trim modifier.
+fn trim_slice(slice: TokenStream, trim: Option<Trim>) -> TokenStream {
+    match trim {
+        Some(Trim::Both) => quote!(#slice.trim()),
+        Some(Trim::Start) => quote!(#slice.trim_start()),
+        Some(Trim::End) => quote!(#slice.trim_end()),
+        Some(Trim::Off) | None => slice,
+    }
+}
+
+/// Returns whether the elements of a repetition have modifiers that change how they are parsed,
+/// unlike the `trailing` and `max` modifiers that only limit the iterator.
+pub(crate) fn has_element_modifiers(modifiers: &Modifiers) -> bool {
+    let limits = Modifiers {
+        trailing: modifiers.trailing,
+        max: modifiers.max,
+        ..Modifiers::default()
+    };
+    modifiers != &limits
+}
+
+/// Parses every element of `iter` with the element modifiers, or returns `iter` as is if it has none.
+///
+/// An iterator with element modifiers is over the `&str` of each element, which are then parsed here.
+fn with_modifiers(iter: TokenStream, modifiers: &Modifiers) -> TokenStream {
+    let iter = with_limits(iter, modifiers);
+    if !has_element_modifiers(modifiers) {
+        return iter;
+    }
+    let parse = modifiers.gen_parse();
+    quote!({
+        let __prse_multi: &str = __prse_parse;
+        #iter.map(move |__prse_element| {
+            __prse_element.and_then(|__prse_parse: &str| {
+                ::prse::__private::add_err_multi_context(#parse, __prse_multi, __prse_parse)
+            })
+        })
+    })
+}
+
+/// The separator between the elements of a repetition, which are split by the separator of their row if they have one.
+fn element_sep<'a>(sep: &'a str, modifiers: &'a Modifiers) -> &'a str {
+    modifiers.row.as_ref().map_or(sep, |(row_sep, ..)| row_sep)
+}
+
+/// The type of an element of a repetition, which is an array of `element` if it is split into rows.
+fn row_type(element: &Ident, modifiers: &Modifiers) -> TokenStream {
+    match &modifiers.row {
+        Some((_, columns, _)) => {
+            let columns = *columns as usize;
+            quote!([ #element ; #columns ])
+        }
+        None => element.to_token_stream(),
+    }
+}
+
+/// Generates the statements that collect the elements of `iter` into the array `var` of `count` elements,
+/// returning an [`Array`](::prse::ParseError::Array) error if there are more or less elements.
+fn gen_array(var: TokenStream, iter: TokenStream, count: u8) -> TokenStream {
+    let idx = 0..count;
+    quote! {
+        let mut __prse_iter = #iter;
+        let #var = [ #(
+            __prse_iter.next()
+            .ok_or_else(|| ::prse::ParseError::Array {
+                expected: #count,
+                found: #idx,
+            })??
+        ),* ];
+        let __prse_count_left = __prse_iter.count();
+        if __prse_count_left != 0 {
+            return Err(::prse::ParseError::Array {
+                expected: #count,
+                found: #count + __prse_count_left as u8,
+            });
+        }
+    }
+}
+
+/// Removes the `\r` of a `\r\n` line ending from the capture before a literal that starts with `\n`,
+/// so that patterns match Windows line endings like [`str::lines`] does.
+fn strip_cr(literal: &str) -> Option<TokenStream> {
+    literal.starts_with('\n').then(|| {
+        quote! {
+            __prse_parse = __prse_parse.strip_suffix('\r').unwrap_or(__prse_parse);
+        }
+    })
+}
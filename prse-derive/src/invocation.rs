@@ -1,17 +1,36 @@
-use proc_macro2::TokenStream;
+use proc_macro2::{Ident, Span, TokenStream};
 use quote::{ToTokens, TokenStreamExt};
+use syn::ext::IdentExt;
 use syn::parse::{Parse, ParseStream};
 use syn::spanned::Spanned;
-use syn::{Expr, LitStr, Token};
+use syn::{Expr, LitStr, Token, Type, Visibility};
 
-use crate::instructions::Instructions;
+use crate::instructions::{Anchor, Instruction, Instructions};
+use crate::modifier::Modifiers;
+use crate::var::Var;
 
 #[derive(Clone)]
 pub struct ParseInvocation {
     input: Expr,
     trailing: Option<TrailingExpr>,
     instructions: Instructions,
+    pattern_span: Span,
+    validator: Option<TokenStream>,
+    fallback: Option<TokenStream>,
+    callback: Option<TokenStream>,
+    padding: Option<Padding>,
+    /// Whether every capture is returned with its location, used by `parse_located!`.
+    located: bool,
     pub try_parse: bool,
+    pub anchor: Anchor,
+}
+
+/// The arguments of `parse_padded!` after the pattern.
+#[derive(Clone)]
+struct Padding {
+    len: Expr,
+    default: Expr,
+    overflow: Option<Expr>,
 }
 
 // Implementing our own enum so we do not need to enable syn's full feature
@@ -60,12 +79,24 @@ impl TrailingExpr {
     }
 }
 
+/// The error given when the pattern is a path, such as the name of a const, instead of a string literal.
+const PATTERN_NOT_LITERAL: &str = "expected the pattern to be a string literal, as macros cannot read the value of a const. See the \"Reusing patterns\" section of the prse documentation for alternatives.";
+
+/// Parses the pattern, which must be a string literal.
+fn parse_pattern(stream: ParseStream) -> syn::Result<LitStr> {
+    if stream.peek(Ident::peek_any) || stream.peek(Token![::]) {
+        let path: syn::Path = stream.parse()?;
+        return Err(syn::Error::new(path.span(), PATTERN_NOT_LITERAL));
+    }
+    stream.parse()
+}
+
 impl Parse for ParseInvocation {
     fn parse(stream: ParseStream) -> syn::Result<Self> {
         let input = stream.parse()?;
         let trailing = TrailingExpr::parse(stream)?;
         let _coma: Token![,] = stream.parse()?;
-        let lit = stream.parse::<LitStr>()?;
+        let lit = parse_pattern(stream)?;
         let lit_string = lit.value();
         let instructions = Instructions::new(&lit_string, lit.span())?;
 
@@ -73,16 +104,111 @@ impl Parse for ParseInvocation {
             input,
             trailing,
             try_parse: false,
+            anchor: Anchor::Whole,
             instructions,
+            pattern_span: lit.span(),
+            validator: None,
+            fallback: None,
+            callback: None,
+            padding: None,
+            located: false,
         })
     }
 }
 
-impl ParseInvocation {}
+impl ParseInvocation {
+    /// Parses the arguments of `try_parse_validated!`, the validator is kept as is
+    /// since closures can only be parsed with syn's full feature.
+    pub fn parse_validated(stream: ParseStream) -> syn::Result<Self> {
+        let mut invocation: Self = stream.parse()?;
+        let _coma: Token![,] = stream.parse()?;
+        let validator: TokenStream = stream.parse()?;
+        if validator.is_empty() {
+            return Err(stream.error("Expected a validator after the pattern."));
+        }
+        invocation.try_parse = true;
+        invocation.validator = Some(validator);
+        Ok(invocation)
+    }
+
+    /// Parses the arguments of `parse_or!`, the fallback is kept as is for the same reason as the validator.
+    pub fn parse_fallback(stream: ParseStream) -> syn::Result<Self> {
+        let mut invocation: Self = stream.parse()?;
+        let _coma: Token![,] = stream.parse()?;
+        let fallback: TokenStream = stream.parse()?;
+        if fallback.is_empty() {
+            return Err(stream.error("Expected a fallback after the pattern."));
+        }
+        invocation.try_parse = true;
+        invocation.fallback = Some(fallback);
+        Ok(invocation)
+    }
+
+    /// Parses the arguments of `parse_located!`, whose captures all need a location in the input,
+    /// so they cannot have a default.
+    pub fn parse_located(stream: ParseStream) -> syn::Result<Self> {
+        let mut invocation: Self = stream.parse()?;
+        if invocation.instructions.has_defaults() {
+            return Err(syn::Error::new(
+                invocation.pattern_span,
+                "the default modifier cannot be used with parse_located!, as a default has no location.",
+            ));
+        }
+        invocation.located = true;
+        Ok(invocation)
+    }
+
+    /// Parses the arguments of `parse_for_each!`, the pattern's repetition is turned into an iterator
+    /// whose elements are passed to the callback one at a time.
+    pub fn parse_for_each(stream: ParseStream) -> syn::Result<Self> {
+        let mut invocation: Self = stream.parse()?;
+        let _coma: Token![,] = stream.parse()?;
+        let callback: TokenStream = stream.parse()?;
+        if callback.is_empty() {
+            return Err(stream.error("Expected a callback after the pattern."));
+        }
+        invocation.instructions = invocation
+            .instructions
+            .into_for_each(invocation.pattern_span, "parse_for_each!")?;
+        invocation.try_parse = true;
+        invocation.callback = Some(callback);
+        Ok(invocation)
+    }
+
+    /// Parses the arguments of `parse_padded!`, the pattern's repetition is turned into an iterator
+    /// whose elements are collected into a Vec of the given length.
+    pub fn parse_padded(stream: ParseStream) -> syn::Result<Self> {
+        let mut invocation: Self = stream.parse()?;
+        let arguments = || {
+            if stream.is_empty() {
+                return Err(
+                    stream.error("Expected a length and a default value after the pattern.")
+                );
+            }
+            let _coma: Token![,] = stream.parse()?;
+            stream.parse()
+        };
+        let len = arguments()?;
+        let default = arguments()?;
+        let overflow = if stream.parse::<Option<Token![,]>>()?.is_some() && !stream.is_empty() {
+            Some(stream.parse()?)
+        } else {
+            None
+        };
+        invocation.instructions = invocation
+            .instructions
+            .into_for_each(invocation.pattern_span, "parse_padded!")?;
+        invocation.padding = Some(Padding {
+            len,
+            default,
+            overflow,
+        });
+        Ok(invocation)
+    }
+}
 
 impl ToTokens for ParseInvocation {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        let func_name = format_ident!("__prse_func");
         let input = &self.input;
         let trailing = &self.trailing;
         let mut renames = vec![];
@@ -92,35 +218,69 @@ impl ToTokens for ParseInvocation {
         self.instructions
             .gen_return_idents(&mut return_idents, &mut func_idents, &mut renames);
 
-        let renames: TokenStream = renames.iter().flat_map(|(l, r)| quote!(#l = #r;)).collect();
+        // Named captures in optional groups are only assigned when the group is present.
+        let optional = self.instructions.optional_idents();
+        let renames: TokenStream = renames
+            .iter()
+            .flat_map(|(l, r)| {
+                if optional.contains(r) {
+                    quote! {
+                        if let ::core::option::Option::Some(__prse_value) = #r {
+                            #l = __prse_value;
+                        }
+                    }
+                } else {
+                    quote!(#l = #r;)
+                }
+            })
+            .collect();
 
-        let mut body = quote! {
-            let mut __prse_parse: &str;
-            let mut __prse_remaining = __prse_input;
+        let parse = if self.located {
+            self.instructions.gen_parse_located(quote!(__prse_input))
+        } else {
+            self.instructions
+                .gen_parse(quote!(__prse_input), self.anchor)
         };
 
-        self.instructions.gen_body(&mut body);
-
-        let function = self.instructions.gen_function(body, func_name.clone());
+        // When advancing, the input is a cursor that is moved past the matched text once parsing succeeds.
+        let mut result = if self.anchor == Anchor::Advance {
+            func_idents.push(format_ident!("__prse_rest"));
+            quote_spanned! { input.span() =>
+                let __prse_cursor: &mut &str = #input #trailing;
+                let __prse_input: &str = *__prse_cursor;
+            }
+        } else {
+            quote_spanned! { input.span() =>
+                #[allow(clippy::needless_borrow)]
+                let __prse_input: &str = &#input #trailing;
+            }
+        };
+        let advance =
+            (self.anchor == Anchor::Advance).then(|| quote!(*__prse_cursor = __prse_rest;));
 
-        let mut result = quote_spanned! { input.span() =>
-            #[allow(clippy::needless_borrow)]
-            let __prse_input: &str = &#input #trailing;
+        let ok_result = match &self.validator {
+            None => quote!(Ok(( #(#return_idents),* ))),
+            Some(validator) => quote! {
+                ::prse::__private::validate(Ok(( #(#return_idents),* )), #validator)
+            },
         };
 
-        result.append_all(if self.try_parse {
+        // The elements are only padded once the repetition has been matched.
+        result.append_all(if self.try_parse || self.padding.is_some() {
             quote! {
-                match #func_name (__prse_input) {
+                match #parse {
                     Ok(( #(#func_idents),* )) => {
+                        #advance
                         #renames
-                        Ok(( #(#return_idents),* ))
+                        #ok_result
                     }
                     Err(e) => Err(e),
                 }
             }
         } else {
             quote! {
-                let ( #(#func_idents),* ) = ::prse::__private::unwrap_parse(#func_name (__prse_input));
+                let ( #(#func_idents),* ) = ::prse::__private::unwrap_parse(#parse);
+                #advance
                 #renames
                 #[allow(clippy::unused_unit)]
                 {
@@ -129,18 +289,505 @@ impl ToTokens for ParseInvocation {
             }
         });
 
+        let result = quote! {
+            {
+                use ::prse::Parse;
+
+                #result
+            }
+        };
+
+        if let Some(Padding {
+            len,
+            default,
+            overflow,
+        }) = &self.padding
+        {
+            let overflow = match overflow {
+                Some(overflow) => overflow.to_token_stream(),
+                None => quote!(::prse::Overflow::Truncate),
+            };
+            let padded = quote!(::prse::__private::pad(#result, #len, #default, #overflow));
+            tokens.append_all(if self.try_parse {
+                padded
+            } else {
+                quote!(::prse::__private::unwrap_parse(#padded))
+            });
+            return;
+        }
+
+        // The fallback is only evaluated once parsing has failed.
+        tokens.append_all(match (&self.fallback, &self.callback) {
+            (None, Some(callback)) => quote! {
+                ::prse::__private::for_each(#result, #callback)
+            },
+            (None, None) => result,
+            (Some(fallback), _) => quote! {
+                match #result {
+                    Ok(__prse_value) => __prse_value,
+                    Err(_) => #fallback,
+                }
+            },
+        });
+    }
+}
+
+/// A function generated by `parse_fn!` that wraps a `try_parse!` invocation.
+pub struct ParseFn {
+    vis: Visibility,
+    name: Ident,
+    invocation: ParseInvocation,
+    return_type: Type,
+}
+
+impl Parse for ParseFn {
+    fn parse(stream: ParseStream) -> syn::Result<Self> {
+        let vis = stream.parse()?;
+        let name = stream.parse()?;
+        let _coma: Token![,] = stream.parse()?;
+        let lit = parse_pattern(stream)?;
+        let lit_string = lit.value();
+        let instructions = Instructions::new(&lit_string, lit.span())?;
+        if instructions
+            .0
+            .iter()
+            .any(|i| matches!(i.get_var(), Some(Var::Ident(_))))
+        {
+            return Err(syn::Error::new(
+                lit.span(),
+                "parse_fn! cannot use named captures, consider using positional captures instead.",
+            ));
+        }
+        let _coma: Token![,] = stream.parse()?;
+        let return_type = stream.parse()?;
+
+        Ok(Self {
+            vis,
+            name,
+            invocation: ParseInvocation {
+                input: parse_quote!(__prse_fn_input),
+                trailing: None,
+                instructions,
+                pattern_span: lit.span(),
+                validator: None,
+                fallback: None,
+                callback: None,
+                padding: None,
+                located: false,
+                try_parse: true,
+                anchor: Anchor::Whole,
+            },
+            return_type,
+        })
+    }
+}
+
+impl ToTokens for ParseFn {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ParseFn {
+            vis,
+            name,
+            invocation,
+            return_type,
+        } = self;
+        tokens.append_all(quote! {
+            #vis fn #name<'a>(__prse_fn_input: &'a str) -> ::core::result::Result<#return_type, ::prse::ParseError> {
+                #invocation
+            }
+        });
+    }
+}
+
+/// The arguments of `parse_map!`, which returns the raw text of every capture keyed by its name.
+pub struct ParseMap {
+    invocation: ParseInvocation,
+    pub try_parse: bool,
+}
+
+impl Parse for ParseMap {
+    fn parse(stream: ParseStream) -> syn::Result<Self> {
+        let mut invocation: ParseInvocation = stream.parse()?;
+        if !cfg!(feature = "alloc") {
+            return Err(stream.error("parse_map! is only available with the alloc feature."));
+        }
+        if invocation
+            .instructions
+            .0
+            .iter()
+            .any(|i| matches!(i, Instruction::Count(..)))
+        {
+            return Err(stream.error("parse_map! cannot be used with counts."));
+        }
+        // Every capture is kept as the raw slice it matched, whatever modifiers or repetition it used.
+        // Brackets already match the slice inside of them, so they are kept as is.
+        for i in invocation.instructions.0.iter_mut() {
+            if matches!(i, Instruction::Balanced(..) | Instruction::Line(_)) {
+                continue;
+            }
+            if let Some(var) = i.get_var() {
+                *i = Instruction::Parse(var.clone());
+            }
+        }
+        Ok(Self {
+            invocation,
+            try_parse: false,
+        })
+    }
+}
+
+impl ToTokens for ParseMap {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ParseInvocation {
+            input,
+            trailing,
+            instructions,
+            ..
+        } = &self.invocation;
+        let map: TokenStream = if cfg!(feature = "std") {
+            quote!(::std::collections::BTreeMap)
+        } else {
+            quote!(::alloc::collections::BTreeMap)
+        };
+
+        let optional = instructions.optional_indices();
+        let mut idents = vec![];
+        let mut types = vec![];
+        let mut inserts = vec![];
+        let mut num_implied = 0_usize;
+        for (idx, i) in instructions.0.iter().enumerate() {
+            let key = match i.get_var() {
+                None => continue,
+                Some(Var::Ident(ident)) => ident.unraw().to_string(),
+                Some(Var::Position(p)) => p.to_string(),
+                Some(Var::Implied) => {
+                    num_implied += 1;
+                    (num_implied - 1).to_string()
+                }
+            };
+            let ident = format_ident!("__prse_{idx}");
+            // Captures in an optional group are left out of the map when the group is absent.
+            if optional.contains(&idx) {
+                types.push(quote!(::core::option::Option<&str>));
+                inserts.push(quote! {
+                    if let ::core::option::Option::Some(__prse_value) = #ident {
+                        __prse_map.insert(#key, __prse_value);
+                    }
+                });
+            } else {
+                types.push(quote!(&str));
+                inserts.push(quote!(__prse_map.insert(#key, #ident);));
+            }
+            idents.push(ident);
+        }
+
+        let parse = instructions.gen_parse(quote!(__prse_input), Anchor::Whole);
+
+        let result = if self.try_parse {
+            quote!(__prse_result)
+        } else {
+            quote!(::prse::__private::unwrap_parse(__prse_result))
+        };
+
         tokens.append_all(quote! {
             {
                 use ::prse::Parse;
 
-                #function
+                #[allow(clippy::needless_borrow)]
+                let __prse_input: &str = &#input #trailing;
+                let __prse_result: ::core::result::Result<#map<&'static str, &str>, ::prse::ParseError> =
+                    #parse.map(|( #(#idents),* ): ( #(#types),* )| {
+                        #[allow(unused_mut)]
+                        let mut __prse_map = #map::new();
+                        #(#inserts)*
+                        __prse_map
+                    });
+                #result
+            }
+        });
+    }
+}
+
+/// The arguments of `parse_records!`, which parses every record between blank lines with the pattern.
+pub struct ParseRecords {
+    invocation: ParseInvocation,
+    pub try_parse: bool,
+}
+
+impl Parse for ParseRecords {
+    fn parse(stream: ParseStream) -> syn::Result<Self> {
+        let invocation: ParseInvocation = stream.parse()?;
+        if !cfg!(feature = "alloc") {
+            return Err(stream.error("parse_records! is only available with the alloc feature."));
+        }
+        if invocation
+            .instructions
+            .0
+            .iter()
+            .any(|i| matches!(i.get_var(), Some(Var::Ident(_))))
+        {
+            return Err(syn::Error::new(
+                invocation.pattern_span,
+                "parse_records! cannot assign named captures, as every record is parsed on its own.",
+            ));
+        }
+        Ok(Self {
+            invocation,
+            try_parse: false,
+        })
+    }
+}
+
+impl ToTokens for ParseRecords {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ParseInvocation {
+            input,
+            trailing,
+            instructions,
+            ..
+        } = &self.invocation;
+        let vec = if cfg!(feature = "std") {
+            quote!(::std::vec::Vec)
+        } else {
+            quote!(::alloc::vec::Vec)
+        };
+        let mut return_idents = vec![];
+        let mut func_idents = vec![];
+        instructions.gen_return_idents(&mut return_idents, &mut func_idents, &mut vec![]);
+        let parse = instructions.gen_parse(quote!(__prse_input), Anchor::Whole);
+
+        let result = if self.try_parse {
+            quote!(__prse_result)
+        } else {
+            quote!(::prse::__private::unwrap_parse(__prse_result))
+        };
 
+        tokens.append_all(quote! {
+            {
+                use ::prse::Parse;
+
+                #[allow(clippy::needless_borrow)]
+                let __prse_records: &str = &#input #trailing;
+                let __prse_result: ::core::result::Result<#vec<_>, ::prse::ParseError> =
+                    ::prse::__private::split_records(__prse_records)
+                        .into_iter()
+                        .enumerate()
+                        .map(|(__prse_index, __prse_input)| {
+                            ::prse::__private::add_record_context(
+                                #parse.map(|( #(#func_idents),* )| ( #(#return_idents),* )),
+                                __prse_input,
+                                __prse_records,
+                                __prse_index,
+                            )
+                        })
+                        .collect();
                 #result
             }
         });
     }
 }
 
+/// The arguments of `which_matches!`, the input and the patterns that are tried in order.
+pub struct WhichMatches {
+    input: Expr,
+    trailing: Option<TrailingExpr>,
+    patterns: Vec<Instructions>,
+}
+
+impl Parse for WhichMatches {
+    fn parse(stream: ParseStream) -> syn::Result<Self> {
+        let input = stream.parse()?;
+        let trailing = TrailingExpr::parse(stream)?;
+        let _coma: Token![,] = stream.parse()?;
+        let content;
+        let brackets = bracketed!(content in stream);
+        let mut patterns = vec![];
+        while !content.is_empty() {
+            let lit = parse_pattern(&content)?;
+            let mut instructions = Instructions::new(&lit.value(), lit.span())?;
+            for i in instructions.0.iter_mut() {
+                matched_capture(i);
+            }
+            patterns.push(instructions);
+            if !content.is_empty() {
+                let _coma: Token![,] = content.parse()?;
+            }
+        }
+        if patterns.is_empty() {
+            return Err(syn::Error::new(
+                brackets.span.join(),
+                "Expected at least one pattern.",
+            ));
+        }
+        Ok(Self {
+            input,
+            trailing,
+            patterns,
+        })
+    }
+}
+
+/// Replaces the modifiers of a capture that convert its text, only keeping those that change what text
+/// it matches, then returns the type of the matched capture.
+fn matched_capture(i: &mut Instruction) -> Option<TokenStream> {
+    let strip = |modifiers: &mut Modifiers| {
+        *modifiers = Modifiers {
+            trim: modifiers.trim,
+            doubled: modifiers.doubled,
+            trailing: modifiers.trailing,
+            max: modifiers.max,
+            shape: modifiers.shape.take(),
+            format: modifiers.format,
+            row: modifiers.row.take(),
+            ..Modifiers::default()
+        };
+        let element = if modifiers.doubled {
+            if cfg!(feature = "std") {
+                quote!(::std::string::String)
+            } else {
+                quote!(::alloc::string::String)
+            }
+        } else {
+            quote!(&str)
+        };
+        match &modifiers.row {
+            Some((_, columns, _)) => {
+                let columns = *columns as usize;
+                quote!([#element; #columns])
+            }
+            None => element,
+        }
+    };
+    let vec = if cfg!(feature = "std") {
+        quote!(::std::vec::Vec)
+    } else {
+        quote!(::alloc::vec::Vec)
+    };
+    Some(match i {
+        Instruction::Parse(_)
+        | Instruction::Balanced(..)
+        | Instruction::SingleChar(_)
+        | Instruction::Line(_) => {
+            quote!(&str)
+        }
+        Instruction::Modified(_, modifiers) => strip(modifiers),
+        Instruction::VecParse(.., modifiers) => {
+            let element = strip(modifiers);
+            quote!(#vec<#element>)
+        }
+        Instruction::IterParse(_, sep, .., modifiers) => {
+            strip(modifiers);
+            if sep.is_empty() {
+                quote!(::prse::ParseChars<'_, &str>)
+            } else {
+                quote!(::prse::ParseIter<'_, &str>)
+            }
+        }
+        Instruction::MultiParse(_, _, count, _, modifiers) => {
+            let count = *count as usize;
+            let element = strip(modifiers);
+            quote!([#element; #count])
+        }
+        Instruction::MapParse(var, ..) => {
+            *i = Instruction::Parse(var.clone());
+            quote!(&str)
+        }
+        Instruction::Count(..) => quote!(usize),
+        Instruction::Quoted(_) if cfg!(feature = "std") => quote!(::std::string::String),
+        Instruction::Quoted(_) => quote!(::alloc::string::String),
+        _ => return None,
+    })
+}
+
+impl ToTokens for WhichMatches {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let input = &self.input;
+        let trailing = &self.trailing;
+        let checks = self.patterns.iter().map(|instructions| {
+            let optional = instructions.optional_indices();
+            let types = instructions.0.iter().enumerate().filter_map(|(idx, i)| {
+                let ty = matched_capture(&mut i.clone())?;
+                Some(if optional.contains(&idx) {
+                    quote!(::core::option::Option<#ty>)
+                } else {
+                    ty
+                })
+            });
+            let parse = instructions.gen_parse(quote!(__prse_input), Anchor::Whole);
+            quote! {
+                {
+                    let __prse_result: ::core::result::Result<( #(#types),* ), ::prse::ParseError> = #parse;
+                    __prse_result.is_ok()
+                }
+            }
+        });
+        let indices = 0..self.patterns.len();
+
+        tokens.append_all(quote! {
+            {
+                use ::prse::Parse;
+
+                #[allow(clippy::needless_borrow)]
+                let __prse_input: &str = &#input #trailing;
+                #(
+                    if #checks {
+                        ::core::option::Option::Some(#indices)
+                    } else
+                )* {
+                    ::core::option::Option::None
+                }
+            }
+        });
+    }
+}
+
+/// The arguments of `pattern_literals!`, the literals of the pattern and whether a capture comes before each of them.
+pub struct PatternLiterals {
+    segments: Vec<(bool, String)>,
+}
+
+impl Parse for PatternLiterals {
+    fn parse(stream: ParseStream) -> syn::Result<Self> {
+        let lit = parse_pattern(stream)?;
+        let instructions = Instructions::new(&lit.value(), lit.span())?;
+        let mut segments = vec![];
+        let mut after_capture = false;
+        for i in instructions.0 {
+            match i {
+                Instruction::Lit(l) => {
+                    segments.push((after_capture, l));
+                    after_capture = false;
+                }
+                Instruction::FlexLit(..) | Instruction::SkipWhitespace => {
+                    return Err(syn::Error::new(
+                        lit.span(),
+                        "pattern_literals! cannot be used with pattern flags.",
+                    ));
+                }
+                Instruction::GroupStart(_) | Instruction::GroupEnd => {
+                    return Err(syn::Error::new(
+                        lit.span(),
+                        "pattern_literals! cannot be used with optional groups.",
+                    ));
+                }
+                _ => after_capture = true,
+            }
+        }
+        Ok(Self { segments })
+    }
+}
+
+impl ToTokens for PatternLiterals {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let segments = self
+            .segments
+            .iter()
+            .map(|(after_capture, l)| quote!((#after_capture, #l)));
+        tokens.append_all(quote! {
+            ::prse::PatternLiterals::new(&[ #(#segments),* ])
+        });
+    }
+}
+
 pub(crate) fn string_to_tokens(string: &str) -> TokenStream {
     string
         .parse()
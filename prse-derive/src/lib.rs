@@ -8,7 +8,8 @@ extern crate quote;
 extern crate syn;
 
 use derive::Derive;
-use invocation::ParseInvocation;
+use instructions::Anchor;
+use invocation::{ParseFn, ParseInvocation, ParseMap, ParseRecords, PatternLiterals, WhichMatches};
 use proc_macro::TokenStream;
 use quote::ToTokens;
 
@@ -16,6 +17,7 @@ mod derive;
 mod expand_derive;
 mod instructions;
 mod invocation;
+mod modifier;
 mod var;
 
 /// The `parse` macro allows you to parse a string into any type that implements [`Parse`](trait.Parse.html).
@@ -101,6 +103,68 @@ mod var;
 /// ```ignore
 /// assert_eq!([3, 2, 1], parse!("321", "{::3}"))
 /// ```
+/// ## Separator classes
+///
+/// A separator written as `[<chars>]+` matches any run of those chars, where `\s` is any whitespace.
+/// Separators at the start or end of the input are ignored.
+/// ```ignore
+/// let nums: Vec<u32> = parse!(" 1, 2\t3,,4 ", r"{:[,\s]+:}");
+/// assert_eq!(nums, [1, 2, 3, 4]);
+/// ```
+/// Without the `+`, a separator set `[<chars>]` splits on any single one of its chars.
+/// ```ignore
+/// let nums: Vec<u32> = parse!("1,2;3", "{:[,;]:}");
+/// assert_eq!(nums, [1, 2, 3]);
+/// ```
+/// A `[` in a separator is matched as is when escaped as `\[`.
+/// ```ignore
+/// let nums: Vec<u32> = parse!("1[,]2", r"{:\[,]:}");
+/// assert_eq!(nums, [1, 2]);
+/// ```
+/// ## Element modifiers
+///
+/// Capture modifiers can be applied to every element of a repetition by adding them after the count,
+/// `{<var>:<sep>:<count>:<modifier>,...}`. An iterator can only use the `trailing` and `max` modifiers.
+/// ```ignore
+/// let bytes: [u8; 3] = parse!(" 0x1 , 0xff ,0x3", "{:,:3:x,trim}");
+/// assert_eq!(bytes, [1, 255, 3]);
+/// ```
+/// The `dd` modifier lets the elements contain the separator by doubling it, `{:,:dd}` is a shorthand for a Vec.
+/// The elements then have to be owned types as the doubled separators are removed.
+/// ```ignore
+/// let fields: Vec<String> = parse!("a,,b,c", "{:,:dd}");
+/// assert_eq!(fields, ["a,b", "c"]);
+/// ```
+/// A separator at the end of the input ends the last element, the `trailing` modifier keeps the empty element after it.
+/// ```ignore
+/// let row: [Missing<i32, Blank>; 4] = parse!("1,,3,", "{:,:4:trailing}");
+/// ```
+/// The `max=<count>` modifier returns a `TooManyElements` error once there are more than `count` elements.
+/// ```ignore
+/// let many: Result<Vec<u32>, _> = try_parse!("1,2,3,4", "{:,::max=3}");
+/// assert!(many.is_err());
+/// ```
+/// ## Rows
+///
+/// A Vec or an array of rows that are each parsed into an array is given by the separator and number of columns
+/// of the row in brackets, `{<var>:<sep>:[<row sep>:<columns>]}` or `{<var>:<sep>:<count>:[<row sep>:<columns>]}`.
+/// A row with the wrong number of columns returns an `Array` error naming the index of the row.
+/// ```ignore
+/// let matrix: Vec<[i32; 3]> = parse!("1,2,3;4,5,6", "{:;:[,:3]}");
+/// assert_eq!(matrix, [[1, 2, 3], [4, 5, 6]]);
+/// ```
+/// ## Followed by a literal
+///
+/// Like any other capture, a repetition only covers the text up to the first occurrence of the literal
+/// that follows it, so a list can be ended by a terminator. An empty list yields no elements.
+/// ```ignore
+/// let (items, rest): (Vec<u32>, &str) = parse!("1,2,3;rest", "{:,:};{}");
+/// assert_eq!(items, [1, 2, 3]);
+/// assert_eq!(rest, "rest");
+///
+/// let (items, rest): (Vec<u32>, &str) = parse!(";rest", "{:,:};{}");
+/// assert!(items.is_empty());
+/// ```
 /// # Syntax
 ///
 /// The [`parse!`] macro uses a literal with `{}` brackets to denote where it should
@@ -131,6 +195,55 @@ mod var;
 ///
 /// assert_eq!(num, 42);
 /// ```
+///
+/// ## Back-references
+///
+/// If a named capture appears more than once, every later occurrence is a back-reference.
+/// Instead of being parsed again, the text it covers must be byte for byte identical to the text
+/// covered by the first occurrence (before it was parsed), otherwise a
+/// [`ParseError::BackReference`](enum.ParseError.html#variant.BackReference) is returned.
+/// Back-references can only be used with plain captures and not with repetitions.
+///
+/// ```ignore
+/// let input = "<<b>>bold text<<b>>";
+/// let tag: &str;
+///
+/// let content: &str = parse!(input, "<<{tag}>>{}<<{tag}>>");
+///
+/// assert_eq!(tag, "b");
+/// assert_eq!(content, "bold text");
+/// ```
+///
+/// ## Optional groups
+///
+/// Part of a pattern can be made optional by surrounding it with `{[` and `]}`.
+/// The groups are tried as present first, from left to right, and left out if the input does not match.
+/// Unnamed captures inside a group are returned as an [`Option`], while named captures are only
+/// assigned when the group is present. A capture with a `default=<value>` modifier is instead always assigned,
+/// with the value only parsed when the group is absent.
+/// A pattern can have at most 4 optional groups, they cannot be nested and cannot contain back-references.
+///
+/// ```ignore
+/// let (path, version): (&str, Option<&str>) = parse!("GET /index.html", "GET {}{[ {}]}");
+/// assert_eq!(version, None);
+///
+/// let version: &str;
+/// let path: &str = parse!("GET /index.html", "GET {}{[ {version:default=HTTP/1.0}]}");
+/// assert_eq!(version, "HTTP/1.0");
+/// ```
+///
+/// To get all the captures of a group as one [`Option`] of a tuple, surround it with `{(` and `)}` instead.
+/// When the group is present every capture has to parse, and otherwise the whole tuple is [`None`].
+/// A tuple group with a single capture is returned as an [`Option`] of that capture. Tuple groups can only
+/// contain unnamed captures, and cannot be used when deriving [`Parse`](derive.Parse.html).
+///
+/// ```ignore
+/// let point: Option<(i32, i32)> = parse!("pt=(1,2)", "pt={(({},{}))}");
+/// assert_eq!(point, Some((1, 2)));
+///
+/// let point: Option<(i32, i32)> = parse!("pt=", "pt={(({},{}))}");
+/// assert_eq!(point, None);
+/// ```
 #[proc_macro]
 pub fn parse(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as ParseInvocation);
@@ -154,9 +267,260 @@ pub fn try_parse(input: TokenStream) -> TokenStream {
     input.to_token_stream().into()
 }
 
+/// Like [`parse!`] but only requires the input to start with the pattern.
+///
+/// Matching stops right after the last literal of the pattern and any input left after it is ignored,
+/// without needing to capture it. If the pattern ends with a capture, then that capture still
+/// consumes the rest of the input just like with [`parse!`].
+/// ```ignore
+/// let input = "GET /index.html HTTP/1.1";
+///
+/// let path: &str = parse_prefix!(input, "GET {} ");
+/// assert_eq!(path, "/index.html");
+/// ```
+#[proc_macro]
+pub fn parse_prefix(input: TokenStream) -> TokenStream {
+    let mut input = parse_macro_input!(input as ParseInvocation);
+    input.anchor = Anchor::Prefix;
+    input.to_token_stream().into()
+}
+
+/// Returns a [`Result`](https://doc.rust-lang.org/stable/std/result/enum.Result.html) instead of unwrapping like [`parse_prefix!`].
+///
+/// For more information please look at [`parse_prefix!`] and [`try_parse!`].
+/// ```ignore
+/// let version: Result<u32, _> = try_parse_prefix!("v2.1.0", "v{}.");
+/// assert_eq!(version, Ok(2));
+///
+/// let version: Result<u32, _> = try_parse_prefix!("2.1.0", "v{}.");
+/// assert!(version.is_err());
+/// ```
+#[proc_macro]
+pub fn try_parse_prefix(input: TokenStream) -> TokenStream {
+    let mut input = parse_macro_input!(input as ParseInvocation);
+    input.try_parse = true;
+    input.anchor = Anchor::Prefix;
+    input.to_token_stream().into()
+}
+
+/// Like [`parse_prefix!`] but takes a `&mut &str` cursor and moves it past the text that the pattern matched.
+///
+/// Calling it repeatedly walks through the input, which is useful for hand-written parsers that
+/// consume their input bit by bit. A capture at the end of the pattern consumes the rest of the input,
+/// leaving the cursor empty.
+/// ```ignore
+/// let mut cursor = "move 3 from 1 to 2";
+///
+/// let count: u32 = parse_advance!(&mut cursor, "move {} ");
+/// let (from, to): (u32, u32) = parse_advance!(&mut cursor, "from {} to {}");
+/// assert_eq!((count, from, to), (3, 1, 2));
+/// assert!(cursor.is_empty());
+/// ```
+#[proc_macro]
+pub fn parse_advance(input: TokenStream) -> TokenStream {
+    let mut input = parse_macro_input!(input as ParseInvocation);
+    input.anchor = Anchor::Advance;
+    input.to_token_stream().into()
+}
+
+/// Returns a [`Result`](https://doc.rust-lang.org/stable/std/result/enum.Result.html) instead of unwrapping like [`parse_advance!`].
+///
+/// The cursor is only moved when parsing succeeds, on error it is left unchanged so that
+/// another pattern can be tried from the same position.
+/// For more information please look at [`parse_advance!`] and [`try_parse!`].
+/// ```ignore
+/// let mut cursor = "x=1;y=2;";
+///
+/// let x: Result<u32, _> = try_parse_advance!(&mut cursor, "x={};");
+/// assert_eq!(x, Ok(1));
+/// let x: Result<u32, _> = try_parse_advance!(&mut cursor, "x={};");
+/// assert!(x.is_err());
+/// assert_eq!(cursor, "y=2;");
+/// ```
+#[proc_macro]
+pub fn try_parse_advance(input: TokenStream) -> TokenStream {
+    let mut input = parse_macro_input!(input as ParseInvocation);
+    input.try_parse = true;
+    input.anchor = Anchor::Advance;
+    input.to_token_stream().into()
+}
+
+/// Like [`parse!`] but every capture is returned together with the `Location` it starts at.
+///
+/// Each capture becomes a tuple of its value and a [`Location`](struct.Location.html) holding the line and column
+/// of the start of the capture, as well as its byte offset in the input. The location is where the text
+/// of the capture starts, so it includes any whitespace that is trimmed before parsing. A count is located
+/// at the start of the counted literals, and the captures in optional groups are an `Option` of the tuple.
+/// ```ignore
+/// let input = "fn main() {\n    let x = 5;\n}";
+/// let ((name, at_name), (value, at_value)): ((&str, Location), (u32, Location)) =
+///     parse_located!(input, "fn {}() {{\n    let x = {};\n}}");
+///
+/// assert_eq!((name, at_name.line, at_name.col), ("main", 1, 4));
+/// assert_eq!((value, at_value.line, at_value.col), (5, 2, 13));
+/// ```
+#[proc_macro]
+pub fn parse_located(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input with ParseInvocation::parse_located);
+    input.to_token_stream().into()
+}
+
+/// Returns a [`Result`](https://doc.rust-lang.org/stable/std/result/enum.Result.html) instead of unwrapping like [`parse_located!`].
+///
+/// For more information please look at [`parse_located!`] and [`try_parse!`].
+/// ```ignore
+/// let located: Result<(u32, Location), _> = try_parse_located!("a\nb: 7", "a\nb: {}");
+/// assert_eq!(located.unwrap().1, Location { line: 2, col: 4, offset: 5 });
+///
+/// let located: Result<(u32, Location), _> = try_parse_located!("a\nb: x", "a\nb: {}");
+/// assert!(located.is_err());
+/// ```
+#[proc_macro]
+pub fn try_parse_located(input: TokenStream) -> TokenStream {
+    let mut input = parse_macro_input!(input with ParseInvocation::parse_located);
+    input.try_parse = true;
+    input.to_token_stream().into()
+}
+
+/// Generates a function that parses its input using the given pattern, like [`try_parse!`] does.
+///
+/// The function is named using the first argument, which can be preceded by a visibility, and returns
+/// a `Result` of the type given as the last argument. The lifetime `'a` can be used to borrow from the input.
+/// Named captures cannot be used as there is no variable to assign them to.
+/// ```ignore
+/// use prse::parse_fn;
+///
+/// parse_fn!(pub parse_point, "({}, {})", (i32, i32));
+/// parse_fn!(parse_key, "{}={}", (&'a str, u32));
+///
+/// assert_eq!(parse_point("(1, -2)"), Ok((1, -2)));
+/// assert!(parse_point("(1, -2").is_err());
+/// assert_eq!(parse_key("port=80"), Ok(("port", 80)));
+///
+/// let points: Vec<_> = ["(0, 0)", "(3, 4)"].into_iter().map(parse_point).collect();
+/// ```
+#[proc_macro]
+pub fn parse_fn(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as ParseFn);
+    input.to_token_stream().into()
+}
+
+/// Matches the input against the pattern and returns the raw text of every capture, keyed by the capture's name.
+///
+/// Nothing is parsed into a type, a `BTreeMap<&'static str, &str>` is returned instead, which is useful
+/// for debugging a pattern or for generic loaders that decide how to parse each value later.
+/// Positional captures are keyed by their position as a string (`"0"`, `"1"`, ...),
+/// counting from the first positional capture. Repetitions and modified captures are kept as the
+/// text they matched, without being split or trimmed.
+/// Like [`parse!`], this panics if the input does not match, this macro is only available with the alloc feature.
+/// ```ignore
+/// use prse::parse_map;
+///
+/// let captures = parse_map!("GET /index.html HTTP/1.1", "{method} {} HTTP/{version}");
+///
+/// assert_eq!(captures["method"], "GET");
+/// assert_eq!(captures["0"], "/index.html");
+/// assert_eq!(captures["version"], "1.1");
+/// ```
+#[proc_macro]
+pub fn parse_map(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as ParseMap);
+    input.to_token_stream().into()
+}
+
+/// Returns a [`Result`](https://doc.rust-lang.org/stable/std/result/enum.Result.html) instead of unwrapping like [`parse_map!`].
+///
+/// For more information please look at [`parse_map!`] and [`try_parse!`].
+/// ```ignore
+/// use prse::try_parse_map;
+///
+/// let captures = try_parse_map!("width=80", "{key}={value}").unwrap();
+/// assert_eq!(captures["key"], "width");
+///
+/// assert!(try_parse_map!("width", "{key}={value}").is_err());
+/// ```
+#[proc_macro]
+pub fn try_parse_map(input: TokenStream) -> TokenStream {
+    let mut input = parse_macro_input!(input as ParseMap);
+    input.try_parse = true;
+    input.to_token_stream().into()
+}
+
+/// Like [`try_parse!`] but also runs a validator over the parsed result.
+///
+/// The validator is called with a reference to the value that [`try_parse!`] would have returned and
+/// must return a `Result<(), ParseError>`. If it returns an error, the error is returned as is.
+/// Named variables are assigned before the validator runs, so the validator can also use them.
+///
+/// This is useful for formats that contain a checksum or other constraints that can only be checked
+/// once everything has been parsed.
+/// ```ignore
+/// use prse::{try_parse_validated, ParseError};
+///
+/// let check_digit = |digits: &[u32; 4]| {
+///     if digits.iter().sum::<u32>() % 10 == 0 {
+///         Ok(())
+///     } else {
+///         Err(ParseError::new("invalid check digit"))
+///     }
+/// };
+///
+/// let code: Result<[u32; 4], _> = try_parse_validated!("Code: 1-2-3-4", "Code: {:-:4}", check_digit);
+/// assert!(code.is_ok());
+///
+/// let code: Result<[u32; 4], _> = try_parse_validated!("Code: 1-2-3-5", "Code: {:-:4}", check_digit);
+/// assert!(code.is_err());
+/// ```
+#[proc_macro]
+pub fn try_parse_validated(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input with ParseInvocation::parse_validated);
+    input.to_token_stream().into()
+}
+
+/// Splits the input into records separated by blank lines and parses each of them with the pattern into a `Vec`.
+///
+/// This suits record oriented text such as vCards, email headers or the entries of `/proc/cpuinfo`.
+/// Any number of blank lines, lines that only hold whitespace, separate two records and the blank lines at the
+/// start and end of the input are skipped, so the input can end in a newline. Both `\n` and `\r\n` line endings
+/// are supported, each record keeps the line endings between its lines but not the one after its last line.
+/// Every record must match the whole pattern and is returned as the captures that [`parse!`] would have returned,
+/// so named captures cannot be used. The error of a record is wrapped in a
+/// [`ParseError::Context`](enum.ParseError.html#variant.Context) whose field name is `record <index>`,
+/// counting from 0. Like [`parse!`], this panics if a record fails to parse,
+/// this macro is only available with the alloc feature.
+/// ```ignore
+/// use prse::parse_records;
+///
+/// let input = "name: Alice\nage: 30\n\nname: Bob\nage: 25\n";
+/// let people: Vec<(&str, u8)> = parse_records!(input, "name: {}\nage: {}");
+/// assert_eq!(people, [("Alice", 30), ("Bob", 25)]);
+/// ```
+#[proc_macro]
+pub fn parse_records(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as ParseRecords);
+    input.to_token_stream().into()
+}
+
+/// Returns a [`Result`](https://doc.rust-lang.org/stable/std/result/enum.Result.html) instead of unwrapping like [`parse_records!`].
+///
+/// For more information please look at [`parse_records!`] and [`try_parse!`].
+/// ```ignore
+/// use prse::try_parse_records;
+///
+/// let people: Result<Vec<(&str, u8)>, _> = try_parse_records!("Alice 30\n\nBob x", "{} {}");
+/// assert!(people.is_err());
+/// ```
+#[proc_macro]
+pub fn try_parse_records(input: TokenStream) -> TokenStream {
+    let mut input = parse_macro_input!(input as ParseRecords);
+    input.try_parse = true;
+    input.to_token_stream().into()
+}
+
 /// Automatically implements the [`Parse`](trait.Parse.html) trait using one of two methods.
 ///
-/// You can define how each field should be parsed using the `prse` attribute.
+/// You can define how each field should be parsed using the `prse` attribute, written either as
+/// `#[prse = "..."]` or `#[prse("...")]`.
 /// The prse attribute uses the same syntax as [`parse!`] and even supports Vec, Iterator and Array repetition.
 ///
 ///```ignore
@@ -174,6 +538,72 @@ pub fn try_parse(input: TokenStream) -> TokenStream {
 /// assert_eq!(pos.y, 2);
 ///```
 ///
+/// Fields can be of any type that implements `Parse`, including other derived types,
+/// and with the `alloc` feature a failing field is reported in a `ParseError::Context` with the field's name.
+///
+/// A field that is named more than once is a [back-reference](macro.parse.html#back-references), so its later
+/// occurrences must match the text of the first one or a
+/// [`ParseError::BackReference`](enum.ParseError.html#variant.BackReference) is returned.
+/// As a repeated field is valid, a field name that is repeated by mistake is not a compile error
+/// and is only found when an input does not match.
+///
+///```ignore
+/// use prse::{parse, try_parse, Parse};
+///
+/// #[derive(Debug, Parse)]
+/// #[prse = "<{tag}>{body}</{tag}>"]
+/// struct Element<'a> {
+///     tag: &'a str,
+///     body: &'a str,
+/// }
+///
+/// let element: Element = parse!("<b>bold</b>", "{}");
+/// assert_eq!(element.tag, "b");
+/// let mismatch: Result<Element, _> = try_parse!("<b>bold</i>", "{}");
+/// assert!(mismatch.is_err());
+///```
+///
+/// Tuple structs use implied or positional captures, which must cover every field exactly once,
+/// and unit structs can only contain a literal.
+///
+///```ignore
+/// use prse::{parse, Parse};
+///
+/// #[derive(Debug, Parse, PartialEq)]
+/// #[prse("{}°C")]
+/// struct Celsius(f64);
+///
+/// #[derive(Debug, Parse, PartialEq)]
+/// #[prse("ACK")]
+/// struct Ack;
+///
+/// assert_eq!(parse!("20°C", "{}"), Celsius(20.0));
+/// assert_eq!(parse!("ACK", "{}"), Ack);
+///```
+///
+/// A struct can be given several prse attributes to parse it from any of them, every pattern must
+/// parse all of the fields that do not have a default. The patterns are tried in order and the first one to match is used.
+/// If none of them match, a [`ParseError::Other`](enum.ParseError.html#variant.Other) holding the error of each pattern is returned.
+///
+///```ignore
+/// use prse::{parse, try_parse, Parse};
+///
+/// #[derive(Debug, Parse, PartialEq)]
+/// #[prse = "x={x},y={y}"]
+/// #[prse = "{x},{y}"]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// let named: Point = parse!("x=1,y=2", "{}");
+/// let positional: Point = parse!("1,2", "{}");
+/// assert_eq!(named, positional);
+///
+/// let invalid: Result<Point, _> = try_parse!("x=1", "{}");
+/// assert!(invalid.is_err());
+///```
+///
 /// This can also be done on enums.
 ///
 ///```ignore
@@ -201,6 +631,203 @@ pub fn try_parse(input: TokenStream) -> TokenStream {
 /// assert_eq!(pos2, Position::NoPos);
 /// assert_eq!(pos3, Position::NoPos);
 ///```
+///
+/// The variants are tried in the order they are declared and the first one to match is returned.
+/// If none of them match, the error of the first variant whose pattern starts with a literal found
+/// at the start of the input (or that starts with a capture) is returned, as the input most likely
+/// has the right tag but is otherwise invalid. If no variant's leading literal matched, a
+/// [`ParseError::Other`](enum.ParseError.html#variant.Other) is returned instead.
+///
+///```ignore
+/// use prse::{try_parse, Parse, ParseError};
+///
+/// #[derive(Debug, Parse)]
+/// enum Shape {
+///     #[prse = "circle r={r}"]
+///     Circle { r: f64 },
+///     #[prse = "rect w={w} h={h}"]
+///     Rect { w: f64, h: f64 },
+/// }
+///
+/// let unknown: Result<Shape, _> = Shape::from_str("triangle a=1");
+/// assert!(matches!(unknown, Err(ParseError::Other(_))));
+///
+/// // The error from parsing `h` as a Rect is returned.
+/// let invalid: Result<Shape, _> = Shape::from_str("rect w=2 h=?");
+/// assert!(matches!(invalid, Err(ParseError::Context { .. })));
+///```
+///
+/// Every named field must be parsed by the pattern, unless it is marked with `#[prse(default)]`
+/// in which case it is set using its [`Default`] implementation.
+///
+///```ignore
+/// use prse::{parse, Parse};
+///
+/// #[derive(Debug, Parse)]
+/// #[prse = "{name}"]
+/// struct User<'a> {
+///     name: &'a str,
+///     #[prse(default)]
+///     logins: u32,
+/// }
+///
+/// let user: User = parse!("bob", "{}");
+/// assert_eq!(user.name, "bob");
+/// assert_eq!(user.logins, 0);
+///```
+///
+/// The default can also be parsed from a string using `#[prse(default = "...")]`.
+/// Fields captured in an optional group must have a default, either on the field or given to the capture
+/// by its `default=<value>` modifier, which is used when the group is absent.
+///
+///```ignore
+/// use prse::{parse, Parse};
+///
+/// #[derive(Debug, Parse)]
+/// #[prse = "{method} {path}{[ {version}]}"]
+/// struct Request<'a> {
+///     method: &'a str,
+///     path: &'a str,
+///     #[prse(default = "HTTP/1.0")]
+///     version: &'a str,
+/// }
+///
+/// let request: Request = parse!("GET /", "{}");
+/// assert_eq!(request.version, "HTTP/1.0");
+///
+/// let request: Request = parse!("GET / HTTP/2", "{}");
+/// assert_eq!(request.version, "HTTP/2");
+///```
+///
+/// A field marked with `#[prse(multi)]` can be captured more than once, each occurrence is parsed
+/// separately and they are all collected into the field using its [`FromIterator`] implementation.
+/// Multi fields can only be captured using plain captures, e.g. `{field}`.
+///
+///```ignore
+/// use prse::{parse, Parse};
+///
+/// #[derive(Debug, Parse)]
+/// #[prse = "Set-Cookie: {cookies}\nSet-Cookie: {cookies}"]
+/// struct Cookies {
+///     #[prse(multi)]
+///     cookies: Vec<String>,
+/// }
+///
+/// let c: Cookies = parse!("Set-Cookie: a=1\nSet-Cookie: b=2", "{}");
+/// assert_eq!(c.cookies, ["a=1", "b=2"]);
+///```
+///
+/// A field marked with `#[prse(radix = <radix>)]` has every capture parsed with that radix, like the `b`, `o` and `x`
+/// modifiers, where the radix is 2, 8 or 16. This also works on unnamed fields and applies to every element of a repetition,
+/// but the field cannot also have a radix modifier in the pattern.
+///
+///```ignore
+/// use prse::{parse, Parse};
+///
+/// #[derive(Debug, Parse)]
+/// #[prse = "pid={pid} mem={mem}"]
+/// struct Process {
+///     #[prse(radix = 16)]
+///     pid: u32,
+///     mem: u32,
+/// }
+///
+/// let p: Process = parse!("pid=0x1f mem=1024", "{}");
+/// assert_eq!((p.pid, p.mem), (0x1f, 1024));
+///```
+///
+/// A single capture can be split into several fields by a sub-pattern, given on the struct using
+/// `#[prse(sub(<name> = "<pattern>"))]`. The pattern captures the sub-pattern by its name, and once the
+/// pattern matched, the text of that capture is parsed by the sub-pattern into the fields it names.
+/// A sub-pattern can only be captured once, by a plain capture that is not in an optional group.
+///
+///```ignore
+/// use prse::{parse, Parse};
+///
+/// #[derive(Debug, Parse)]
+/// #[prse = "{date} {time}"]
+/// #[prse(sub(date = "{year}-{month}-{day}", time = "{hour}:{minute}"))]
+/// struct Timestamp {
+///     year: u16,
+///     month: u8,
+///     day: u8,
+///     hour: u8,
+///     minute: u8,
+/// }
+///
+/// let t: Timestamp = parse!("2021-01-02 10:30", "{}");
+/// assert_eq!((t.year, t.month, t.day, t.hour, t.minute), (2021, 1, 2, 10, 30));
+///```
+///
+/// Fieldless enums can instead be parsed from their discriminant by adding `#[prse(from_repr)]` to the enum.
+/// The integer is parsed using the type of the enum's `repr` (or `isize` if there is none),
+/// and an integer that does not match any variant results in [`ParseError::Other`](enum.ParseError.html).
+/// ```ignore
+/// use prse::{parse, try_parse, Parse};
+///
+/// #[derive(Debug, PartialEq, Parse)]
+/// #[prse(from_repr)]
+/// #[repr(u8)]
+/// enum Status {
+///     Idle = 0,
+///     Running = 1,
+///     Failed = 4,
+/// }
+///
+/// let status: Status = parse!("status=4", "status={}");
+/// assert_eq!(status, Status::Failed);
+/// let unknown: Result<Status, _> = try_parse!("status=2", "status={}");
+/// assert!(unknown.is_err());
+/// ```
+///
+/// To pick a variant by the range an integer falls in add `#[prse(range)]` to the enum and a range such as
+/// `#[prse(range = "200..300")]` to each variant. Either bound can be left out and the end can be
+/// made inclusive with `..=`. The integer is parsed in the same way as `from_repr`, the first
+/// variant whose range contains it is returned and an integer outside every range results in
+/// [`ParseError::Other`](enum.ParseError.html).
+/// ```ignore
+/// use prse::{parse, try_parse, Parse};
+///
+/// #[derive(Debug, PartialEq, Parse)]
+/// #[prse(range)]
+/// #[repr(u16)]
+/// enum StatusClass {
+///     #[prse(range = "100..200")]
+///     Informational,
+///     #[prse(range = "200..300")]
+///     Success,
+///     #[prse(range = "300..=399")]
+///     Redirection,
+///     #[prse(range = "400..600")]
+///     Error,
+/// }
+///
+/// let class: StatusClass = parse!("HTTP/1.1 404", "HTTP/1.1 {}");
+/// assert_eq!(class, StatusClass::Error);
+/// let unknown: Result<StatusClass, _> = try_parse!("HTTP/1.1 700", "HTTP/1.1 {}");
+/// assert!(unknown.is_err());
+/// ```
+///
+/// Structs of bools can be parsed from a whitespace separated list of flags by adding `#[prse(flags)]` to the struct.
+/// Each flag sets the field of the same name to true, the fields of missing flags are false, and a flag
+/// that is not the name of a field results in [`ParseError::Other`](enum.ParseError.html).
+/// Use `#[prse(flags(ignore_unknown))]` to skip unknown flags instead.
+/// ```ignore
+/// use prse::{parse, try_parse, Parse};
+///
+/// #[derive(Debug, PartialEq, Parse)]
+/// #[prse(flags)]
+/// struct Options {
+///     verbose: bool,
+///     debug: bool,
+///     quiet: bool,
+/// }
+///
+/// let options: Options = parse!("flags: verbose debug", "flags: {}");
+/// assert_eq!(options, Options { verbose: true, debug: true, quiet: false });
+/// let unknown: Result<Options, _> = try_parse!("flags: verbose color", "flags: {}");
+/// assert!(unknown.is_err());
+/// ```
 /// If no prse attributes are found, it will use your [`FromStr`](core::str::FromStr) implementation.
 /// ```ignore
 /// use prse::{parse, Parse};
@@ -234,3 +861,125 @@ pub fn derive_parse(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as Derive);
     input.into_token_stream().into()
 }
+
+/// Like [`parse!`] but returns the fallback instead of panicking if the input cannot be parsed.
+///
+/// The fallback must have the same type as the value [`parse!`] would have returned and
+/// is only evaluated if parsing fails, the error itself is discarded.
+/// Named variables are only assigned when parsing succeeds.
+/// ```ignore
+/// let port: u16 = parse_or!("port=http", "port={}", 80);
+/// assert_eq!(port, 80);
+///
+/// let port: u16 = parse_or!("port=8080", "port={}", unreachable!());
+/// assert_eq!(port, 8080);
+/// ```
+#[proc_macro]
+pub fn parse_or(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input with ParseInvocation::parse_fallback);
+    input.to_token_stream().into()
+}
+
+/// Returns the index of the first pattern that matches the input, or [`None`] if none of them do.
+///
+/// The literals, counts, shapes and brackets of the patterns are matched, but the captures are not parsed
+/// into any type, so this can be used to pick which typed parser to run without parsing the input twice.
+/// The patterns are given as a list of string literals.
+/// ```ignore
+/// let commands = ["move 3 from 1 to 2", "rotate 90", "jump"];
+/// let kinds = commands.map(|c| which_matches!(c, ["move {} from {} to {}", "rotate {}", "stop"]));
+///
+/// assert_eq!(kinds, [Some(0), Some(1), None]);
+/// ```
+#[proc_macro]
+pub fn which_matches(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as WhichMatches);
+    input.to_token_stream().into()
+}
+
+/// Parses every element of a repetition and passes it to the callback, without collecting them.
+///
+/// The pattern must contain a single unnamed Vec or Iterator repetition such as `{:,:}`,
+/// without the alloc feature only the Iterator repetition `{:,:0}` can be used.
+/// Elements are parsed one at a time, so the memory used does not grow with the number of elements
+/// and no allocation is needed. Parsing stops at the first element that fails to parse, returning a
+/// [`Result`](https://doc.rust-lang.org/stable/std/result/enum.Result.html) of `()`
+/// with [`ParseError`](enum.ParseError.html) as the error type.
+/// ```ignore
+/// let mut total = 0;
+/// parse_for_each!("values: 1,2,3", "values: {:,:}", |n: u32| total += n).unwrap();
+/// assert_eq!(total, 6);
+///
+/// let mut seen = Vec::new();
+/// let result = parse_for_each!("1,x,3", "{:,:}", |n: u32| seen.push(n));
+/// assert!(result.is_err());
+/// assert_eq!(seen, [1]);
+/// ```
+#[proc_macro]
+pub fn parse_for_each(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input with ParseInvocation::parse_for_each);
+    input.to_token_stream().into()
+}
+
+/// Parses every element of a repetition into a `Vec` that always has the given length.
+///
+/// The pattern must contain a single unnamed Vec or Iterator repetition such as `{:,:}`, followed by
+/// the length of the `Vec` and the default value that pads it when the input has fewer elements.
+/// By default the elements past the length are dropped without being parsed, an optional
+/// [`Overflow`](enum.Overflow.html) can be given last to choose what happens to them instead.
+/// Like [`parse!`], this panics if the input does not match or an element fails to parse,
+/// this macro is only available with the alloc feature.
+/// ```ignore
+/// use prse::{parse_padded, Overflow};
+///
+/// let features: Vec<f64> = parse_padded!("0.5,1.5", "{:,:}", 4, 0.0);
+/// assert_eq!(features, [0.5, 1.5, 0.0, 0.0]);
+///
+/// let features: Vec<f64> = parse_padded!("1,2,3,4,5", "{:,:}", 4, 0.0);
+/// assert_eq!(features, [1.0, 2.0, 3.0, 4.0]);
+///
+/// let features: Vec<f64> = parse_padded!("1,2,3,4", "{:,:}", 4, 0.0, Overflow::Error);
+/// assert_eq!(features, [1.0, 2.0, 3.0, 4.0]);
+/// ```
+#[proc_macro]
+pub fn parse_padded(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input with ParseInvocation::parse_padded);
+    input.to_token_stream().into()
+}
+
+/// Returns a [`Result`](https://doc.rust-lang.org/stable/std/result/enum.Result.html) instead of unwrapping like [`parse_padded!`].
+///
+/// For more information please look at [`parse_padded!`] and [`try_parse!`].
+/// ```ignore
+/// use prse::{try_parse_padded, Overflow};
+///
+/// let features: Result<Vec<u8>, _> = try_parse_padded!("1,2,3", "{:,:}", 2, 0, Overflow::Error);
+/// assert!(features.is_err());
+///
+/// let features: Result<Vec<u8>, _> = try_parse_padded!("1,x", "{:,:}", 3, 0);
+/// assert!(features.is_err());
+/// ```
+#[proc_macro]
+pub fn try_parse_padded(input: TokenStream) -> TokenStream {
+    let mut input = parse_macro_input!(input with ParseInvocation::parse_padded);
+    input.try_parse = true;
+    input.to_token_stream().into()
+}
+
+/// Returns the [`PatternLiterals`](struct.PatternLiterals.html) of a pattern, the literal text between its captures.
+///
+/// Nothing is parsed, the literals can be used to hint at what the input should look like, such as
+/// the text to autocomplete next. Patterns with pattern flags or optional groups are not supported,
+/// as their literals do not always match the same text.
+/// ```ignore
+/// use prse::pattern_literals;
+///
+/// let literals = pattern_literals!("({}, {})");
+/// assert!(literals.literals().eq(["(", ", ", ")"]));
+/// assert_eq!(literals.next_expected("(1"), Some(", "));
+/// ```
+#[proc_macro]
+pub fn pattern_literals(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as PatternLiterals);
+    input.to_token_stream().into()
+}
@@ -0,0 +1,536 @@
+use proc_macro2::{Span, TokenStream};
+use syn::LitStr;
+
+/// The radix used to parse an integer capture.
+#[derive(Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Debug)]
+pub enum Radix {
+    /// Detect the radix from the `0x`, `0o` or `0b` prefix.
+    Auto,
+    Binary,
+    Octal,
+    Hex,
+}
+
+/// Which side of a capture whitespace is trimmed from.
+#[derive(Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Debug)]
+pub enum Trim {
+    Both,
+    Start,
+    End,
+    /// Never trim, used to opt out of the `t` flag.
+    Off,
+}
+
+/// What a length modifier counts in place of parsing the capture.
+#[derive(Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Debug)]
+pub enum Length {
+    Bytes,
+    Chars,
+}
+
+/// A format the captured text is checked against before it is parsed.
+#[derive(Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Debug)]
+pub enum Format {
+    Host,
+    Email,
+}
+
+/// The keys of a map modifier with the source of the expression each of them maps to.
+pub type Mapping = Vec<(String, String)>;
+
+/// The modifiers that change how a plain capture is parsed, `{<var>:<modifier>,...}`.
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Debug, Default)]
+pub struct Modifiers {
+    pub radix: Option<Radix>,
+    pub trim: Option<Trim>,
+    /// A doubled separator is part of the element instead of separating two elements.
+    pub doubled: bool,
+    /// A separator at the end of the input is followed by an empty element instead of ending the last one.
+    pub trailing: bool,
+    /// The number of elements a repetition can have before it returns an error, given by `max=<count>`.
+    pub max: Option<usize>,
+    /// The digits of the integer can be grouped using underscores.
+    pub grouped: bool,
+    /// The shape the captured text must match before it is parsed, given after a `~`.
+    pub shape: Option<String>,
+    /// The capture is replaced by its length, so it is not parsed at all.
+    pub length: Option<Length>,
+    /// The format the captured text must look like before it is parsed.
+    pub format: Option<Format>,
+    /// The keys the whole capture is matched against and the expressions they map to, given by `map(<key> => <value>, ...)`.
+    pub mapping: Option<Mapping>,
+    /// The text the capture is parsed from to get the value it must be equal to, given by `== <value>`.
+    pub expected: Option<String>,
+    /// The text the capture is parsed from when its optional group is absent, given by `default=<value>`.
+    pub default: Option<String>,
+    /// Each element of a repetition is itself an array split on the separator, given by `[<sep>:<count>]`,
+    /// and the other modifiers apply to the elements of that array. The flag is set if it skips separators.
+    pub row: Option<(String, u8, bool)>,
+}
+
+impl Modifiers {
+    pub fn parse(input: &str, input_span: Span) -> syn::Result<Self> {
+        let mut modifiers = Modifiers::default();
+        for modifier in split_modifiers(input) {
+            let modifier = modifier.trim_start();
+            // The shape and the expected value take the rest of the modifiers, so they are kept as they are written.
+            if let Some(shape) = modifier.strip_prefix('~') {
+                validate_shape(shape, input_span)?;
+                set_once(
+                    &mut modifiers.shape,
+                    String::from(shape),
+                    "shape",
+                    input_span,
+                )?;
+                continue;
+            }
+            if let Some(expected) = modifier.strip_prefix("==") {
+                let expected = parse_expected(expected.trim(), input_span)?;
+                set_once(&mut modifiers.expected, expected, "==", input_span)?;
+                continue;
+            }
+            let modifier = modifier.trim_end();
+            match modifier {
+                "auto" => set_once(&mut modifiers.radix, Radix::Auto, "radix", input_span)?,
+                "b" => set_once(&mut modifiers.radix, Radix::Binary, "radix", input_span)?,
+                "o" => set_once(&mut modifiers.radix, Radix::Octal, "radix", input_span)?,
+                "x" => set_once(&mut modifiers.radix, Radix::Hex, "radix", input_span)?,
+                "trim" => set_once(&mut modifiers.trim, Trim::Both, "trim", input_span)?,
+                "ltrim" => set_once(&mut modifiers.trim, Trim::Start, "trim", input_span)?,
+                "rtrim" => set_once(&mut modifiers.trim, Trim::End, "trim", input_span)?,
+                "notrim" => set_once(&mut modifiers.trim, Trim::Off, "trim", input_span)?,
+                "len" => set_once(&mut modifiers.length, Length::Bytes, "length", input_span)?,
+                "clen" if !cfg!(feature = "unicode") => {
+                    return Err(syn::Error::new(
+                        input_span,
+                        "unicode feature is required to count the chars of a capture.",
+                    ))
+                }
+                "clen" => set_once(&mut modifiers.length, Length::Chars, "length", input_span)?,
+                "host" => set_once(&mut modifiers.format, Format::Host, "format", input_span)?,
+                "email" => set_once(&mut modifiers.format, Format::Email, "format", input_span)?,
+                "_" if !modifiers.grouped => modifiers.grouped = true,
+                "_" => {
+                    return Err(syn::Error::new(
+                        input_span,
+                        "only a single _ modifier can be used per capture.",
+                    ))
+                }
+                "dd" if !modifiers.doubled => modifiers.doubled = true,
+                "dd" => {
+                    return Err(syn::Error::new(
+                        input_span,
+                        "only a single dd modifier can be used per capture.",
+                    ))
+                }
+                _ if modifier.starts_with("max=") => {
+                    let max = modifier["max=".len()..].trim();
+                    let Ok(max) = max.parse() else {
+                        return Err(syn::Error::new(
+                            input_span,
+                            format!("expected a number of elements after the max modifier but found {max:?}."),
+                        ));
+                    };
+                    set_once(&mut modifiers.max, max, "max", input_span)?
+                }
+                _ if modifier.starts_with("default=") => {
+                    let default = String::from(&modifier["default=".len()..]);
+                    set_once(&mut modifiers.default, default, "default", input_span)?
+                }
+                _ if modifier.starts_with("map(") => {
+                    let mapping = parse_mapping(modifier, input_span)?;
+                    set_once(&mut modifiers.mapping, mapping, "map", input_span)?
+                }
+                "trailing" if !modifiers.trailing => modifiers.trailing = true,
+                "trailing" => {
+                    return Err(syn::Error::new(
+                        input_span,
+                        "only a single trailing modifier can be used per capture.",
+                    ))
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        input_span,
+                        format!("unknown modifier {modifier:?}, expected one of auto, b, o, x, trim, ltrim, rtrim, notrim, len, clen, host, email, map, _, dd, trailing, max=<count>, default=<value>, a ~ followed by a shape or an == followed by a value."),
+                    ))
+                }
+            }
+        }
+        if modifiers.length.is_some() && (modifiers.radix.is_some() || modifiers.grouped) {
+            return Err(syn::Error::new(
+                input_span,
+                "a length modifier cannot be used with a radix or the _ modifier.",
+            ));
+        }
+        if modifiers.length.is_some() && modifiers.expected.is_some() {
+            return Err(syn::Error::new(
+                input_span,
+                "a length modifier cannot be used with the == modifier.",
+            ));
+        }
+        if modifiers.mapping.is_some()
+            && modifiers
+                != (Modifiers {
+                    trim: modifiers.trim,
+                    mapping: modifiers.mapping.clone(),
+                    default: modifiers.default.clone(),
+                    ..Modifiers::default()
+                })
+        {
+            return Err(syn::Error::new(
+                input_span,
+                "the map modifier can only be combined with a trim or default modifier.",
+            ));
+        }
+        Ok(modifiers)
+    }
+
+    /// The trait bound the captured type must satisfy.
+    ///
+    /// Elements with doubled separators are parsed from a temporary string, so they cannot borrow from the input.
+    /// An expected value is compared to the parsed capture, so the type must also implement `PartialEq`.
+    pub fn bound(&self) -> TokenStream {
+        let bound = match self.radix {
+            Some(_) => quote!(::prse::ParseRadix),
+            None if self.grouped => quote!(::prse::ParseRadix),
+            None if self.doubled => quote!(for<'b> Parse<'b>),
+            None => quote!(Parse<'a>),
+        };
+        match self.expected {
+            Some(_) => quote!(#bound + ::core::cmp::PartialEq),
+            None => bound,
+        }
+    }
+
+    /// Generates an expression that parses `__prse_parse` into a `Result<T, ParseError>`.
+    pub fn gen_parse(&self) -> TokenStream {
+        let trim = self.trim.and_then(|trim| match trim {
+            Trim::Both => Some(quote!(let __prse_parse = __prse_parse.trim();)),
+            Trim::Start => Some(quote!(let __prse_parse = __prse_parse.trim_start();)),
+            Trim::End => Some(quote!(let __prse_parse = __prse_parse.trim_end();)),
+            Trim::Off => None,
+        });
+        let radix = match self.radix {
+            None if !self.grouped => None,
+            // Grouped digits without a radix modifier are always decimal.
+            None => Some(quote!(::core::option::Option::Some(10))),
+            Some(Radix::Auto) => Some(quote!(::core::option::Option::None)),
+            Some(Radix::Binary) => Some(quote!(::core::option::Option::Some(2))),
+            Some(Radix::Octal) => Some(quote!(::core::option::Option::Some(8))),
+            Some(Radix::Hex) => Some(quote!(::core::option::Option::Some(16))),
+        };
+        if let Some(mapping) = &self.mapping {
+            let keys: Vec<&str> = mapping.iter().map(|(key, _)| key.as_str()).collect();
+            let indices = 0..keys.len();
+            return quote!({
+                #trim
+                match __prse_parse {
+                    #(#keys => ::core::result::Result::<usize, ::prse::ParseError>::Ok(#indices),)*
+                    _ => ::core::result::Result::Err(::prse::__private::unmapped_key(__prse_parse, &[#(#keys),*])),
+                }
+            });
+        }
+        let parse = match (self.length, radix) {
+            (Some(Length::Bytes), _) => {
+                quote!(::core::result::Result::<usize, ::prse::ParseError>::Ok(
+                    __prse_parse.len()
+                ))
+            }
+            (Some(Length::Chars), _) => quote! {
+                ::core::result::Result::<usize, ::prse::ParseError>::Ok(__prse_parse.chars().count())
+            },
+            (None, None) => quote!(::prse::ExtParseStr::lending_parse(__prse_parse)),
+            (None, Some(radix)) if self.grouped => {
+                quote!(::prse::__private::parse_grouped_radix(__prse_parse, #radix))
+            }
+            (None, Some(radix)) => quote!(::prse::__private::parse_radix(__prse_parse, #radix)),
+        };
+        // The expected value is parsed in the same way as the capture, without checking its shape or format.
+        let expected = self.expected.as_ref().map(|expected| {
+            quote! {
+                |__prse_value| ::prse::__private::check_expected(__prse_value, __prse_parse, #expected, |__prse_parse| #parse)
+            }
+        });
+        let parse = match &self.shape {
+            None => parse,
+            Some(shape) => quote! {
+                ::prse::__private::check_shape(__prse_parse, #shape).and_then(|()| #parse)
+            },
+        };
+        let parse = match self.format {
+            None => parse,
+            Some(Format::Host) => quote! {
+                ::prse::__private::check_host(__prse_parse).and_then(|()| #parse)
+            },
+            Some(Format::Email) => quote! {
+                ::prse::__private::check_email(__prse_parse).and_then(|()| #parse)
+            },
+        };
+        let parse = match expected {
+            None => parse,
+            Some(expected) => quote!(#parse.and_then(#expected)),
+        };
+        quote!({
+            #trim
+            #parse
+        })
+    }
+}
+
+/// How literals are matched when pattern flags are used.
+#[derive(Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Debug, Default)]
+pub struct LiteralFlags {
+    pub case_insensitive: bool,
+    pub flexible_whitespace: bool,
+    /// Splits a capture on the last match of the literal after it instead of the first.
+    pub right_greedy: bool,
+}
+
+/// The flags given at the start of a pattern, `(?<flags>)`, that apply to the whole pattern.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+pub struct PatternFlags {
+    pub literals: LiteralFlags,
+    pub trim: bool,
+    /// Skips the whitespace at the start and end of the input, as well as implying the w and t flags.
+    pub columns: bool,
+}
+
+impl PatternFlags {
+    /// Splits the flags off the start of the pattern, returning the rest of the pattern.
+    pub fn parse(input: &str, input_span: Span) -> syn::Result<(Self, &str)> {
+        let mut flags = PatternFlags::default();
+        let Some((cluster, rest)) = input.strip_prefix("(?").and_then(|s| s.split_once(')')) else {
+            return Ok((flags, input));
+        };
+        for flag in cluster.chars() {
+            let flag = match flag {
+                'i' => &mut flags.literals.case_insensitive,
+                'r' => &mut flags.literals.right_greedy,
+                'w' => &mut flags.literals.flexible_whitespace,
+                't' => &mut flags.trim,
+                'c' => &mut flags.columns,
+                _ => {
+                    return Err(syn::Error::new(
+                        input_span,
+                        format!("unknown pattern flag {flag:?}, expected one of c, i, r, t or w."),
+                    ))
+                }
+            };
+            if std::mem::replace(flag, true) {
+                return Err(syn::Error::new(
+                    input_span,
+                    "each pattern flag can only be given once.",
+                ));
+            }
+        }
+        if flags.columns {
+            flags.literals.flexible_whitespace = true;
+            flags.trim = true;
+        }
+        Ok((flags, rest))
+    }
+}
+
+/// Splits the modifiers of a capture on the commas between them.
+///
+/// The commas inside the brackets and string literals of a `map(...)` modifier do not split it,
+/// and a shape or an expected value takes everything after it as it is always the last modifier.
+fn split_modifiers(input: &str) -> Vec<&str> {
+    let mut modifiers = vec![];
+    let mut rest = input;
+    loop {
+        let start = rest.trim_start();
+        if start.starts_with('~') || start.starts_with("==") {
+            modifiers.push(rest);
+            return modifiers;
+        }
+        let end = if start.starts_with("map(") {
+            mapping_end(rest).and_then(|end| rest[end..].find(',').map(|comma| end + comma))
+        } else {
+            rest.find(',')
+        };
+        match end {
+            Some(end) => {
+                modifiers.push(&rest[..end]);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                modifiers.push(rest);
+                return modifiers;
+            }
+        }
+    }
+}
+
+/// Returns the index right after the `)` closing the first `map(` of `input`, skipping the brackets and
+/// string literals of its values.
+fn mapping_end(input: &str) -> Option<usize> {
+    let start = input.find("map(")? + "map(".len();
+    let (mut depth, mut in_string, mut escaped) = (0_usize, false, false);
+    for (idx, c) in input[start..].char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            _ if in_string => {}
+            '(' | '[' | '{' => depth += 1,
+            ')' if depth == 0 => return Some(start + idx + 1),
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Splits `input` on the commas that are outside of brackets and string literals.
+fn split_values(input: &str) -> Vec<&str> {
+    let mut values = vec![];
+    let (mut depth, mut in_string, mut escaped) = (0_usize, false, false);
+    let mut value_start = 0;
+    for (idx, c) in input.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            _ if in_string => {}
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                values.push(&input[value_start..idx]);
+                value_start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    values.push(&input[value_start..]);
+    values
+}
+
+/// Parses a `map(<key> => <value>, ...)` modifier.
+///
+/// The keys are trimmed and every value is kept as the source of an expression.
+fn parse_mapping(modifier: &str, input_span: Span) -> syn::Result<Mapping> {
+    let error = |message: &str| syn::Error::new(input_span, message);
+    let contents = match mapping_end(modifier) {
+        Some(end) if end == modifier.len() => &modifier["map(".len()..end - 1],
+        Some(_) => {
+            return Err(error(
+                "expected a comma between the map modifier and the next modifier.",
+            ))
+        }
+        None => return Err(error("expected a ) to close the map modifier.")),
+    };
+    let mut mapping: Mapping = vec![];
+    for entry in split_values(contents)
+        .into_iter()
+        .filter(|entry| !entry.trim().is_empty())
+    {
+        let (key, value) = entry
+            .split_once("=>")
+            .map(|(key, value)| (key.trim(), value.trim()))
+            .filter(|(key, value)| !key.is_empty() && !value.is_empty())
+            .ok_or_else(|| {
+                error("every entry of the map modifier must be of the form <key> => <value>.")
+            })?;
+        if value.parse::<TokenStream>().is_err() {
+            return Err(error(&format!(
+                "invalid value {value:?} for the key {key:?} of the map modifier."
+            )));
+        }
+        if mapping.iter().any(|(k, _)| k == key) {
+            return Err(error(&format!(
+                "duplicate key {key:?} in the map modifier."
+            )));
+        }
+        mapping.push((String::from(key), String::from(value)));
+    }
+    if mapping.is_empty() {
+        return Err(error("the map modifier needs at least one key."));
+    }
+    Ok(mapping)
+}
+
+/// Parses the value after an `==` modifier, which is either a Rust string literal or taken as it is written.
+fn parse_expected(expected: &str, input_span: Span) -> syn::Result<String> {
+    match syn::parse_str::<LitStr>(expected) {
+        Ok(lit) => Ok(lit.value()),
+        Err(_) if expected.is_empty() => Err(syn::Error::new(
+            input_span,
+            "expected a value after the == modifier.",
+        )),
+        Err(_) => Ok(String::from(expected)),
+    }
+}
+
+/// Returns the index of the `:` that makes the part of a capture after its variable a repetition,
+/// `<sep>:<count>`, or `None` if it only holds modifiers.
+///
+/// The colons inside a `map(...)` modifier are part of its values, as are those after an `==` modifier
+/// when they are the last modifier and either have no other colon or are a string literal.
+pub fn repetition_colon(split: &str) -> Option<usize> {
+    let mut offset = 0;
+    for modifier in split_modifiers(split) {
+        let start = modifier.trim_start();
+        if let Some(expected) = start.strip_prefix("==") {
+            let expected = expected.trim();
+            if !expected.contains(':') || syn::parse_str::<LitStr>(expected).is_ok() {
+                return None;
+            }
+        }
+        let plain = match mapping_end(modifier) {
+            Some(end) if start.starts_with("map(") => &modifier[end..],
+            _ => modifier,
+        };
+        if let Some(colon) = plain.find(':') {
+            return Some(offset + (modifier.len() - plain.len()) + colon);
+        }
+        offset += modifier.len() + 1;
+    }
+    None
+}
+
+/// Checks that every `*` and `+` in a shape follows something it can repeat and that the shape does not end in a `\`.
+fn validate_shape(shape: &str, input_span: Span) -> syn::Result<()> {
+    if shape.is_empty() {
+        return Err(syn::Error::new(input_span, "a shape cannot be empty."));
+    }
+    let mut chars = shape.chars();
+    let mut can_repeat = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '*' | '+' if can_repeat => can_repeat = false,
+            '*' | '+' => {
+                return Err(syn::Error::new(
+                    input_span,
+                    format!(
+                    "a {c} in a shape must follow a char, \\d, \\w or ., use \\{c} to match a {c}."
+                ),
+                ))
+            }
+            '\\' if chars.next().is_none() => {
+                return Err(syn::Error::new(
+                    input_span,
+                    "a shape cannot end with a \\, use \\\\ to match a \\.",
+                ))
+            }
+            _ => can_repeat = true,
+        }
+    }
+    Ok(())
+}
+
+/// Sets a modifier that can only be given once per capture.
+fn set_once<T>(
+    modifier: &mut Option<T>,
+    value: T,
+    kind: &str,
+    input_span: Span,
+) -> syn::Result<()> {
+    if modifier.replace(value).is_some() {
+        return Err(syn::Error::new(
+            input_span,
+            format!("only a single {kind} modifier can be used per capture."),
+        ));
+    }
+    Ok(())
+}
@@ -1,5 +1,7 @@
-use crate::instructions::Instruction;
-use proc_macro2::{Ident, Span};
+use crate::instructions::{has_element_modifiers, separator_class, separator_set, Instruction};
+use crate::modifier::{repetition_colon, Modifiers};
+use proc_macro2::{Ident, Span, TokenStream};
+use syn::ext::IdentExt;
 use syn::parse::{Parse, ParseStream};
 use syn::{parse_str, LitInt};
 
@@ -16,6 +18,17 @@ impl Var {
             i.set_span(span)
         }
     }
+
+    /// The name of the capture given to the error context, if it is a named capture.
+    pub fn field_name(&self) -> TokenStream {
+        match self {
+            Var::Ident(ident) => {
+                let name = ident.unraw().to_string();
+                quote!(::core::option::Option::Some(#name))
+            }
+            _ => quote!(::core::option::Option::None),
+        }
+    }
 }
 
 impl Parse for Var {
@@ -46,22 +59,147 @@ impl Parse for Var {
 }
 
 pub fn parse_var(input: String, input_span: Span) -> syn::Result<Instruction> {
+    let rows = input
+        .split_once(':')
+        .filter(|(_, split)| repetition_colon(split).is_some())
+        .and_then(|(var, split)| Some((var, split_rows(split)?)));
+    if let Some((var, row)) = rows {
+        return parse_rows(var, row, input_span);
+    }
     match input.split_once(':') {
         Some((var, split)) => {
             let mut var: Var = parse_str(var)?;
             var.add_span(input_span);
-            if let Some((sep, num)) = split.rsplit_once(':') {
+            if let Some((sep, num, modifiers)) =
+                split_repetition(split).filter(|_| repetition_colon(split).is_some())
+            {
+                let modifiers = match modifiers {
+                    Some(modifiers) => Modifiers::parse(modifiers, input_span)?,
+                    None => Modifiers::default(),
+                };
+                if modifiers.mapping.is_some() {
+                    return Err(syn::Error::new(
+                        input_span,
+                        "the map modifier cannot be used on repetitions.",
+                    ));
+                }
+                if modifiers.expected.is_some() {
+                    return Err(syn::Error::new(
+                        input_span,
+                        "the == modifier cannot be used on repetitions.",
+                    ));
+                }
+                if modifiers.default.is_some() {
+                    return Err(syn::Error::new(
+                        input_span,
+                        "the default modifier cannot be used on repetitions.",
+                    ));
+                }
+                if modifiers.length.is_some() {
+                    return Err(syn::Error::new(
+                        input_span,
+                        "a length modifier cannot be used on repetitions.",
+                    ));
+                }
                 let (num, is_multi_sep) = num
                     .strip_prefix('!')
                     .map(|num| (num, true))
                     .unwrap_or((num, false));
 
+                if matches!(sep, "[]" | "[]+") {
+                    return Err(syn::Error::new(
+                        input_span,
+                        "a separator set needs at least one char, use \\[ for a separator that starts with a [.",
+                    ));
+                }
+                if separator_class(sep).is_some() && (is_multi_sep || modifiers.doubled) {
+                    return Err(syn::Error::new(
+                        input_span,
+                        "a separator class already matches any run of its chars, so it cannot skip separators or use the dd modifier.",
+                    ));
+                }
+                if num.trim() == "count" {
+                    let error = if sep.is_empty() {
+                        Some("a count needs a literal to count.")
+                    } else if is_multi_sep || modifiers != Modifiers::default() {
+                        Some("a count cannot skip separators or use modifiers.")
+                    } else {
+                        None
+                    };
+                    if let Some(error) = error {
+                        return Err(syn::Error::new(input_span, error));
+                    }
+                    return Ok(Instruction::Count(var, String::from(sep)));
+                }
+                if num.trim() == "map" {
+                    let error = if sep.is_empty() {
+                        Some("a map needs a separator between its entries.")
+                    } else if modifiers
+                        != (Modifiers {
+                            max: modifiers.max,
+                            ..Modifiers::default()
+                        })
+                    {
+                        Some("a map can only use the max modifier.")
+                    } else {
+                        None
+                    };
+                    if let Some(error) = error {
+                        return Err(syn::Error::new(input_span, error));
+                    }
+                    return Ok(Instruction::MapParse(
+                        var,
+                        String::from(sep),
+                        is_multi_sep,
+                        modifiers.max,
+                    ));
+                }
+                if modifiers.trailing {
+                    let error = if sep.is_empty() {
+                        Some("the trailing modifier cannot be used with an empty separator.")
+                    } else if is_multi_sep {
+                        Some("the trailing modifier cannot be used while skipping separators.")
+                    } else if separator_class(sep).is_some() {
+                        Some("a separator class ignores the separators at the end of the input, so it cannot use the trailing modifier.")
+                    } else if modifiers.doubled {
+                        Some("the trailing modifier cannot be used with the dd modifier.")
+                    } else {
+                        None
+                    };
+                    if let Some(error) = error {
+                        return Err(syn::Error::new(input_span, error));
+                    }
+                }
                 if sep.is_empty() && is_multi_sep {
                     return Err(syn::Error::new(
                         input_span,
                         "skipping separators is not supported with char iterators.",
                     ));
                 }
+                if modifiers.doubled {
+                    let error = if sep.is_empty() {
+                        Some("the dd modifier cannot be used with an empty separator.")
+                    } else if is_multi_sep {
+                        Some("the dd modifier cannot be used while skipping separators.")
+                    } else if separator_set(sep).is_some() {
+                        Some("the dd modifier cannot be used with a separator set.")
+                    } else if num.trim() == "0" {
+                        Some("the dd modifier cannot be used with an iterator, consider using a Vec or an array instead.")
+                    } else {
+                        None
+                    };
+                    if let Some(error) = error {
+                        return Err(syn::Error::new(input_span, error));
+                    }
+                }
+
+                // An iterator is a ParseIter or ParseChars of its elements, which cannot hold the modifiers.
+                if num.trim() == "0" && has_element_modifiers(&modifiers) {
+                    return Err(syn::Error::new(
+                        input_span,
+                        "an iterator can only use the trailing and max modifiers, consider using a Vec or an array instead.",
+                    ));
+                }
 
                 Ok(if num.trim().is_empty() {
                     if !cfg!(feature = "alloc") {
@@ -70,11 +208,19 @@ pub fn parse_var(input: String, input_span: Span) -> syn::Result<Instruction> {
                             "alloc feature is required to parse into a Vec.",
                         ));
                     }
-                    Instruction::VecParse(var, String::from(sep), is_multi_sep)
+                    Instruction::VecParse(var, String::from(sep), is_multi_sep, modifiers)
                 } else {
                     match num.parse() {
-                        Ok(0_u8) => Instruction::IterParse(var, String::from(sep), is_multi_sep),
-                        Ok(x) => Instruction::MultiParse(var, String::from(sep), x, is_multi_sep),
+                        Ok(0_u8) => {
+                            Instruction::IterParse(var, String::from(sep), is_multi_sep, modifiers)
+                        }
+                        Ok(x) => Instruction::MultiParse(
+                            var,
+                            String::from(sep),
+                            x,
+                            is_multi_sep,
+                            modifiers,
+                        ),
                         Err(_) => {
                             return Err(syn::Error::new(
                                 input_span,
@@ -83,11 +229,46 @@ pub fn parse_var(input: String, input_span: Span) -> syn::Result<Instruction> {
                         }
                     }
                 })
-            } else {
+            } else if split.trim().is_empty() {
                 Err(syn::Error::new(
                     input_span,
                     "invalid multi parse, it must be of the form <var>:<sep>:<count>.",
                 ))
+            } else if let Some((open, close)) = bracket_pair(split) {
+                Ok(Instruction::Balanced(var, open, close))
+            } else if split.trim() == "c" {
+                Ok(Instruction::SingleChar(var))
+            } else if split.trim() == "line" {
+                Ok(Instruction::Line(var))
+            } else if split.trim() == "qe" {
+                if !cfg!(feature = "alloc") {
+                    return Err(syn::Error::new(
+                        input_span,
+                        "alloc feature is required to un-escape a quoted string.",
+                    ));
+                }
+                Ok(Instruction::Quoted(var))
+            } else {
+                let modifiers = Modifiers::parse(split, input_span)?;
+                if modifiers.doubled {
+                    return Err(syn::Error::new(
+                        input_span,
+                        "the dd modifier can only be used on repetitions.",
+                    ));
+                }
+                if modifiers.trailing {
+                    return Err(syn::Error::new(
+                        input_span,
+                        "the trailing modifier can only be used on repetitions.",
+                    ));
+                }
+                if modifiers.max.is_some() {
+                    return Err(syn::Error::new(
+                        input_span,
+                        "the max modifier can only be used on repetitions.",
+                    ));
+                }
+                Ok(Instruction::Modified(var, modifiers))
             }
         }
         None => {
@@ -98,11 +279,125 @@ pub fn parse_var(input: String, input_span: Span) -> syn::Result<Instruction> {
     }
 }
 
+/// Parses a repetition of rows split by [`split_rows`] into a Vec or an array with the row in its modifiers.
+#[allow(clippy::type_complexity)]
+fn parse_rows(
+    var: &str,
+    (sep, count, row_sep, columns, modifiers): (&str, &str, &str, &str, Option<&str>),
+    input_span: Span,
+) -> syn::Result<Instruction> {
+    let rows = count.strip_prefix('!').unwrap_or(count).trim();
+    if !matches!(rows, "count" | "map") && !rows.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(syn::Error::new(
+            input_span,
+            format!("expected a number of rows but found {count}, use an empty count for a separator containing a colon."),
+        ));
+    }
+    // A row is checked like an array of its elements, so it has the same separators and errors.
+    let row = match columns.strip_prefix('!').unwrap_or(columns).trim().parse() {
+        Ok(1_u8..) => parse_var(format!(":{row_sep}:{columns}"), input_span)?,
+        _ => {
+            return Err(syn::Error::new(
+                input_span,
+                format!("expected a number of columns between 1 and 255 but found {columns}."),
+            ));
+        }
+    };
+    let Instruction::MultiParse(_, row_sep, columns, is_multi_row, _) = row else {
+        unreachable!("a row with a count is always an array")
+    };
+    let repetition = match modifiers {
+        Some(modifiers) => format!("{var}:{sep}:{count}:{modifiers}"),
+        None => format!("{var}:{sep}:{count}"),
+    };
+    let error = match parse_var(repetition, input_span)? {
+        Instruction::VecParse(var, sep, is_multi, modifiers) if !modifiers.doubled => {
+            let row = Some((row_sep, columns, is_multi_row));
+            return Ok(Instruction::VecParse(
+                var,
+                sep,
+                is_multi,
+                Modifiers { row, ..modifiers },
+            ));
+        }
+        Instruction::MultiParse(var, sep, count, is_multi, modifiers) if !modifiers.doubled => {
+            let row = Some((row_sep, columns, is_multi_row));
+            return Ok(Instruction::MultiParse(
+                var,
+                sep,
+                count,
+                is_multi,
+                Modifiers { row, ..modifiers },
+            ));
+        }
+        Instruction::VecParse(..) | Instruction::MultiParse(..) => {
+            "the dd modifier cannot be used with rows."
+        }
+        Instruction::IterParse(..) => {
+            "rows cannot be used with an iterator, use a Vec or an array instead."
+        }
+        _ => "rows can only be used with a Vec or an array.",
+    };
+    Err(syn::Error::new(input_span, error))
+}
+
+/// Returns the opening and closing bracket of a balanced capture such as `{:()}`.
+///
+/// Any two different punctuation chars can be used, other than those used to separate modifiers.
+fn bracket_pair(s: &str) -> Option<(char, char)> {
+    let mut chars = s.chars();
+    let (open, close) = (chars.next()?, chars.next()?);
+    let is_bracket = |c: char| c.is_ascii_punctuation() && !matches!(c, ',' | '_' | ':');
+    (chars.next().is_none() && open != close && is_bracket(open) && is_bracket(close))
+        .then_some((open, close))
+}
+
+/// Splits a repetition of rows, `<sep>:<count>:[<row sep>:<columns>]:<modifiers>`, into the separator and
+/// count of the repetition, the separator and number of columns of the row and the element modifiers.
+///
+/// The count can be left out for a Vec, `<sep>:[<row sep>:<columns>]`, and the modifiers are optional.
+/// The count is whatever follows the last colon before the row, so a separator containing a colon needs an
+/// empty count, `<sep>::[<row sep>:<columns>]`, and anything else is rejected as a count.
+#[allow(clippy::type_complexity)]
+fn split_rows(split: &str) -> Option<(&str, &str, &str, &str, Option<&str>)> {
+    let start = split.find(":[")?;
+    let row = &split[start + 2..];
+    let end = row
+        .match_indices(']')
+        .map(|(idx, _)| idx)
+        .find(|&idx| row[idx + 1..].is_empty() || row[idx + 1..].starts_with(':'))?;
+    let (row_sep, columns) = row[..end].rsplit_once(':')?;
+    let modifiers = row[end + 1..].strip_prefix(':');
+    let (sep, count) = split[..start]
+        .rsplit_once(':')
+        .unwrap_or((&split[..start], ""));
+    Some((sep, count, row_sep, columns, modifiers))
+}
+
+/// Splits the part of a repetition after the variable into its separator, count and element modifiers.
+///
+/// The modifiers are given after the count, `<sep>:<count>:<modifiers>`, and are only split off if the
+/// last part is not a count so that separators containing colons keep working.
+/// A Vec with doubled separators can also be written without the empty count, `<sep>:dd`.
+fn split_repetition(split: &str) -> Option<(&str, &str, Option<&str>)> {
+    let (rest, last) = split.rsplit_once(':')?;
+    let count = last.strip_prefix('!').unwrap_or(last).trim();
+    if count.bytes().all(|b| b.is_ascii_digit()) {
+        return Some((rest, last, None));
+    }
+    match rest.rsplit_once(':') {
+        Some((sep, num)) => Some((sep, num, Some(last))),
+        None if last.trim() == "dd" => Some((rest, "", Some(last))),
+        None => Some((rest, last, None)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use proc_macro2::Span;
 
     use crate::instructions::Instructions;
+    use crate::modifier::{Format, Length, LiteralFlags, Modifiers, Radix, Trim};
 
     #[test]
     fn test_instruction_pass() {
@@ -117,26 +412,73 @@ mod tests {
             ("{}{{{}}}{}", vec![Parse(Implied), Lit("{".into()), Parse(Implied), Lit("}".into()), Parse(Implied)]),
             (" {}{{:}}}}{} ", vec![Lit(" ".into()), Parse(Implied), Lit("{:}}".into()), Parse(Implied), Lit(" ".into())]),
             (" {} {}}}{}", vec![Lit(" ".into()), Parse(Implied), Lit(" ".into()), Parse(Implied), Lit("}".into()), Parse(Implied)]),
-            ("{:}}:}", vec![VecParse(Implied, "}".into(), false)]),
-            ("{:{{}}:}", vec![VecParse(Implied, "{}".into(), false)]),
-            ("{:{{}}: }", vec![VecParse(Implied, "{}".into(), false)]),
+            ("{:}}:}", vec![VecParse(Implied, "}".into(), false, Modifiers::default())]),
+            ("{:{{}}:}", vec![VecParse(Implied, "{}".into(), false, Modifiers::default())]),
+            ("{:{{}}: }", vec![VecParse(Implied, "{}".into(), false, Modifiers::default())]),
             ("{hello}", vec![Parse(Ident(syn::Ident::new("hello", Span::call_site())))]),
-            ("{:,:5}", vec![MultiParse(Implied, ",".into(), 5, false)]),
-            ("{:,:0}", vec![IterParse(Implied, ",".into(), false)]),
-            ("{:,:}", vec![VecParse(Implied, ",".into(), false)]),
-            ("{:,::1}", vec![MultiParse(Implied, ",:".into(), 1, false)]),
-            ("{:,::0}", vec![IterParse(Implied, ",:".into(), false)]),
-            ("{:,::}", vec![VecParse(Implied, ",:".into(), false)]),
-            ("{::,::85}", vec![MultiParse(Implied, ":,:".into(), 85, false)]),
-            ("{::,::0}", vec![IterParse(Implied, ":,:".into(), false)]),
-            ("{::,::}", vec![VecParse(Implied, ":,:".into(), false)]),
-            ("{::}", vec![VecParse(Implied, "".into(), false)]),
+            ("{:,:5}", vec![MultiParse(Implied, ",".into(), 5, false, Modifiers::default())]),
+            ("{:,:0}", vec![IterParse(Implied, ",".into(), false, Modifiers::default())]),
+            ("{:,:}", vec![VecParse(Implied, ",".into(), false, Modifiers::default())]),
+            ("{:,::1}", vec![MultiParse(Implied, ",:".into(), 1, false, Modifiers::default())]),
+            ("{:,::0}", vec![IterParse(Implied, ",:".into(), false, Modifiers::default())]),
+            ("{:,::}", vec![VecParse(Implied, ",:".into(), false, Modifiers::default())]),
+            ("{::,::85}", vec![MultiParse(Implied, ":,:".into(), 85, false, Modifiers::default())]),
+            ("{::,::0}", vec![IterParse(Implied, ":,:".into(), false, Modifiers::default())]),
+            ("{::,::}", vec![VecParse(Implied, ":,:".into(), false, Modifiers::default())]),
+            ("{:#:count} ", vec![Count(Implied, "#".into()), Lit(" ".into())]),
+            ("{: :!map}", vec![MapParse(Implied, " ".into(), true, None)]),
+            ("{:;:map:max=10}", vec![MapParse(Implied, ";".into(), false, Some(10))]),
+            ("{:,::max=3,x}", vec![VecParse(Implied, ",".into(), false, Modifiers { radix: Some(Radix::Hex), max: Some(3), ..Modifiers::default() })]),
+            ("{}{:()}", vec![Parse(Implied), Balanced(Implied, '(', ')')]),
+            ("{::}", vec![VecParse(Implied, "".into(), false, Modifiers::default())]),
             ("{ 0  }", vec![Parse(Position(0))]),
             ("{1} {0}", vec![Parse(Position(1)), Lit(" ".into()), Parse(Position(0))]),
             ("{0} {  hiya }", vec![Parse(Position(0)), Lit(" ".into()), Parse(Ident(syn::Ident::new("hiya", Span::call_site())))]),
-            ("{:-:!}", vec![VecParse(Implied, "-".into(), true)]),
-            ("{:!:!0}", vec![IterParse(Implied, "!".into(), true)]),
-            ("{:!:!2}", vec![MultiParse(Implied, "!".into(), 2, true)]),
+            ("{:-:!}", vec![VecParse(Implied, "-".into(), true, Modifiers::default())]),
+            ("{:!:!0}", vec![IterParse(Implied, "!".into(), true, Modifiers::default())]),
+            ("{:!:!2}", vec![MultiParse(Implied, "!".into(), 2, true, Modifiers::default())]),
+            ("{a} {a}", vec![Parse(Ident(syn::Ident::new("a", Span::call_site()))), Lit(" ".into()), BackRef(syn::Ident::new("a", Span::call_site()), 0)]),
+            ("{a}-{b}-{a}", vec![Parse(Ident(syn::Ident::new("a", Span::call_site()))), Lit("-".into()), Parse(Ident(syn::Ident::new("b", Span::call_site()))), Lit("-".into()), BackRef(syn::Ident::new("a", Span::call_site()), 0)]),
+            ("{:x}", vec![Modified(Implied, Modifiers { radix: Some(Radix::Hex), ..Modifiers::default() })]),
+            ("{n: auto }", vec![Modified(Ident(syn::Ident::new("n", Span::call_site())), Modifiers { radix: Some(Radix::Auto), ..Modifiers::default() })]),
+            ("{0:b} {1:o}", vec![Modified(Position(0), Modifiers { radix: Some(Radix::Binary), ..Modifiers::default() }), Lit(" ".into()), Modified(Position(1), Modifiers { radix: Some(Radix::Octal), ..Modifiers::default() })]),
+            ("{:rtrim,x}", vec![Modified(Implied, Modifiers { radix: Some(Radix::Hex), trim: Some(Trim::End), ..Modifiers::default() })]),
+            ("{:,::x}", vec![VecParse(Implied, ",".into(), false, Modifiers { radix: Some(Radix::Hex), ..Modifiers::default() })]),
+            ("{:, :!3: trim,b}", vec![MultiParse(Implied, ", ".into(), 3, true, Modifiers { radix: Some(Radix::Binary), trim: Some(Trim::Both), ..Modifiers::default() })]),
+            ("{:a:b:2:ltrim}", vec![MultiParse(Implied, "a:b".into(), 2, false, Modifiers { trim: Some(Trim::Start), ..Modifiers::default() })]),
+            ("{:a:b:0}", vec![IterParse(Implied, "a:b".into(), false, Modifiers::default())]),
+            ("{:::x}", vec![VecParse(Implied, "".into(), false, Modifiers { radix: Some(Radix::Hex), ..Modifiers::default() })]),
+            ("(?t){:,:} {::}", vec![VecParse(Implied, ",".into(), false, Modifiers { trim: Some(Trim::Both), ..Modifiers::default() }), Lit(" ".into()), VecParse(Implied, "".into(), false, Modifiers::default())]),
+            ("{:x,_}", vec![Modified(Implied, Modifiers { radix: Some(Radix::Hex), grouped: true, ..Modifiers::default() })]),
+            ("{:,:dd}", vec![VecParse(Implied, ",".into(), false, Modifiers { doubled: true, ..Modifiers::default() })]),
+            ("{:;:3:dd,x}", vec![MultiParse(Implied, ";".into(), 3, false, Modifiers { radix: Some(Radix::Hex), doubled: true, ..Modifiers::default() })]),
+            (r"{:~\d+-\w*}", vec![Modified(Implied, Modifiers { shape: Some(r"\d+-\w*".into()), ..Modifiers::default() })]),
+            ("{:trim, ~a,b~}", vec![Modified(Implied, Modifiers { trim: Some(Trim::Both), shape: Some("a,b~".into()), ..Modifiers::default() })]),
+            ("{n:trim,len}", vec![Modified(Ident(syn::Ident::new("n", Span::call_site())), Modifiers { trim: Some(Trim::Both), length: Some(Length::Bytes), ..Modifiers::default() })]),
+            ("{:trim,host} {:email}", vec![Modified(Implied, Modifiers { trim: Some(Trim::Both), format: Some(Format::Host), ..Modifiers::default() }), Lit(" ".into()), Modified(Implied, Modifiers { format: Some(Format::Email), ..Modifiers::default() })]),
+            ("{:trim, map(n => Dir::North, s=>(1, \"a,b\"))}", vec![Modified(Implied, Modifiers { trim: Some(Trim::Both), mapping: Some(vec![("n".into(), "Dir::North".into()), ("s".into(), "(1, \"a,b\")".into())]), ..Modifiers::default() })]),
+            ("{:x, == ff} {a:== \"a:b\"}", vec![Modified(Implied, Modifiers { radix: Some(Radix::Hex), expected: Some("ff".into()), ..Modifiers::default() }), Lit(" ".into()), Modified(Ident(syn::Ident::new("a", Span::call_site())), Modifiers { expected: Some("a:b".into()), ..Modifiers::default() })]),
+            ("{}{[:{:x,default=50}]}", vec![Parse(Implied), GroupStart(false), Lit(":".into()), Modified(Implied, Modifiers { radix: Some(Radix::Hex), default: Some("50".into()), ..Modifiers::default() }), GroupEnd]),
+            ("{:==:}", vec![VecParse(Implied, "==".into(), false, Modifiers::default())]),
+            ("{:map(a => X::A), trim}", vec![Modified(Implied, Modifiers { trim: Some(Trim::Both), mapping: Some(vec![("a".into(), "X::A".into())]), ..Modifiers::default() })]),
+            ("{:map(:2}", vec![MultiParse(Implied, "map(".into(), 2, false, Modifiers::default())]),
+            ("{:map():map}", vec![MapParse(Implied, "map()".into(), false, None)]),
+            ("{:;:[,:3]}", vec![VecParse(Implied, ";".into(), false, Modifiers { row: Some((",".into(), 3, false)), ..Modifiers::default() })]),
+            ("{:\n:!2:[ :!4]:x}", vec![MultiParse(Implied, "\n".into(), 2, true, Modifiers { radix: Some(Radix::Hex), row: Some((" ".into(), 4, true)), ..Modifiers::default() })]),
+            ("{}{[ {}]}", vec![Parse(Implied), GroupStart(false), Lit(" ".into()), Parse(Implied), GroupEnd]),
+            ("a{[]]}", vec![Lit("a".into()), GroupStart(false), Lit("]".into()), GroupEnd]),
+            ("={(({},{}))}", vec![Lit("=".into()), GroupStart(true), Lit("(".into()), Parse(Implied), Lit(",".into()), Parse(Implied), Lit(")".into()), GroupEnd]),
+            ("(?i){} A", vec![Parse(Implied), FlexLit(" A".into(), LiteralFlags { case_insensitive: true, flexible_whitespace: false, right_greedy: false })]),
+            ("(?w)a {}", vec![FlexLit("a ".into(), LiteralFlags { case_insensitive: false, flexible_whitespace: true, right_greedy: false }), Parse(Implied)]),
+            ("(?t){} {:ltrim} {:x}", vec![Modified(Implied, Modifiers { trim: Some(Trim::Both), ..Modifiers::default() }), Lit(" ".into()), Modified(Implied, Modifiers { trim: Some(Trim::Start), ..Modifiers::default() }), Lit(" ".into()), Modified(Implied, Modifiers { radix: Some(Radix::Hex), trim: Some(Trim::Both), ..Modifiers::default() })]),
+            ("(?t){} {:notrim}", vec![Modified(Implied, Modifiers { trim: Some(Trim::Both), ..Modifiers::default() }), Lit(" ".into()), Modified(Implied, Modifiers { trim: Some(Trim::Off), ..Modifiers::default() })]),
+            ("(?t){a}={a}", vec![Modified(Ident(syn::Ident::new("a", Span::call_site())), Modifiers { trim: Some(Trim::Both), ..Modifiers::default() }), Lit("=".into()), BackRef(syn::Ident::new("a", Span::call_site()), 0)]),
+            ("(?c){a}, {a}", vec![SkipWhitespace, Modified(Ident(syn::Ident::new("a", Span::call_site())), Modifiers { trim: Some(Trim::Both), ..Modifiers::default() }), FlexLit(", ".into(), LiteralFlags { case_insensitive: false, flexible_whitespace: true, right_greedy: false }), BackRef(syn::Ident::new("a", Span::call_site()), 1), SkipWhitespace]),
+            ("{:c}{a: c }", vec![SingleChar(Implied), SingleChar(Ident(syn::Ident::new("a", Span::call_site())))]),
+            ("{:line}\n{a:line}", vec![Line(Implied), Lit("\n".into()), Line(Ident(syn::Ident::new("a", Span::call_site())))]),
+            ("{:qe},{}", vec![Quoted(Implied), Lit(",".into()), Parse(Implied)]),
+            ("(?){} ", vec![Parse(Implied), Lit(" ".into())]),
+            ("(?{}", vec![Lit("(?".into()), Parse(Implied)]),
         ];
         for (input, expected) in cases {
             let output = Instructions::new(input, Span::call_site());
@@ -0,0 +1,236 @@
+use core::num::IntErrorKind;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{Parse, ParseError};
+
+/// The units accepted by [`SignedDuration`] and the number of nanoseconds in each.
+const UNITS: &[(&str, u64)] = &[
+    ("ns", 1),
+    ("us", 1_000),
+    ("µs", 1_000),
+    ("ms", 1_000_000),
+    ("s", 1_000_000_000),
+    ("m", 60 * 1_000_000_000),
+    ("h", 60 * 60 * 1_000_000_000),
+    ("d", 24 * 60 * 60 * 1_000_000_000),
+];
+
+/// A duration that can be negative, such as a clock adjustment of `"-30s"`, stored as an `i64` of nanoseconds.
+///
+/// The duration is an integer followed by one of the units `ns`, `us` (or `µs`), `ms`, `s`, `m`, `h` or `d`,
+/// and it can start with a `-` or `+` sign. This covers roughly 292 years in either direction.
+/// An unknown or missing unit and a duration that does not fit into an `i64` of nanoseconds result in
+/// [`ParseError::Other`], while invalid digits result in [`ParseError::Int`].
+///
+/// ```
+/// # use prse::{parse, try_parse, SignedDuration};
+/// use core::time::Duration;
+///
+/// let offset: SignedDuration = parse!("offset: -30s", "offset: {}");
+/// assert_eq!(offset.0, -30_000_000_000);
+/// assert!(offset.is_negative());
+/// assert_eq!(offset.unsigned_abs(), Duration::from_secs(30));
+///
+/// let ahead: SignedDuration = parse!("+1h", "{}");
+/// assert_eq!(ahead.unsigned_abs(), Duration::from_secs(60 * 60));
+///
+/// let unknown: Result<SignedDuration, _> = try_parse!("30 years", "{}");
+/// assert!(unknown.is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct SignedDuration(pub i64);
+
+impl SignedDuration {
+    /// Returns `true` if the duration is less than zero.
+    pub fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+
+    /// Returns the length of the duration, without its sign.
+    pub fn unsigned_abs(self) -> Duration {
+        Duration::from_nanos(self.0.unsigned_abs())
+    }
+}
+
+impl<'a> Parse<'a> for SignedDuration {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        let s = s.trim();
+        let (negative, unsigned) = match s.strip_prefix('-') {
+            Some(unsigned) => (true, unsigned),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let split = unsigned
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(unsigned.len());
+        let (digits, unit) = unsigned.split_at(split);
+        let factor = UNITS
+            .iter()
+            .find_map(|&(name, factor)| (name == unit).then_some(factor))
+            .ok_or_else(|| {
+                ParseError::other(format_args!(
+                    "unknown unit {unit:?} in {s:?}, expected one of ns, us, ms, s, m, h or d."
+                ))
+            })?;
+        let too_long = || ParseError::other(format_args!("{s:?} does not fit into a duration."));
+        let value = match digits.parse::<u64>() {
+            Ok(value) => value,
+            Err(e) if e.kind() == &IntErrorKind::PosOverflow => return Err(too_long()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let nanos = i128::from(value) * i128::from(factor);
+        let nanos = if negative { -nanos } else { nanos };
+        i64::try_from(nanos)
+            .map(SignedDuration)
+            .map_err(|_| too_long())
+    }
+}
+
+/// A clock style duration such as `"01:30:45"`, of the form `HH:MM:SS` or `MM:SS`.
+///
+/// The seconds can have up to 9 fractional digits, as in `"00:00:01.500"`, while the hours are not limited to 24.
+/// Minutes and seconds of 60 or more, components that are not made of digits, or a number of components other than
+/// 2 or 3 result in [`ParseError::Other`].
+///
+/// ```
+/// # use prse::{parse, try_parse, Hms};
+/// use core::time::Duration;
+///
+/// let elapsed: Hms = parse!("elapsed 01:30:45", "elapsed {}");
+/// assert_eq!(elapsed.0, Duration::from_secs(60 * 60 + 30 * 60 + 45));
+///
+/// let lap: Hms = parse!("02:03.25", "{}");
+/// assert_eq!(lap.0, Duration::from_millis(123_250));
+///
+/// let invalid: Result<Hms, _> = try_parse!("01:60", "{}");
+/// assert!(invalid.is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Hms(pub Duration);
+
+impl From<Hms> for Duration {
+    fn from(hms: Hms) -> Self {
+        hms.0
+    }
+}
+
+impl<'a> Parse<'a> for Hms {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        let s = s.trim();
+        let malformed = || {
+            ParseError::other(format_args!(
+                "expected {s:?} to be of the form HH:MM:SS or MM:SS."
+            ))
+        };
+        let number = |digits: &str| {
+            if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(malformed());
+            }
+            digits
+                .parse::<u64>()
+                .map_err(|_| ParseError::other(format_args!("{s:?} does not fit into a duration.")))
+        };
+
+        let mut components = s.rsplit(':');
+        let seconds = components.next().ok_or_else(malformed)?;
+        let minutes = components.next().ok_or_else(malformed)?;
+        let hours = components.next().map_or(Ok(0), number)?;
+        if components.next().is_some() {
+            return Err(malformed());
+        }
+        let (seconds, fraction) = seconds.split_once('.').unwrap_or((seconds, "0"));
+        let (minutes, seconds) = (number(minutes)?, number(seconds)?);
+        if minutes >= 60 || seconds >= 60 {
+            return Err(ParseError::other(format_args!(
+                "the minutes and seconds of {s:?} must be less than 60."
+            )));
+        }
+        if fraction.len() > 9 {
+            return Err(ParseError::other(format_args!(
+                "{s:?} has more than 9 fractional digits."
+            )));
+        }
+        let nanos = number(fraction)? * 10_u64.pow(9 - fraction.len() as u32);
+
+        let secs = hours
+            .checked_mul(60 * 60)
+            .and_then(|h| h.checked_add(minutes * 60 + seconds))
+            .ok_or_else(|| {
+                ParseError::other(format_args!("{s:?} does not fit into a duration."))
+            })?;
+        Ok(Hms(Duration::new(secs, nanos as u32)))
+    }
+}
+
+/// A Unix timestamp such as `"1700000000"`, the number of seconds since 1970-01-01 00:00:00 UTC.
+///
+/// The seconds can have up to 9 fractional digits, as in `"1700000000.123"`, and can start with a `-` or `+` sign,
+/// where negative timestamps are before 1970. A timestamp that is not made of digits or that does not fit
+/// into a [`SystemTime`] results in [`ParseError::Other`].
+/// This type is only available with the `std` feature.
+///
+/// ```
+/// # use prse::{parse, try_parse, Epoch};
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let logged: Epoch = parse!("[1700000000.123] started", "[{}] started");
+/// assert_eq!(logged.0, UNIX_EPOCH + Duration::from_millis(1_700_000_000_123));
+///
+/// let before: Epoch = parse!("-86400", "{}");
+/// assert_eq!(before.0, UNIX_EPOCH - Duration::from_secs(86400));
+///
+/// let invalid: Result<Epoch, _> = try_parse!("17e8", "{}");
+/// assert!(invalid.is_err());
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Epoch(pub SystemTime);
+
+#[cfg(feature = "std")]
+impl From<Epoch> for SystemTime {
+    fn from(epoch: Epoch) -> Self {
+        epoch.0
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> Parse<'a> for Epoch {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        let s = s.trim();
+        let (negative, unsigned) = match s.strip_prefix('-') {
+            Some(unsigned) => (true, unsigned),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let (seconds, fraction) = match unsigned.split_once('.') {
+            Some((seconds, fraction)) => (seconds, Some(fraction)),
+            None => (unsigned, None),
+        };
+        let is_number =
+            |digits: &str| !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit());
+        if !is_number(seconds) || !fraction.map_or(true, is_number) {
+            return Err(ParseError::other(format_args!(
+                "expected {s:?} to be a number of seconds since the Unix epoch."
+            )));
+        }
+        let fraction = fraction.unwrap_or("0");
+        if fraction.len() > 9 {
+            return Err(ParseError::other(format_args!(
+                "{s:?} has more than 9 fractional digits."
+            )));
+        }
+
+        let too_long = || ParseError::other(format_args!("{s:?} does not fit into a timestamp."));
+        let seconds = seconds.parse::<u64>().map_err(|_| too_long())?;
+        let nanos = fraction.parse::<u32>().map_err(|_| too_long())?
+            * 10_u32.pow(9 - fraction.len() as u32);
+        let offset = Duration::new(seconds, nanos);
+        let time = if negative {
+            UNIX_EPOCH.checked_sub(offset)
+        } else {
+            UNIX_EPOCH.checked_add(offset)
+        };
+        time.map(Epoch).ok_or_else(too_long)
+    }
+}
@@ -0,0 +1,56 @@
+use crate::parse_error::ParseError;
+
+/// A [`ParseError`] extension trait with helpers for converting it into the error types of other crates.
+///
+/// The trait is sealed and cannot be implemented on any other type. It is only available with the `std` feature.
+pub trait ParseErrorExt: __private::Sealed {
+    /// Creates an [`io::Error`](std::io::Error) of kind [`InvalidData`](std::io::ErrorKind::InvalidData)
+    /// from a reference to the error.
+    ///
+    /// As the error is only borrowed, the `io::Error` holds its message instead of the `ParseError`,
+    /// use [`into_io_error`](ParseError::into_io_error) to keep the `ParseError` as the inner error.
+    ///
+    /// ```
+    /// # use prse::{try_parse, ParseError, ParseErrorExt};
+    /// use std::io::ErrorKind;
+    ///
+    /// let error: Result<u8, ParseError> = try_parse!("level: x", "level: {}");
+    /// let error = error.unwrap_err();
+    /// let io_error = error.as_io_error();
+    /// assert_eq!(io_error.kind(), ErrorKind::InvalidData);
+    /// assert_eq!(io_error.to_string(), error.to_string());
+    /// ```
+    fn as_io_error(&self) -> std::io::Error;
+
+    /// Converts the error into an [`anyhow::Error`], which can still be downcast back into the `ParseError`.
+    ///
+    /// This is the same as `anyhow::Error::from`, but can be called at the end of a method chain.
+    /// This function is only available with the `anyhow` feature.
+    ///
+    /// ```
+    /// # use prse::{try_parse, ParseError, ParseErrorExt};
+    /// let error: Result<u8, ParseError> = try_parse!("level: x", "level: {}");
+    /// let error = error.map_err(ParseErrorExt::into_anyhow).unwrap_err();
+    /// assert!(error.downcast_ref::<ParseError>().is_some());
+    /// ```
+    #[cfg(feature = "anyhow")]
+    fn into_anyhow(self) -> anyhow::Error;
+}
+
+impl ParseErrorExt for ParseError {
+    fn as_io_error(&self) -> std::io::Error {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, self.to_string())
+    }
+
+    #[cfg(feature = "anyhow")]
+    fn into_anyhow(self) -> anyhow::Error {
+        anyhow::Error::new(self)
+    }
+}
+
+#[doc(hidden)]
+mod __private {
+    pub trait Sealed {}
+
+    impl Sealed for super::ParseError {}
+}
@@ -0,0 +1,107 @@
+use core::fmt;
+
+use crate::{Parse, ParseError};
+
+/// More digits than an `i64` can hold, so that longer numbers are still reported as an overflow.
+const MAX_DIGITS: usize = 20;
+
+/// A fixed-point decimal stored as an `i64` scaled by `10^SCALE`.
+///
+/// This parses decimals exactly, without going through a float, so `"12.34"` as a `Fixed<2>` holds `1234`.
+/// The number can start with a sign and either the integer or the fractional part can be left out.
+/// A number with more than `SCALE` fractional digits is never rounded and returns [`ParseError::Other`],
+/// while invalid digits and overflows return [`ParseError::Int`]. `SCALE` can be at most 18.
+///
+/// ```
+/// # use prse::{parse, try_parse, Fixed};
+/// let price: Fixed<2> = parse!("Total: 12.34", "Total: {}");
+/// assert_eq!(price.0, 1234);
+/// assert_eq!(price.to_string(), "12.34");
+///
+/// let delta: Fixed<3> = parse!("-.5", "{}");
+/// assert_eq!(delta.0, -500);
+///
+/// let too_precise: Result<Fixed<2>, _> = try_parse!("0.125", "{}");
+/// assert!(too_precise.is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Fixed<const SCALE: u32>(pub i64);
+
+impl<const SCALE: u32> Fixed<SCALE> {
+    const SCALE_FITS: () = assert!(SCALE <= 18, "the scale of Fixed can be at most 18");
+
+    /// The number the value is scaled by, `10^SCALE`.
+    pub const FACTOR: i64 = 10_i64.pow(SCALE);
+
+    /// Returns the integer part of the value, rounded towards zero.
+    pub fn trunc(self) -> i64 {
+        self.0 / Self::FACTOR
+    }
+}
+
+impl<const SCALE: u32> fmt::Display for Fixed<SCALE> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if SCALE == 0 {
+            return write!(f, "{}", self.0);
+        }
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let value = self.0.unsigned_abs();
+        let factor = Self::FACTOR as u64;
+        let width = SCALE as usize;
+        write!(f, "{sign}{}.{:0width$}", value / factor, value % factor)
+    }
+}
+
+impl<'a, const SCALE: u32> Parse<'a> for Fixed<SCALE> {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::SCALE_FITS;
+
+        let s = s.trim();
+        let (negative, unsigned) = match s.strip_prefix('-') {
+            Some(unsigned) => (true, unsigned),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        if integer.is_empty() && fraction.is_empty() {
+            return Ok(Fixed(s.parse::<i64>()?));
+        }
+        if !integer
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|b| b.is_ascii_digit())
+        {
+            // Produces the same invalid digit error as parsing an integer.
+            return Ok(Fixed("-".parse::<i64>()?));
+        }
+        if fraction.len() > SCALE as usize {
+            return Err(ParseError::other(format_args!(
+                "{s:?} has more than {SCALE} fractional digits."
+            )));
+        }
+
+        // Join both parts and the missing trailing zeros to parse them as a single integer.
+        let padding = SCALE as usize - fraction.len();
+        let digits = integer
+            .bytes()
+            .chain(fraction.bytes())
+            .chain(core::iter::repeat(b'0').take(padding))
+            .skip_while(|&b| b == b'0');
+        let mut buf = [b'0'; MAX_DIGITS + 2];
+        let mut len = 0;
+        if negative {
+            buf[0] = b'-';
+            len = 1;
+        }
+        for digit in digits.take(MAX_DIGITS) {
+            buf[len] = digit;
+            len += 1;
+        }
+        // A zero value has no digits left once the leading zeros are skipped.
+        if len == 0 || buf[..len] == [b'-'] {
+            return Ok(Fixed(0));
+        }
+        let signed = core::str::from_utf8(&buf[..len]).expect("only ascii digits are copied");
+        Ok(Fixed(signed.parse::<i64>()?))
+    }
+}
@@ -0,0 +1,133 @@
+use core::fmt;
+
+use crate::{Parse, ParseError};
+
+/// An exact fraction such as `"3/4"`, or a mixed number such as `"3 1/4"`, in its lowest terms.
+///
+/// The value is kept as a rational so that it can be compared and added up without rounding,
+/// use [`to_f64`](Self::to_f64) or [`f64::from`] to get an approximation of it.
+/// A mixed number is turned into an improper fraction, so `"3 1/4"` is `13/4`, and a whole number
+/// without a fraction such as `"2"` is `2/1`. The sign can only be given before the whole value and
+/// whitespace around the numbers is ignored, but the whole part must be separated from the fraction by whitespace.
+///
+/// Invalid digits return [`ParseError::Int`], while a zero denominator or a value that does not fit
+/// in the numerator and denominator return [`ParseError::Other`].
+///
+/// ```
+/// # use prse::{parse, try_parse, Fraction};
+/// let flour: Fraction = parse!("3 1/4 cups of flour", "{} cups of flour");
+/// assert_eq!(flour, Fraction { numerator: 13, denominator: 4 });
+/// assert_eq!(flour.to_f64(), 3.25);
+///
+/// let half: Fraction = parse!("-2/4", "{}");
+/// assert_eq!((half.numerator, half.denominator), (-1, 2));
+/// assert_eq!(half.to_string(), "-1/2");
+///
+/// let zero: Result<Fraction, _> = try_parse!("1/0", "{}");
+/// assert!(zero.is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fraction {
+    /// The signed numerator, which has no common factor with the denominator.
+    pub numerator: i64,
+    /// The denominator, which is never zero.
+    pub denominator: u64,
+}
+
+impl Fraction {
+    /// Returns the closest float to the fraction.
+    pub fn to_f64(self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+
+    /// Returns the whole part of the fraction, rounded towards zero.
+    pub fn trunc(self) -> i64 {
+        // The denominator is at least 1, so a denominator that does not fit makes the quotient 0.
+        i64::try_from(self.denominator).map_or(0, |denominator| self.numerator / denominator)
+    }
+}
+
+impl Default for Fraction {
+    fn default() -> Self {
+        Fraction {
+            numerator: 0,
+            denominator: 1,
+        }
+    }
+}
+
+impl From<Fraction> for f64 {
+    fn from(fraction: Fraction) -> Self {
+        fraction.to_f64()
+    }
+}
+
+impl fmt::Display for Fraction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.numerator, self.denominator)
+    }
+}
+
+/// Parses a number made of ASCII digits only, as [`str::parse`] would also accept a leading `+`.
+fn parse_digits(s: &str) -> Result<u64, ParseError> {
+    // A sign is accepted by u64, so anything that is not a digit is rejected first.
+    if !s.bytes().all(|b| b.is_ascii_digit()) {
+        // Produces the same invalid digit error as parsing an integer.
+        return Ok("-".parse::<u64>()?);
+    }
+    Ok(s.parse::<u64>()?)
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+impl<'a> Parse<'a> for Fraction {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        let trimmed = s.trim();
+        let (negative, unsigned) = match trimmed.strip_prefix('-') {
+            Some(unsigned) => (true, unsigned),
+            None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+        };
+        let (whole, numerator, denominator) = match unsigned.rsplit_once('/') {
+            Some((left, denominator)) => {
+                let left = left.trim();
+                let (whole, numerator) = match left.rsplit_once(char::is_whitespace) {
+                    Some((whole, numerator)) => (parse_digits(whole.trim_end())?, numerator),
+                    None => (0, left),
+                };
+                (
+                    whole,
+                    parse_digits(numerator)?,
+                    parse_digits(denominator.trim())?,
+                )
+            }
+            None => (parse_digits(unsigned)?, 0, 1),
+        };
+        if denominator == 0 {
+            return Err(ParseError::other(format_args!(
+                "{s:?} has a zero denominator."
+            )));
+        }
+        let too_large = || ParseError::other(format_args!("{s:?} is too large for a fraction."));
+        let magnitude = whole
+            .checked_mul(denominator)
+            .and_then(|whole| whole.checked_add(numerator))
+            .ok_or_else(too_large)?;
+        let divisor = gcd(magnitude, denominator);
+        let (magnitude, denominator) = (magnitude / divisor, denominator / divisor);
+        let numerator = if negative {
+            0_i64.checked_sub_unsigned(magnitude)
+        } else {
+            i64::try_from(magnitude).ok()
+        }
+        .ok_or_else(too_large)?;
+        Ok(Fraction {
+            numerator,
+            denominator,
+        })
+    }
+}
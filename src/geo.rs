@@ -0,0 +1,71 @@
+use crate::{Parse, ParseError};
+
+/// A latitude and longitude pair in decimal degrees, such as `"40.7128,-74.0060"`.
+///
+/// Each coordinate can either be signed or followed by a hemisphere (with an optional `°`),
+/// so `"40.7128°N, 74.0060°W"` is also accepted, but a coordinate with both, such as `"-40°S"`,
+/// returns a [`ParseError::Other`]. Whitespace around each coordinate is ignored.
+/// The latitude must be within `-90..=90` and the longitude within `-180..=180`,
+/// otherwise a [`ParseError::Other`] is returned.
+///
+/// This type is only available with the `geo` feature.
+///
+/// ```
+/// # use prse::{parse, try_parse, LatLon};
+/// let nyc: LatLon = parse!("New York: 40.7128,-74.0060", "New York: {}");
+/// assert_eq!(nyc, LatLon { lat: 40.7128, lon: -74.006 });
+///
+/// let nyc: LatLon = parse!("40.7128°N, 74.0060°W", "{}");
+/// assert_eq!(nyc, LatLon { lat: 40.7128, lon: -74.006 });
+///
+/// let invalid: Result<LatLon, _> = try_parse!("91,0", "{}");
+/// assert!(invalid.is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct LatLon {
+    /// The latitude in degrees, positive values are north of the equator.
+    pub lat: f64,
+    /// The longitude in degrees, positive values are east of the prime meridian.
+    pub lon: f64,
+}
+
+/// Parses a single coordinate, `positive` and `negative` are the hemisphere suffixes.
+fn parse_coordinate(s: &str, positive: char, negative: char, max: f64) -> Result<f64, ParseError> {
+    let s = s.trim();
+    let (number, sign) = if let Some(number) = s.strip_suffix(positive) {
+        (number, Some(1.0))
+    } else if let Some(number) = s.strip_suffix(negative) {
+        (number, Some(-1.0))
+    } else {
+        (s, None)
+    };
+    let number = number.trim_end();
+    let number = number.strip_suffix('°').unwrap_or(number);
+    if sign.is_some() && number.starts_with(['+', '-']) {
+        return Err(ParseError::other(format_args!(
+            "{s:?} has both a sign and a hemisphere, expected only one of them."
+        )));
+    }
+    let value = number.parse::<f64>()? * sign.unwrap_or(1.0);
+    if (-max..=max).contains(&value) {
+        Ok(value)
+    } else {
+        Err(ParseError::other(format_args!(
+            "{value} is out of range, expected a value between -{max} and {max}."
+        )))
+    }
+}
+
+impl<'a> Parse<'a> for LatLon {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        let (lat, lon) = s.split_once(',').ok_or_else(|| {
+            ParseError::other(format_args!(
+                "expected a ',' between the latitude and longitude in {s:?}."
+            ))
+        })?;
+        Ok(LatLon {
+            lat: parse_coordinate(lat, 'N', 'S', 90.0)?,
+            lon: parse_coordinate(lon, 'E', 'W', 180.0)?,
+        })
+    }
+}
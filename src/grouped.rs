@@ -0,0 +1,92 @@
+use crate::{Parse, ParseError};
+
+/// The longest number that can be parsed once its group separators are removed.
+const MAX_LEN: usize = 64;
+
+/// A number whose integer digits are grouped in threes by `SEP`, with `DECIMAL` separating its fractional digits.
+///
+/// The separators default to the US convention of `"1,234.56"`, while `Grouped<T, '.', ','>` parses the
+/// European `"1.234,56"`. The first group holds 1 to 3 digits and every following group exactly 3,
+/// anything else such as `"1,23,456"` or a separator in the fractional digits returns [`ParseError::Other`].
+/// A number without any group separator, like `"1234"`, is also accepted.
+///
+/// Once the separators are removed, and the decimal char replaced by a `.`, the number is parsed as a `T`,
+/// so a sign or invalid digits are handled by `T`. `SEP` and `DECIMAL` must be different.
+///
+/// The ungrouped number is built without allocating, so it can be at most 64 bytes long including its sign
+/// and decimal point, a longer number returns [`ParseError::Other`] before `T` is parsed.
+///
+/// ```
+/// # use prse::{parse, try_parse, Grouped};
+/// let total: Grouped<f64> = parse!("Total: $1,234.56", "Total: ${}");
+/// assert_eq!(total.0, 1234.56);
+///
+/// let total: Grouped<f64, '.', ','> = parse!("Summe: 1.234,56 €", "Summe: {} €");
+/// assert_eq!(total.0, 1234.56);
+///
+/// let population: Grouped<u64> = parse!("8,100,000,000", "{}");
+/// assert_eq!(population.0, 8_100_000_000);
+///
+/// let misgrouped: Result<Grouped<u32>, _> = try_parse!("1,23,456", "{}");
+/// assert!(misgrouped.is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Grouped<T, const SEP: char = ',', const DECIMAL: char = '.'>(pub T);
+
+impl<T, const SEP: char, const DECIMAL: char> Grouped<T, SEP, DECIMAL> {
+    const DISTINCT: () = assert!(
+        SEP != DECIMAL,
+        "the group separator and decimal char of Grouped must be different"
+    );
+}
+
+impl<'a, T, const SEP: char, const DECIMAL: char> Parse<'a> for Grouped<T, SEP, DECIMAL>
+where
+    T: for<'b> Parse<'b>,
+{
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::DISTINCT;
+
+        let s = s.trim();
+        let unsigned = s.strip_prefix(['-', '+']).unwrap_or(s);
+        let sign = &s[..s.len() - unsigned.len()];
+        let (integer, fraction) = match unsigned.split_once(DECIMAL) {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (unsigned, None),
+        };
+
+        let misgrouped = || {
+            ParseError::other(format_args!(
+                "invalid digit grouping in {s:?}, expected groups of 3 digits separated by {SEP:?}."
+            ))
+        };
+        if fraction.is_some_and(|f| f.contains(SEP)) {
+            return Err(misgrouped());
+        }
+        if integer.contains(SEP) {
+            let mut groups = integer.split(SEP);
+            let first = groups.next().unwrap_or_default();
+            if !(1..=3).contains(&first.len()) || groups.any(|g| g.len() != 3) {
+                return Err(misgrouped());
+            }
+        }
+
+        let mut buf = [0_u8; MAX_LEN];
+        let mut len = 0;
+        let parts = core::iter::once(sign)
+            .chain(integer.split(SEP))
+            .chain(fraction.into_iter().flat_map(|f| [".", f]));
+        for part in parts {
+            let Some(slot) = buf.get_mut(len..len + part.len()) else {
+                return Err(ParseError::other(format_args!(
+                    "{s:?} has too many digits, expected at most {MAX_LEN} bytes without the group separators."
+                )));
+            };
+            slot.copy_from_slice(part.as_bytes());
+            len += part.len();
+        }
+        let ungrouped = core::str::from_utf8(&buf[..len]).expect("only whole strs are copied");
+        T::from_str(ungrouped).map(Grouped)
+    }
+}
@@ -0,0 +1,228 @@
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::{Parse, ParseError};
+
+/// The separators that [`Hex`] and [`HexArray`] accept between byte pairs by default.
+const DEFAULT_SEPARATORS: &[char] = &[' ', '\t', '\n', '\r', ':'];
+
+/// Decodes a string of hex digit pairs, calling `push` with each decoded byte.
+///
+/// Any char in `separators` is skipped as long as it is found between two byte pairs.
+fn decode(s: &str, separators: &[char], mut push: impl FnMut(u8)) -> Result<(), ParseError> {
+    let mut high = None;
+    for c in s.trim().chars() {
+        if high.is_none() && separators.contains(&c) {
+            continue;
+        }
+        let digit = c
+            .to_digit(16)
+            .ok_or_else(|| ParseError::other(format_args!("invalid hex digit {c:?} in {s:?}.")))?
+            as u8;
+        match high.take() {
+            None => high = Some(digit),
+            Some(h) => push(h << 4 | digit),
+        }
+    }
+    match high {
+        None => Ok(()),
+        Some(_) => Err(ParseError::other(format_args!(
+            "expected an even number of hex digits in {s:?}."
+        ))),
+    }
+}
+
+/// Decodes a hex string such as `"deadbeef"` or `"de:ad:be:ef"` into its bytes.
+///
+/// Whitespace and `:` are accepted between byte pairs, other separators can be used with
+/// [`Hex::from_str_with_separators`]. An odd number of digits or an invalid digit results
+/// in [`ParseError::Other`].
+///
+/// This type is only available with the `alloc` feature, see [`HexArray`] for a fixed size alternative.
+///
+/// ```
+/// # use prse::{parse, Hex};
+/// let mac: Hex = parse!("MAC: 00:1a:2B:3c:4d:5e", "MAC: {}");
+/// assert_eq!(mac.0, vec![0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e]);
+///
+/// let hash: Hex = parse!("deadbeef", "{}");
+/// assert_eq!(hash.0, vec![0xde, 0xad, 0xbe, 0xef]);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Hex(pub Vec<u8>);
+
+#[cfg(feature = "alloc")]
+impl Hex {
+    /// Decodes a hex string, only accepting the given `separators` between byte pairs.
+    ///
+    /// ```
+    /// # use prse::Hex;
+    /// let hex = Hex::from_str_with_separators("de-ad-be-ef", &['-']).unwrap();
+    /// assert_eq!(hex.0, vec![0xde, 0xad, 0xbe, 0xef]);
+    /// assert!(Hex::from_str_with_separators("de ad", &['-']).is_err());
+    /// ```
+    pub fn from_str_with_separators(s: &str, separators: &[char]) -> Result<Self, ParseError> {
+        let mut bytes = Vec::with_capacity(s.len() / 2);
+        decode(s, separators, |b| bytes.push(b))?;
+        Ok(Hex(bytes))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Parse<'a> for Hex {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        Self::from_str_with_separators(s, DEFAULT_SEPARATORS)
+    }
+}
+
+/// Decodes a hex string into exactly `N` bytes.
+///
+/// It accepts the same input as [`Hex`] but does not need to allocate.
+/// If the string does not contain exactly `N` bytes a [`ParseError::Array`] is returned.
+///
+/// ```
+/// # use prse::{parse, try_parse, HexArray};
+/// let mac: HexArray<6> = parse!("00:1a:2b:3c:4d:5e", "{}");
+/// assert_eq!(mac.0, [0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e]);
+///
+/// let short: Result<HexArray<4>, _> = try_parse!("beef", "{}");
+/// assert!(short.is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HexArray<const N: usize>(pub [u8; N]);
+
+impl<const N: usize> HexArray<N> {
+    /// Decodes a hex string, only accepting the given `separators` between byte pairs.
+    ///
+    /// ```
+    /// # use prse::HexArray;
+    /// let hex = HexArray::<2>::from_str_with_separators("be_ef", &['_']).unwrap();
+    /// assert_eq!(hex.0, [0xbe, 0xef]);
+    /// ```
+    pub fn from_str_with_separators(s: &str, separators: &[char]) -> Result<Self, ParseError> {
+        let mut bytes = [0; N];
+        let mut found = 0_usize;
+        decode(s, separators, |b| {
+            if let Some(byte) = bytes.get_mut(found) {
+                *byte = b;
+            }
+            found += 1;
+        })?;
+        if found != N {
+            return Err(ParseError::Array {
+                expected: u8::try_from(N).unwrap_or(u8::MAX),
+                found: u8::try_from(found).unwrap_or(u8::MAX),
+            });
+        }
+        Ok(HexArray(bytes))
+    }
+}
+
+impl<'a, const N: usize> Parse<'a> for HexArray<N> {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        Self::from_str_with_separators(s, DEFAULT_SEPARATORS)
+    }
+}
+
+/// Decodes a colour such as `"#ff8800"` into its red, green, blue and alpha channels.
+///
+/// The alpha channel is `0xff` unless it is given, which is only accepted with `alpha`.
+fn decode_colour(s: &str, alpha: bool) -> Result<[u8; 4], ParseError> {
+    let trimmed = s.trim();
+    let digits = trimmed.strip_prefix('#').unwrap_or(trimmed);
+    let mut channels = [0xff; 4];
+    let mut found = 0_usize;
+    if digits.len() == 3 || (alpha && digits.len() == 4) {
+        // Each digit of the shorthand form is repeated, so "f80" is the same as "ff8800".
+        for c in digits.chars() {
+            let digit = c.to_digit(16).ok_or_else(|| {
+                ParseError::other(format_args!("invalid hex digit {c:?} in {s:?}."))
+            })? as u8;
+            channels[found] = digit << 4 | digit;
+            found += 1;
+        }
+    } else {
+        decode(digits, &[], |b| {
+            if let Some(channel) = channels.get_mut(found) {
+                *channel = b;
+            }
+            found += 1;
+        })?;
+    }
+    if found == 3 || (alpha && found == 4) {
+        Ok(channels)
+    } else if alpha {
+        Err(ParseError::other(format_args!(
+            "expected 3, 4, 6 or 8 hex digits in {s:?}."
+        )))
+    } else {
+        Err(ParseError::other(format_args!(
+            "expected 3 or 6 hex digits in {s:?}."
+        )))
+    }
+}
+
+/// A colour hex code such as `"#ff8800"` parsed into its red, green and blue channels.
+///
+/// The leading `#` is optional and the 3 digit shorthand `"#f80"` repeats each digit, so it is the same as `"#ff8800"`.
+/// A different number of digits or an invalid digit results in [`ParseError::Other`], see [`Rgba`] to also parse an alpha channel.
+///
+/// ```
+/// # use prse::{parse, try_parse, Rgb};
+/// let accent: Rgb = parse!("accent: #FF8800", "accent: {}");
+/// assert_eq!(accent, Rgb(0xff, 0x88, 0x00));
+///
+/// let shorthand: Rgb = parse!("f80", "{}");
+/// let (r, g, b): (u8, u8, u8) = shorthand.into();
+/// assert_eq!((r, g, b), (0xff, 0x88, 0x00));
+///
+/// let transparent: Result<Rgb, _> = try_parse!("#ff880000", "{}");
+/// assert!(transparent.is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl From<Rgb> for (u8, u8, u8) {
+    fn from(Rgb(r, g, b): Rgb) -> Self {
+        (r, g, b)
+    }
+}
+
+impl<'a> Parse<'a> for Rgb {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        let [r, g, b, _] = decode_colour(s, false)?;
+        Ok(Rgb(r, g, b))
+    }
+}
+
+/// A colour hex code such as `"#ff8800aa"` parsed into its red, green, blue and alpha channels.
+///
+/// It accepts the same input as [`Rgb`] as well as 8 digits and the 4 digit shorthand `"#f80a"`.
+/// Without an alpha channel the colour is opaque, so its alpha is `0xff`.
+///
+/// ```
+/// # use prse::{parse, Rgba};
+/// let overlay: Rgba = parse!("#FF8800AA", "{}");
+/// assert_eq!(overlay, Rgba(0xff, 0x88, 0x00, 0xaa));
+///
+/// let opaque: Rgba = parse!("#f80", "{}");
+/// assert_eq!(opaque, Rgba(0xff, 0x88, 0x00, 0xff));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rgba(pub u8, pub u8, pub u8, pub u8);
+
+impl From<Rgba> for (u8, u8, u8, u8) {
+    fn from(Rgba(r, g, b, a): Rgba) -> Self {
+        (r, g, b, a)
+    }
+}
+
+impl<'a> Parse<'a> for Rgba {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        let [r, g, b, a] = decode_colour(s, true)?;
+        Ok(Rgba(r, g, b, a))
+    }
+}
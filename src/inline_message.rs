@@ -0,0 +1,91 @@
+use core::fmt::{self, Write};
+
+/// The maximum number of bytes an [`InlineMessage`] can hold.
+pub const INLINE_MESSAGE_CAPACITY: usize = 64;
+
+/// A small message stored inline without allocating.
+///
+/// This is what [`ParseError::Other`](crate::ParseError::Other) holds when the `inline-message` feature
+/// is enabled without the `alloc` feature. Messages longer than [`INLINE_MESSAGE_CAPACITY`] bytes
+/// are truncated on a char boundary so that the stored message is always valid UTF-8.
+///
+/// This type is only available with the `inline-message` feature.
+///
+/// ```
+/// # use prse::InlineMessage;
+/// let message = InlineMessage::new(format_args!("expected {} digits", 4));
+/// assert_eq!(message.as_str(), "expected 4 digits");
+/// assert!(!message.is_truncated());
+///
+/// let long = InlineMessage::new("é".repeat(40));
+/// assert_eq!(long.as_str().len(), 64);
+/// assert!(long.is_truncated());
+/// ```
+#[derive(Clone, Copy)]
+pub struct InlineMessage {
+    buf: [u8; INLINE_MESSAGE_CAPACITY],
+    len: u8,
+    truncated: bool,
+}
+
+impl InlineMessage {
+    /// Creates a new message from anything that can be displayed, truncating it if it does not fit.
+    pub fn new(message: impl fmt::Display) -> Self {
+        let mut inline = InlineMessage {
+            buf: [0; INLINE_MESSAGE_CAPACITY],
+            len: 0,
+            truncated: false,
+        };
+        // Writing never fails as a message that is too long is truncated instead.
+        let _ = write!(inline, "{message}");
+        inline
+    }
+
+    /// Returns the stored message.
+    pub fn as_str(&self) -> &str {
+        // The buffer only ever contains whole chars.
+        core::str::from_utf8(&self.buf[..self.len as usize]).unwrap_or_default()
+    }
+
+    /// Returns true if part of the message had to be discarded.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+}
+
+impl Write for InlineMessage {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.truncated {
+            return Ok(());
+        }
+        let len = self.len as usize;
+        let mut end = s.len().min(INLINE_MESSAGE_CAPACITY - len);
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        self.buf[len..len + end].copy_from_slice(&s.as_bytes()[..end]);
+        self.len += end as u8;
+        self.truncated = end != s.len();
+        Ok(())
+    }
+}
+
+impl fmt::Display for InlineMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for InlineMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl PartialEq for InlineMessage {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for InlineMessage {}
@@ -0,0 +1,169 @@
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::ops::Deref;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::{Parse, ParseError};
+
+/// Where [`Interned`] keeps the strings it has already seen.
+///
+/// Implement this trait on your own type to choose how long the strings are kept and who they are shared with,
+/// such as a `static` set behind a `Mutex` to share them across threads.
+///
+/// ```
+/// # use prse::{parse, Interned, Interner};
+/// use std::collections::HashSet;
+/// use std::sync::{Arc, Mutex};
+///
+/// static STRINGS: Mutex<Option<HashSet<Arc<str>>>> = Mutex::new(None);
+///
+/// struct Shared;
+///
+/// impl Interner for Shared {
+///     fn intern(s: &str) -> Arc<str> {
+///         let mut strings = STRINGS.lock().unwrap();
+///         let strings = strings.get_or_insert_with(HashSet::new);
+///         match strings.get(s) {
+///             Some(interned) => interned.clone(),
+///             None => {
+///                 let interned: Arc<str> = Arc::from(s);
+///                 strings.insert(interned.clone());
+///                 interned
+///             }
+///         }
+///     }
+/// }
+///
+/// let colors: [Interned<Shared>; 3] = parse!("red blue red", "{: :3}");
+/// assert!(Arc::ptr_eq(colors[0].as_arc(), colors[2].as_arc()));
+/// ```
+pub trait Interner {
+    /// Returns the shared copy of `s`, creating it if `s` has not been seen before.
+    fn intern(s: &str) -> Arc<str>;
+}
+
+/// The default [`Interner`] of [`Interned`], which keeps a separate set of strings for each thread.
+///
+/// The strings are kept until [`clear`](Self::clear) is called or the thread exits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ThreadLocalInterner;
+
+thread_local! {
+    static THREAD_STRINGS: RefCell<HashSet<Arc<str>>> = RefCell::new(HashSet::new());
+}
+
+impl ThreadLocalInterner {
+    /// Forgets every string interned on the current thread, the values that were already parsed are unaffected.
+    pub fn clear() {
+        THREAD_STRINGS.with(|strings| strings.borrow_mut().clear());
+    }
+}
+
+impl Interner for ThreadLocalInterner {
+    fn intern(s: &str) -> Arc<str> {
+        THREAD_STRINGS.with(|strings| {
+            let mut strings = strings.borrow_mut();
+            match strings.get(s) {
+                Some(interned) => interned.clone(),
+                None => {
+                    let interned: Arc<str> = Arc::from(s);
+                    strings.insert(interned.clone());
+                    interned
+                }
+            }
+        })
+    }
+}
+
+/// A string that shares its allocation with every other equal string interned by `I`.
+///
+/// Parsing the same value many times, as in a column of categories, then only allocates it once.
+/// The captured string is interned as is, use the `trim` modifier to ignore the whitespace around it.
+///
+/// This type is only available with the `intern` feature.
+///
+/// ```
+/// # use prse::{parse, Interned};
+/// use std::sync::Arc;
+///
+/// let animals: Vec<Interned> = parse!("cat,dog,cat", "{:,:}");
+/// assert_eq!(&*animals[0], "cat");
+/// assert!(Arc::ptr_eq(animals[0].as_arc(), animals[2].as_arc()));
+/// ```
+pub struct Interned<I = ThreadLocalInterner> {
+    value: Arc<str>,
+    interner: PhantomData<fn() -> I>,
+}
+
+impl<I> Interned<I> {
+    /// Returns the shared string.
+    pub fn as_arc(&self) -> &Arc<str> {
+        &self.value
+    }
+
+    /// Consumes the `Interned`, returning the shared string.
+    pub fn into_inner(self) -> Arc<str> {
+        self.value
+    }
+}
+
+impl<I> Deref for Interned<I> {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<I> From<Interned<I>> for Arc<str> {
+    fn from(interned: Interned<I>) -> Self {
+        interned.value
+    }
+}
+
+impl<I> fmt::Debug for Interned<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Interned").field(&self.value).finish()
+    }
+}
+
+impl<I> fmt::Display for Interned<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.value)
+    }
+}
+
+impl<I> Clone for Interned<I> {
+    fn clone(&self) -> Self {
+        Interned {
+            value: self.value.clone(),
+            interner: PhantomData,
+        }
+    }
+}
+
+impl<I> PartialEq for Interned<I> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<I> Eq for Interned<I> {}
+
+impl<I> Hash for Interned<I> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+impl<'a, I: Interner> Parse<'a> for Interned<I> {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        Ok(Interned {
+            value: I::intern(s),
+            interner: PhantomData,
+        })
+    }
+}
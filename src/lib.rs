@@ -68,6 +68,39 @@
 //! assert_eq!(rhs, Position {x: -2, y: 9});
 //! ```
 //!
+//! ## Empty input
+//!
+//! A pattern always has to consume the whole input, so an empty pattern only matches an empty input
+//! and any input left over is a [`ParseError::Literal`]. A capture given an empty input is handed the empty
+//! string, which is fine for a `&str` but is an error for most other types such as integers.
+//! ```
+//! # use prse::{try_parse, ParseError};
+//! #
+//! assert_eq!(try_parse!("", ""), Ok(()));
+//! assert!(matches!(try_parse!("left over", ""), Err::<(), _>(ParseError::Literal { .. })));
+//! assert_eq!(try_parse!("", "{}"), Ok::<&str, _>(""));
+//!
+//! let number: Result<u32, _> = try_parse!("", "{}");
+//! assert!(matches!(number.unwrap_err().root_cause(), ParseError::Int(_)));
+//! ```
+//!
+//! ## Adjacent captures
+//!
+//! A capture ends at the literal that follows it, so two captures with nothing in between such as `"{}{}"`
+//! are ambiguous and rejected at compile time. A capture that ends on its own can be directly followed by another
+//! capture: single chars (`{:c}`), counts and balanced brackets, which also start at their opening bracket.
+//! ```compile_fail
+//! # use prse::parse;
+//! #
+//! let (a, b): (u32, u32) = parse!("12", "{}{}");
+//! ```
+//! ```
+//! # use prse::parse;
+//! #
+//! let (sign, amount): (char, u32) = parse!("-12", "{:c}{}");
+//! assert_eq!((sign, amount), ('-', 12));
+//! ```
+//!
 //! # Repetition
 //!
 //! You can parse multiple parts of a string using one of the following methods:
@@ -86,6 +119,16 @@
 //!
 //! assert_eq!(array, ["Beatrice", "Betsy", "Callum"]);
 //! ```
+//!
+//! A separator at the end of the input ends the last element, the `trailing` modifier keeps the empty element
+//! after it instead. Together with [`Missing`] and [`Blank`] this parses sparse rows that keep their length.
+//! ```
+//!# use prse::{parse, Blank, Missing};
+//!#
+//! let row: [Missing<i32, Blank>; 4] = parse!("1,,3,", "{:,:4:trailing}");
+//!
+//! assert_eq!(row.map(|v| v.into_inner()), [Some(1), None, Some(3), None]);
+//! ```
 //! ## Vec
 //!
 //! You can parse a string into a Vec of parsed elements using the following syntax `{<var>:<sep>:}`.
@@ -102,6 +145,26 @@
 //!
 //! assert_eq!(many, vec![true, false, true, false]);
 //! ```
+//!
+//! When the Vec must have a fixed length, such as a feature vector, [`parse_padded!`] pads it with
+//! a default value and drops the extra elements, or returns an error for them with [`Overflow::Error`].
+//! ```
+//!# use prse::parse_padded;
+//!#
+//! let features: Vec<f64> = parse_padded!("0.5,1.5", "{:,:}", 3, 0.0);
+//!
+//! assert_eq!(features, [0.5, 1.5, 0.0]);
+//! ```
+//!
+//! Records of several lines separated by blank lines, such as vCards or email headers, can each be parsed
+//! with the same pattern into a Vec using [`parse_records!`].
+//! ```
+//!# use prse::parse_records;
+//!#
+//! let people: Vec<(&str, u8)> = parse_records!("Alice\n30\n\nBob\n25\n", "{}\n{}");
+//!
+//! assert_eq!(people, [("Alice", 30), ("Bob", 25)]);
+//! ```
 //! ## Iterator
 //!
 //! Alternatively if you are unable to allocate anything then you can use a lazy iterator
@@ -124,6 +187,17 @@
 //! assert_eq!(animal_count, 79);
 //! ```
 //!
+//! To stop at the first element that fails to parse, [`parse_for_each!`] passes each element to a callback
+//! instead and returns the error.
+//! ```
+//!# use prse::parse_for_each;
+//!#
+//! let mut animal_count = 0;
+//! parse_for_each!("[5,23,42,1,3,5]", "[{:,:}]", |c: u32| animal_count += c).unwrap();
+//!
+//! assert_eq!(animal_count, 79);
+//! ```
+//!
 //! ## Modifiers
 //!
 //! All three multi-parsers (Array, Vec and Iterator) allow `{<var>:<sep>:!<kind>}` syntax to skip multiple separators, for example
@@ -142,16 +216,604 @@
 //! assert_eq!([3, 2, 1], parse!("321", "{::3}"))
 //! ```
 //!
+//! ## Line endings
+//!
+//! When the separator is a newline (e.g. `{:\n:}`) a `\r` at the end of each line is removed,
+//! so inputs with Windows line endings can be parsed the same way as Unix ones.
+//! This also applies to a capture followed by a literal starting with a newline.
+//! ```
+//! # use prse::parse;
+//! #
+//! assert_eq!([1, 2, 3], parse!("1\r\n2\r\n3", "{:\n:3}"));
+//! ```
+//!
+//! ## Element modifiers
+//!
+//! The [capture modifiers](#capture-modifiers) can be applied to every element of a repetition
+//! by adding them after the count, `{<var>:<sep>:<count>:<modifier>,...}`.
+//! Since an iterator yields its elements parsed as their own type, it can only use the `trailing` and `max` modifiers.
+//! ```
+//! # use prse::parse;
+//! #
+//! let bytes: [u8; 3] = parse!(" 0x1 , 0xff ,0x3", "{:,:3:x,trim}");
+//! assert_eq!(bytes, [1, 255, 3]);
+//! ```
+//!
+//! ## Doubled separators
+//!
+//! The `dd` element modifier allows an element to contain the separator by doubling it, like quotes in CSV.
+//! A Vec can use the shorter `{<var>:<sep>:dd}` syntax. Since the doubled separators have to be removed,
+//! the elements cannot borrow from the input and must be owned types such as `String`.
+//! This modifier cannot be used with iterators or when skipping separators.
+//! ```
+//! # use prse::parse;
+//! #
+//! let fields: Vec<String> = parse!("a,,b,c", "{:,:dd}");
+//! assert_eq!(fields, ["a,b", "c"]);
+//! ```
+//!
+//! ## Limiting the number of elements
+//!
+//! A repetition has no limit on its number of elements by default, so a crafted input with millions of separators
+//! makes a Vec or map repetition allocate as much. The `max=<count>` element modifier returns a
+//! [`ParseError::TooManyElements`] once there are more elements, without parsing the rest of the input.
+//! It can also be given to a map, `{<var>:<sep>:map:max=<count>}`, and limits the number of rows of a repetition of rows.
+//! ```
+//! # use prse::{try_parse, ParseError};
+//! #
+//! let few: Result<Vec<u32>, _> = try_parse!("1,2,3", "{:,::max=3}");
+//! assert_eq!(few, Ok(vec![1, 2, 3]));
+//! let many: Result<Vec<u32>, _> = try_parse!("1,2,3,4", "{:,::max=3}");
+//! assert_eq!(many, Err(ParseError::TooManyElements { max: 3 }));
+//! ```
+//!
+//! ## Separator classes
+//!
+//! A separator of the form `[<chars>]+` matches any run of the chars between the brackets,
+//! so `{:[, ]+:}` splits on commas, spaces or both. Inside the brackets `\s` stands for any whitespace
+//! and `\` escapes the next char. Separators at the start or the end of the input are ignored,
+//! so an input made of only separators contains no elements. A separator class cannot be combined
+//! with skipping separators or the `dd` modifier as it already matches a whole run.
+//! ```
+//! # use prse::parse;
+//! #
+//! let nums: Vec<u32> = parse!(" 1, 2\t3,,4 ", r"{:[,\s]+:}");
+//! assert_eq!(nums, [1, 2, 3, 4]);
+//! ```
+//!
+//! Without the `+`, a separator set `[<chars>]` splits on any single one of its chars, which is simpler than
+//! a nested repetition for flat lists with inconsistent delimiters. Each char separates two elements on its own,
+//! so consecutive separators result in empty elements unless they are skipped with `!`, and like with any other
+//! separator the elements do not tell which separator came before them. A set cannot be used with the `dd` modifier.
+//! ```
+//! # use prse::parse;
+//! #
+//! let nums: Vec<u32> = parse!("1,2;3", "{:[,;]:}");
+//! assert_eq!(nums, [1, 2, 3]);
+//!
+//! let fields: Vec<&str> = parse!("a,;b", "{:[,;]:}");
+//! assert_eq!(fields, ["a", "", "b"]);
+//! let fields: Vec<&str> = parse!("a,;b", "{:[,;]:!}");
+//! assert_eq!(fields, ["a", "b"]);
+//! ```
+//!
+//! A `[` in a separator can be escaped as `\[` to match it as is, so that a separator starting with a bracket
+//! is not a class or a set and a separator containing `:[` does not start a [row](#rows).
+//! ```
+//! # use prse::parse;
+//! #
+//! let nums: Vec<u32> = parse!("1[,]2", r"{:\[,]:}");
+//! assert_eq!(nums, [1, 2]);
+//! ```
+//!
+//! ## Rows
+//!
+//! A Vec or an array can be split into rows that are each parsed into an array, such as the rows of a matrix,
+//! by giving the separator and number of columns of the row in brackets, `{<var>:<sep>:[<row sep>:<columns>]}`
+//! for a Vec and `{<var>:<sep>:<count>:[<row sep>:<columns>]}` for an array. A row can skip separators with
+//! `!` like any other array, and the [element modifiers](#element-modifiers) apply to each element of a row.
+//! A row that does not have exactly the number of columns returns a [`ParseError::Array`], in a
+//! [`ParseError::Context`] naming the index of the row when the `alloc` feature is enabled.
+//! Rows cannot be used with iterators or the `dd` modifier. The count is everything after the last colon before
+//! the row, so a Vec with a separator containing a colon is written with an empty count, `{<var>:<sep>::[<row sep>:<columns>]}`.
+//! ```
+//! # use prse::{parse, try_parse};
+//! #
+//! let matrix: Vec<[i32; 3]> = parse!("1,2,3;4,5,6", "{:;:[,:3]}");
+//! assert_eq!(matrix, [[1, 2, 3], [4, 5, 6]]);
+//!
+//! let grid: [[u8; 2]; 2] = parse!("ff 0a\n01  10", "{:\n:2:[ :!2]:x}");
+//! assert_eq!(grid, [[255, 10], [1, 16]]);
+//!
+//! let short_row: Result<Vec<[i32; 3]>, _> = try_parse!("1,2,3;4,5", "{:;:[,:3]}");
+//! assert!(short_row.is_err());
+//! ```
+//!
+//! ## Collecting into a map
+//!
+//! Using `map` instead of a number, `{<var>:<sep>:map}` splits the capture into `key=value` entries and collects them
+//! into any type implementing [`FromIterator`] of `(key, value)` tuples, such as a [`HashMap`](std::collections::HashMap).
+//! The key and value are parsed as their own types, both are trimmed so spaces around the `=` are ignored.
+//! Like any map built from an iterator, a key that is repeated keeps its last value and an empty capture results in an empty map.
+//! The separator between entries can be skipped when repeated using `{<var>:<sep>:!map}`.
+//! ```
+//! # use prse::parse;
+//! # use std::collections::{BTreeMap, HashMap};
+//! #
+//! let settings: HashMap<String, u32> = parse!("a=1 b=2 c=3", "{: :map}");
+//! assert_eq!(settings["b"], 2);
+//!
+//! let ports: BTreeMap<&str, u16> = parse!("[http=80, https=443]", "[{:, :map}]");
+//! assert_eq!(ports.into_iter().collect::<Vec<_>>(), [("http", 80), ("https", 443)]);
+//! ```
+//!
+//! To keep the entries in the order they were written, including repeated keys, collect them into a
+//! `Vec<(K, V)>` instead, which is useful when the input has to be written back out unchanged.
+//! An ordered map such as `IndexMap` from the `indexmap` crate also works as it implements [`FromIterator`].
+//! ```
+//! # use prse::parse;
+//! #
+//! let pairs: Vec<(&str, u32)> = parse!("b=2 a=1 b=3", "{: :map}");
+//! assert_eq!(pairs, [("b", 2), ("a", 1), ("b", 3)]);
+//! ```
+//!
+//! ## Counting a literal
+//!
+//! Using `count` instead of a number, `{<var>:<literal>:count}` counts how many times the literal is repeated
+//! at that point and returns the count as a `usize`, after which the rest of the pattern is matched.
+//! The count can be zero, so add the literal to the pattern as well when it has to be found at least once.
+//! As the count stops on its own, it can be directly followed by another capture.
+//! ```
+//! # use prse::parse;
+//! #
+//! let (level, title): (usize, &str) = parse!("### Title", "{:#:count} {}");
+//! assert_eq!((level, title), (3, "Title"));
+//!
+//! let (indent, item): (usize, &str) = parse!("    item", "{:  :count}{}");
+//! assert_eq!((indent, item), (2, "item"));
+//! ```
+//!
+//! ## Balanced brackets
+//!
+//! A capture given a pair of brackets, such as `{<var>:()}`, captures the text inside of the brackets
+//! up to the closing bracket that matches the opening one, skipping over any nested pairs.
+//! Only the text inside of the brackets is parsed, so it can itself be parsed with another pattern to parse nested structures.
+//! Any two different punctuation chars other than `,`, `_` and `:` can be used, such as `()`, `[]` or `<>`.
+//! The brackets start at the first opening bracket after the previous capture, or directly at that point if there is no capture before them.
+//! As they end on their own, brackets can be placed directly next to other captures.
+//! If the closing bracket is missing, a [`ParseError::Unbalanced`] is returned.
+//! ```
+//! # use prse::{parse, try_parse, ParseError};
+//! #
+//! let (name, args): (&str, &str) = parse!("max(1, min(2, 3))", "{}{:()}");
+//! assert_eq!((name, args), ("max", "1, min(2, 3)"));
+//!
+//! let unbalanced: Result<(&str, &str), _> = try_parse!("max(1, min(2, 3)", "{}{:()}");
+//! assert_eq!(unbalanced.unwrap_err(), ParseError::Unbalanced { open: '(', close: ')' });
+//! ```
+//!
+//! ## Single chars
+//!
+//! A capture with the `c` modifier, `{<var>:c}`, captures exactly one char at that point of the input,
+//! so it can be directly followed by another capture. It cannot directly follow a capture, as the end of that
+//! capture would be unknown. The char is parsed like any other capture, so it can be parsed into a `char`, an
+//! integer digit or a `&str`. Reaching the end of the input instead returns a [`ParseError::Other`].
+//! ```
+//! # use prse::parse;
+//! #
+//! let (unit, amount): (char, u32) = parse!("€25", "{:c}{}");
+//! assert_eq!((unit, amount), ('€', 25));
+//!
+//! let (a, x, b, y): (char, u8, char, u8) = parse!("a1b2", "{:c}{:c}{:c}{:c}");
+//! assert_eq!((a, x, b, y), ('a', 1, 'b', 2));
+//! ```
+//!
+//! ## Lines
+//!
+//! A capture with the `line` modifier, `{<var>:line}`, captures the rest of the current line, up to but not including
+//! the next `\n`, or the rest of the input on the last line. A `\r` just before the `\n` is not part of the line,
+//! so Windows line endings are handled. Anything after the capture must then start at the `\n`, and like a
+//! [single char](#single-chars) it cannot directly follow a capture.
+//! ```
+//! # use prse::{parse, try_parse};
+//! #
+//! let (a, b): (&str, &str) = parse!("a: hello world\r\nb: bye", "a: {:line}\nb: {:line}");
+//! assert_eq!((a, b), ("hello world", "bye"));
+//!
+//! let first_line: Result<&str, _> = try_parse!("hello\nworld", "{:line}");
+//! assert!(first_line.is_err());
+//! ```
+//!
+//! ## Escaped quotes
+//!
+//! A capture with the `qe` modifier, `{<var>:qe}`, handles fields that may be quoted like `"he said \"hi\""`.
+//! When the capture starts with a `"`, it ends at the matching unescaped quote and the literal after it must directly
+//! follow the quotes, so separators inside the quotes are part of the field. The quotes are removed and `\"` and `\\`
+//! are un-escaped before the text is parsed, while any other backslash is kept as is.
+//! A field that does not start with a quote is captured like any other capture.
+//!
+//! As the un-escaped text is a temporary string, the captured type cannot borrow from the input,
+//! so it is usually a `String`. This modifier is only available if the alloc feature has been enabled.
+//! To also replace escapes such as `\n`, `\t` or `\u{e9}`, capture the field as an [`Unescaped`].
+//! ```
+//! # use prse::parse;
+//! #
+//! let (name, id): (String, u32) = parse!(r#""Smith, \"Jo\"",42"#, "{:qe},{}");
+//! assert_eq!((name.as_str(), id), (r#"Smith, "Jo""#, 42));
+//!
+//! let (name, id): (String, u32) = parse!("Doe,7", "{:qe},{}");
+//! assert_eq!((name.as_str(), id), ("Doe", 7));
+//! ```
+//!
+//! # Capture modifiers
+//!
+//! A single capture can be given modifiers using the `{<var>:<modifier>}` syntax,
+//! multiple modifiers are separated by commas.
+//!
+//! ## Radix
+//!
+//! Integers can be parsed in a different base using the `x` (hexadecimal), `o` (octal) and `b` (binary) modifiers,
+//! in which case the matching `0x`, `0o` or `0b` prefix is optional. The `auto` modifier instead detects the base from the prefix
+//! following Rust's integer literal rules, and parses the number as decimal if there is no prefix.
+//! In all cases the number can start with a sign and errors are returned as [`ParseError::Int`].
+//! ```
+//! # use prse::parse;
+//! #
+//! let (colour, mode): (u32, u16) = parse!("#ff8000 0o755", "#{:x} {:auto}");
+//! assert_eq!(colour, 0xff8000);
+//! assert_eq!(mode, 0o755);
+//!
+//! let numbers: (i8, i8, i8) = parse!("-0b101 0x7f 12", "{:auto} {:auto} {:auto}");
+//! assert_eq!(numbers, (-5, 127, 12));
+//! ```
+//!
+//! The radix modifiers can be used with any type implementing [`ParseRadix`], which every integer type does.
+//!
+//! ## Digit grouping
+//!
+//! The `_` modifier allows the digits of an integer to be grouped by underscores, as in Rust's integer literals.
+//! It can be combined with any of the radix modifiers, and parses a decimal number otherwise.
+//! An underscore that is not between two digits or after the prefix results in a [`ParseError::Other`].
+//! ```
+//! # use prse::{parse, try_parse};
+//! #
+//! let (mask, flags): (u32, u8) = parse!("0xFF_FF 1010_0101", "{:x,_} {:b,_}");
+//! assert_eq!(mask, 65535);
+//! assert_eq!(flags, 0b1010_0101);
+//!
+//! let population: u64 = parse!("8_100_000_000", "{:_}");
+//! assert_eq!(population, 8_100_000_000);
+//!
+//! let misplaced: Result<u32, _> = try_parse!("FF__FF", "{:x,_}");
+//! assert!(misplaced.is_err());
+//! ```
+//!
+//! ## Trimming
+//!
+//! The `trim`, `ltrim` and `rtrim` modifiers remove the whitespace around, before or after a capture
+//! before it is parsed. This is useful to parse space padded columns, and `&str` captures are still
+//! borrowed from the input.
+//! ```
+//! # use prse::parse;
+//! #
+//! let (name, score): (&str, u32) = parse!("| Alice    |    42 |", "|{:trim}|{:ltrim} |");
+//! assert_eq!(name, "Alice");
+//! assert_eq!(score, 42);
+//! ```
+//!
+//! ## Shapes
+//!
+//! A `~` followed by a shape checks that the whole capture matches the shape before it is parsed, returning
+//! a [`ParseError::Other`] if it does not. The shape is a small subset of a regular expression
+//! that does not need an allocator:
+//! - `\d` matches an ASCII digit and `\w` matches an alphanumeric char or `_`.
+//! - `.` matches any char.
+//! - `*` and `+` repeat the previous element zero or more and one or more times.
+//! - `\` followed by any other char matches that char, such as `\.` or `\*`, and every other char matches itself.
+//!
+//! The shape is the last modifier of the capture, so it can contain commas but not `:`, `{` or `}`.
+//! It is checked after the capture is trimmed.
+//! ```
+//! # use prse::{parse, try_parse};
+//! #
+//! let (id, name): (&str, &str) = parse!("42-abc: Alice", r"{:~\d+-\w+}: {:~.+}");
+//! assert_eq!((id, name), ("42-abc", "Alice"));
+//!
+//! let version: Result<&str, _> = try_parse!("v1.x", r"{:~v\d+\.\d+}");
+//! assert!(version.is_err());
+//! ```
+//!
+//! ## Lengths
+//!
+//! The `len` modifier returns the length in bytes of the text a capture covers as a `usize`, instead of parsing it.
+//! The capture still ends at the next literal, or at the end of the input if it is the last part of the pattern.
+//! With the `unicode` feature, the `clen` modifier returns the number of chars instead.
+//! Both can be combined with the trimming modifiers and a shape, which are applied first.
+//! ```
+//! # use prse::parse;
+//! #
+//! let (key, value): (usize, usize) = parse!("name=Zoë", "{:len}={:len}");
+//! assert_eq!((key, value), (4, 4));
+//!
+//! let padded: usize = parse!("[  ok ]", "[{:trim,len}]");
+//! assert_eq!(padded, 2);
+//! ```
+//!
+//! ## Hosts and emails
+//!
+//! The `host` and `email` modifiers check that a capture looks like a host name or an email address
+//! before it is parsed, returning a [`ParseError::Other`] if it does not. Both are quick approximations
+//! that work without an allocator rather than full implementations of the RFCs:
+//! - A host is at most 253 bytes of labels separated by dots. Each label has 1 to 63 ASCII letters,
+//!   digits or hyphens and cannot start or end with a hyphen. IP addresses in brackets, trailing dots
+//!   and internationalized names that are not already in their `xn--` form are rejected.
+//! - An email has a single `@`, with up to 64 bytes before it that are not whitespace and a host after it.
+//!   Quoted local parts, comments and display names such as `Alice <alice@example.org>` are not supported.
+//!
+//! Like a shape, the modifiers are checked after the capture is trimmed.
+//! ```
+//! # use prse::{parse, try_parse};
+//! #
+//! let (user, host): (&str, &str) = parse!("alice@example.org -> mail.example.org", "{:email} -> {:host}");
+//! assert_eq!((user, host), ("alice@example.org", "mail.example.org"));
+//!
+//! let invalid: Result<&str, _> = try_parse!("-bad-.example", "{:host}");
+//! assert!(invalid.is_err());
+//! ```
+//!
+//! ## Mapping keys to values
+//!
+//! The `map` modifier, `{<var>:map(<key> => <value>, ...)}`, matches the capture against each key and
+//! yields the Rust expression given as its value, which is handy for tiny closed mappings such as single letter codes.
+//! The key must be the whole capture and is compared exactly, so it is case sensitive and includes any whitespace
+//! unless the `trim` modifier is also given. The other modifier a map can be combined with is a
+//! [default](#optional-groups), which has to be one of the keys.
+//! The keys are trimmed in the pattern and cannot contain a `,` or a `)` outside of brackets, while the values
+//! are expressions evaluated once the whole pattern matched, so they all need to have the same type.
+//! A capture that is not one of the keys results in a [`ParseError::Other`] listing the keys,
+//! and the map modifier cannot be used on the elements of a repetition.
+//! ```
+//! # use prse::{parse, try_parse};
+//! #
+//! #[derive(Debug, PartialEq)]
+//! enum Direction {
+//!     North,
+//!     South,
+//! }
+//!
+//! let (steps, direction) = parse!("3 n", "{} {:map(n => Direction::North, s => Direction::South)}");
+//! assert_eq!((steps, direction), (3, Direction::North));
+//!
+//! let level: Result<u8, _> = try_parse!("debug", "{:map(info => 1, warn => 2)}");
+//! assert!(level.is_err());
+//! ```
+//!
+//! ## Expected values
+//!
+//! The `==` modifier, `{<var>:== <value>}`, checks that the capture equals a value known when writing the pattern,
+//! such as the greeting of a protocol handshake, while still returning it. A capture written exactly like the value is
+//! accepted as is, otherwise the value is parsed into the type of the capture, using its radix modifier if there is one,
+//! and compared with [`PartialEq`], so `{:x, == ff}` accepts both `ff` and `FF`. It always comes last and takes everything after the `==`, so it can be written as a Rust string
+//! literal to include leading or trailing whitespace, and it must be a string literal to contain a `:`.
+//! A capture that is not equal to the value results in a [`ParseError::Other`] with both of them, and
+//! the `==` modifier cannot be used on the elements of a repetition or with a length modifier.
+//! ```
+//! # use prse::{parse, try_parse};
+//! #
+//! let (greeting, version): (&str, u8) = parse!("HELLO v2", "{:== HELLO} v{:== 2}");
+//! assert_eq!((greeting, version), ("HELLO", 2));
+//!
+//! let wrong: Result<&str, _> = try_parse!("HELO", "{:== \"HELLO\"}");
+//! assert!(wrong.is_err());
+//! ```
+//!
+//! # Optional groups
+//!
+//! Surrounding part of the pattern with `{[` and `]}` makes it optional, unnamed captures inside it
+//! are returned as an [`Option`] and named ones are only assigned when the group is present.
+//! ```
+//! # use prse::parse;
+//! #
+//! let (host, port): (&str, Option<u16>) = parse!("localhost:8080", "{}{[:{}]}");
+//! assert_eq!((host, port), ("localhost", Some(8080)));
+//!
+//! let (host, port): (&str, Option<u16>) = parse!("localhost", "{}{[:{}]}");
+//! assert_eq!((host, port), ("localhost", None));
+//! ```
+//!
+//! A capture in an optional group can instead be given a default with the `default=<value>` modifier,
+//! `{<var>:default=<value>}`, in which case it is not an [`Option`] and named captures are always assigned.
+//! The value is parsed like the capture, with the same modifiers, but only when the group is absent,
+//! so an invalid default is only an error for the inputs that need it. The value cannot contain a `,` or a `:`.
+//! ```
+//! # use prse::parse;
+//! #
+//! let version: &str;
+//! let path: &str = parse!("GET /", "GET {}{[ {version:default=HTTP/1.0}]}");
+//! assert_eq!((path, version), ("/", "HTTP/1.0"));
+//!
+//! let (host, port): (&str, u16) = parse!("localhost:8080", "{}{[:{:x,default=50}]}");
+//! assert_eq!((host, port), ("localhost", 0x8080));
+//! let (host, port): (&str, u16) = parse!("localhost", "{}{[:{:x,default=50}]}");
+//! assert_eq!((host, port), ("localhost", 0x50));
+//! ```
+//!
+//! Using `{(` and `)}` instead returns every capture of the group together as an [`Option`] of a tuple,
+//! so either all of them are present or none are.
+//! ```
+//! # use prse::parse;
+//! #
+//! let point: Option<(i32, i32)> = parse!("pt=(1,2)", "pt={(({},{}))}");
+//! assert_eq!(point, Some((1, 2)));
+//!
+//! let point: Option<(i32, i32)> = parse!("pt=", "pt={(({},{}))}");
+//! assert_eq!(point, None);
+//! ```
+//!
+//! # Pattern flags
+//!
+//! Flags that apply to the whole pattern can be given at its very start using the `(?<flags>)` syntax,
+//! so a pattern starting with a literal `(?` has to start with a flag cluster first, such as the empty `(?)`:
+//! - `i` matches literals ignoring case.
+//! - `w` lets every run of whitespace in a literal match any non-empty run of whitespace.
+//! - `t` trims every single capture, as if each of them had the `trim` modifier.
+//! - `c` splits whitespace separated [columns](#columns).
+//! - `r` makes every capture [right-greedy](#right-greedy-captures).
+//!
+//! A capture's own modifiers take precedence over the flags, so `{:ltrim}` only trims the start of
+//! the capture even with the `t` flag. Repetitions trim each of their elements, apart from char repetitions and iterators.
+//! The `notrim` modifier opts a single capture out of the `t` flag and passes it exactly as it was found.
+//! A named capture that is repeated to match the same text again is trimmed like its first occurrence before being compared.
+//! ```
+//! # use prse::parse;
+//! #
+//! let (key, value): (&str, u32) = parse!("SET  name   =  42", "(?itw)set {} = {}");
+//! assert_eq!(key, "name");
+//! assert_eq!(value, 42);
+//! ```
+//!
+//! ## Columns
+//!
+//! The `c` flag parses whitespace separated columns, such as the output of `ps` or `df`. It implies the `w` and `t`
+//! flags, so every run of whitespace between the captures is a single separator however wide it is, and it also skips
+//! the whitespace at the start and at the end of the input. Repetitions still use their separator as it is written.
+//! ```
+//! # use prse::parse;
+//! #
+//! let (user, pid, command): (&str, u32, &str) = parse!("  root \t 1234    /sbin/init ", "(?c){} {} {}");
+//! assert_eq!((user, pid, command), ("root", 1234, "/sbin/init"));
+//! ```
+//!
+//! ## Right-greedy captures
+//!
+//! With the `r` flag a capture ends at the last match of the literal after it rather than the first,
+//! which is how the extension of a file name can be found however many dots come before it.
+//! The literal still has to be found, so an input without it is an error.
+//! ```
+//! # use prse::{parse, try_parse};
+//! #
+//! let (stem, ext): (&str, &str) = parse!("report.2021.final.pdf", "(?r){}.{}");
+//! assert_eq!((stem, ext), ("report.2021.final", "pdf"));
+//!
+//! let no_ext: Result<(&str, &str), _> = try_parse!("README", "(?r){}.{}");
+//! assert!(no_ext.is_err());
+//! ```
+//!
+//! ## Trimming by default
+//!
+//! The `t` flag trims every capture of the pattern, which saves annotating every capture when parsing whitespace
+//! padded tables. `&str` captures are still borrowed from the input, and `{:notrim}` gets back the exact slice.
+//! There is deliberately no feature to trim every pattern, as it would also change how the patterns of
+//! every other crate using prse are parsed.
+//! ```
+//! # use prse::parse;
+//! #
+//! let (name, score, raw): (&str, u32, &str) = parse!("  Alice  | 42 | x ", "(?t){}|{}|{:notrim}");
+//! assert_eq!((name, score, raw), ("Alice", 42, " x "));
+//! ```
+//!
+//! # Reusing patterns
+//!
+//! The pattern is read when the macro is expanded, so it must be written as a string literal: macros only see
+//! the tokens they are given and cannot read the value of a `const`. Passing anything else, such as the name of
+//! a `const`, results in a compile error. A pattern that is used in several places can instead be wrapped
+//! in a function using [`parse_fn!`], in a type by deriving [`Parse`], or in a `macro_rules!` macro,
+//! all of which still check the pattern at compile time.
+//! ```
+//! # use prse::{parse, parse_fn};
+//! #
+//! parse_fn!(parse_point, "({}, {})", (i32, i32));
+//!
+//! macro_rules! parse_size {
+//!     ($input:expr) => {
+//!         parse!($input, "{}x{}")
+//!     };
+//! }
+//!
+//! assert_eq!(parse_point("(1, -2)").unwrap(), (1, -2));
+//! let (width, height): (u32, u32) = parse_size!("1920x1080");
+//! assert_eq!((width, height), (1920, 1080));
+//! ```
+//!
 
-pub use prse_derive::{parse, try_parse, Parse};
+pub use prse_derive::{
+    parse, parse_advance, parse_fn, parse_for_each, parse_located, parse_or, parse_prefix,
+    pattern_literals, try_parse, try_parse_advance, try_parse_located, try_parse_prefix,
+    try_parse_validated, which_matches, Parse,
+};
+#[cfg(feature = "alloc")]
+pub use prse_derive::{
+    parse_map, parse_padded, parse_records, try_parse_map, try_parse_padded, try_parse_records,
+};
 
+#[cfg(feature = "std")]
+pub use crate::duration::Epoch;
+pub use crate::duration::{Hms, SignedDuration};
+#[cfg(feature = "std")]
+pub use crate::error_ext::ParseErrorExt;
+pub use crate::fixed::Fixed;
+pub use crate::fraction::Fraction;
+#[cfg(feature = "geo")]
+pub use crate::geo::LatLon;
+pub use crate::grouped::Grouped;
+#[cfg(feature = "alloc")]
+pub use crate::hex::Hex;
+pub use crate::hex::{HexArray, Rgb, Rgba};
+#[cfg(feature = "inline-message")]
+pub use crate::inline_message::{InlineMessage, INLINE_MESSAGE_CAPACITY};
+#[cfg(feature = "intern")]
+pub use crate::intern::{Interned, Interner, ThreadLocalInterner};
 #[rustfmt::skip]
 pub use crate::lending_parse::{ExtParseStr, Parse};
-pub use crate::parse_error::ParseError;
+pub use crate::location::Location;
+#[cfg(feature = "unicode")]
+pub use crate::normalized::{Nfc, Nfkc, Normalized, Normalizer};
+#[cfg(feature = "alloc")]
+pub use crate::padded::Overflow;
 #[doc(hidden)]
 pub use crate::parse_error::__private;
+pub use crate::parse_error::ParseError;
 pub use crate::parse_iterators::{ParseChars, ParseIter};
+pub use crate::pattern_literals::PatternLiterals;
+pub use crate::quantity::{Length, Mass, Quantity, Time, Units};
+pub use crate::radix::ParseRadix;
+pub use crate::range::SteppedRange;
+#[cfg(feature = "std")]
+pub use crate::wrappers::ScopedIpv6;
+pub use crate::wrappers::{
+    BitFlags, Blank, BoolWords, CaseInsensitive, English, FlagNames, French, German, KeyValue,
+    LocaleBool, Missing, NotAvailable, OneOf2, OneOf3, PathComponents, Percent, Ratio, Sentinels,
+    Spanish, Spanned, TryFromStr, WithRaw, WithUnit,
+};
+#[cfg(feature = "alloc")]
+pub use crate::wrappers::{SortedVec, Unescaped};
 
+mod duration;
+#[cfg(feature = "std")]
+mod error_ext;
+mod fixed;
+mod fraction;
+#[cfg(feature = "geo")]
+mod geo;
+mod grouped;
+mod hex;
+#[cfg(feature = "inline-message")]
+mod inline_message;
+#[cfg(feature = "intern")]
+mod intern;
 mod lending_parse;
+mod literal;
+mod location;
+#[cfg(feature = "unicode")]
+mod normalized;
+#[cfg(feature = "alloc")]
+mod padded;
 mod parse_error;
 mod parse_iterators;
+mod pattern_literals;
+mod quantity;
+mod radix;
+mod range;
+#[cfg(feature = "serde")]
+mod serialize;
+mod wrappers;
@@ -0,0 +1,326 @@
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use alloc::{borrow::Cow, string::String};
+
+use crate::ParseError;
+
+/// Returns the length of the match if `s` starts with `literal`.
+///
+/// With `case_insensitive`, chars are compared by their lowercase form, and with `flexible_whitespace`,
+/// every run of whitespace in `literal` matches any non-empty run of whitespace in `s`.
+fn match_at(
+    s: &str,
+    literal: &str,
+    case_insensitive: bool,
+    flexible_whitespace: bool,
+) -> Option<usize> {
+    let mut chars = s.char_indices().peekable();
+    let mut literal = literal.chars().peekable();
+    while let Some(l) = literal.next() {
+        if flexible_whitespace && l.is_whitespace() {
+            while literal.next_if(|c| c.is_whitespace()).is_some() {}
+            chars.next_if(|(_, c)| c.is_whitespace())?;
+            while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+        } else {
+            let (_, c) = chars.next()?;
+            if c != l && !(case_insensitive && c.to_lowercase().eq(l.to_lowercase())) {
+                return None;
+            }
+        }
+    }
+    Some(chars.next().map_or(s.len(), |(idx, _)| idx))
+}
+
+#[doc(hidden)]
+/// Not part of public api, used by the pattern flags to strip a literal from the start of `s`.
+pub fn strip_literal<'a>(
+    s: &'a str,
+    literal: &str,
+    case_insensitive: bool,
+    flexible_whitespace: bool,
+) -> Option<&'a str> {
+    match_at(s, literal, case_insensitive, flexible_whitespace).map(|end| &s[end..])
+}
+
+#[doc(hidden)]
+/// Not part of public api, used by the pattern flags to split `s` on the first match of a literal.
+pub fn split_literal<'a>(
+    s: &'a str,
+    literal: &str,
+    case_insensitive: bool,
+    flexible_whitespace: bool,
+) -> Option<(&'a str, &'a str)> {
+    s.char_indices().find_map(|(start, _)| {
+        let end = match_at(&s[start..], literal, case_insensitive, flexible_whitespace)?;
+        Some((&s[..start], &s[start + end..]))
+    })
+}
+
+#[doc(hidden)]
+/// Not part of public api, used by the `r` pattern flag to split `s` on the last match of a literal.
+///
+/// With `flexible_whitespace`, a literal starting with whitespace covers the whole run of whitespace before it.
+pub fn rsplit_literal<'a>(
+    s: &'a str,
+    literal: &str,
+    case_insensitive: bool,
+    flexible_whitespace: bool,
+) -> Option<(&'a str, &'a str)> {
+    let (start, end) = s.char_indices().rev().find_map(|(start, _)| {
+        let end = match_at(&s[start..], literal, case_insensitive, flexible_whitespace)?;
+        Some((start, start + end))
+    })?;
+    let mut before = &s[..start];
+    if flexible_whitespace && literal.starts_with(char::is_whitespace) {
+        before = before.trim_end();
+    }
+    Some((before, &s[end..]))
+}
+
+#[cfg(feature = "alloc")]
+fn missing_bracket(open: char, s: &str) -> ParseError {
+    crate::__private::literal_error(open, s)
+}
+
+#[cfg(not(feature = "alloc"))]
+fn missing_bracket(_open: char, _s: &str) -> ParseError {
+    ParseError::Literal
+}
+
+/// Returns the text inside the brackets at the start of `s` and the text after the matching closing bracket.
+fn match_balanced(s: &str, open: char, close: char) -> Result<(&str, &str), ParseError> {
+    let inner = s
+        .strip_prefix(open)
+        .ok_or_else(|| missing_bracket(open, s))?;
+    let mut depth = 0_usize;
+    for (idx, c) in inner.char_indices() {
+        if c == close {
+            match depth.checked_sub(1) {
+                Some(d) => depth = d,
+                None => return Ok((&inner[..idx], &inner[idx + close.len_utf8()..])),
+            }
+        } else if c == open {
+            depth += 1;
+        }
+    }
+    Err(ParseError::Unbalanced { open, close })
+}
+
+#[doc(hidden)]
+/// Not part of public api, used by bracket captures that are at the start of `s`.
+pub fn strip_balanced(s: &str, open: char, close: char) -> Result<(&str, &str), ParseError> {
+    match_balanced(s, open, close)
+}
+
+#[doc(hidden)]
+/// Not part of public api, used by bracket captures that follow another capture.
+///
+/// Returns the text before the first opening bracket, the text inside the brackets and the text after them.
+pub fn split_balanced(s: &str, open: char, close: char) -> Result<(&str, &str, &str), ParseError> {
+    let start = s.find(open).ok_or_else(|| missing_bracket(open, s))?;
+    let (inner, rest) = match_balanced(&s[start..], open, close)?;
+    Ok((&s[..start], inner, rest))
+}
+
+#[doc(hidden)]
+/// Not part of public api, used by single char captures to split the first char off `s`.
+pub fn split_char(s: &str) -> Result<(&str, &str), ParseError> {
+    match s.chars().next() {
+        Some(c) => Ok(s.split_at(c.len_utf8())),
+        None => Err(ParseError::other(
+            "expected to find a char but found the end of the input.",
+        )),
+    }
+}
+
+#[doc(hidden)]
+/// Not part of public api, used by line captures to split the current line off `s`.
+/// The rest starts at the `\n` ending the line, and a `\r` before it is not part of the line.
+pub fn split_line(s: &str) -> (&str, &str) {
+    let (line, rest) = s.split_at(s.find('\n').unwrap_or(s.len()));
+    (line.strip_suffix('\r').unwrap_or(line), rest)
+}
+
+/// Returns the index of the quote closing the quoted string at the start of `s`, a backslash escapes the char after it.
+fn closing_quote(s: &str) -> Option<usize> {
+    let mut chars = s.char_indices().skip(1);
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '"' => return Some(idx),
+            _ => {}
+        }
+    }
+    None
+}
+
+#[doc(hidden)]
+/// Not part of public api, used by escaped quote captures to split `s` on the first match of a literal after the quotes.
+///
+/// If `s` does not start with a quoted string it is split like [`split_literal`].
+pub fn split_quoted<'a>(
+    s: &'a str,
+    literal: &str,
+    case_insensitive: bool,
+    flexible_whitespace: bool,
+) -> Option<(&'a str, &'a str)> {
+    match s.starts_with('"').then(|| closing_quote(s)).flatten() {
+        Some(end) => {
+            let (quoted, rest) = s.split_at(end + 1);
+            let len = match_at(rest, literal, case_insensitive, flexible_whitespace)?;
+            Some((quoted, &rest[len..]))
+        }
+        None => split_literal(s, literal, case_insensitive, flexible_whitespace),
+    }
+}
+
+#[doc(hidden)]
+#[cfg(feature = "alloc")]
+/// Not part of public api, used by escaped quote captures to remove the quotes around `s` and un-escape it.
+///
+/// Only `\"` and `\\` are un-escaped, any other backslash is kept as is. Text that is not quoted is returned unchanged.
+pub fn unquote(s: &str) -> Result<Cow<'_, str>, ParseError> {
+    if !s.starts_with('"') {
+        return Ok(Cow::Borrowed(s));
+    }
+    if closing_quote(s) != Some(s.len() - 1) {
+        return Err(ParseError::other(format_args!(
+            "expected the quoted string {s:?} to end with its closing quote."
+        )));
+    }
+    let inner = &s[1..s.len() - 1];
+    if !inner.contains('\\') {
+        return Ok(Cow::Borrowed(inner));
+    }
+    let mut unescaped = String::with_capacity(inner.len());
+    let mut chars = inner.chars().peekable();
+    while let Some(c) = chars.next() {
+        match chars.peek() {
+            Some(&next @ ('"' | '\\')) if c == '\\' => {
+                unescaped.push(next);
+                chars.next();
+            }
+            _ => unescaped.push(c),
+        }
+    }
+    Ok(Cow::Owned(unescaped))
+}
+
+/// A single element of a shape that matches one char.
+#[derive(Clone, Copy)]
+enum ShapeAtom {
+    Digit,
+    Word,
+    Any,
+    Char(char),
+}
+
+impl ShapeAtom {
+    fn matches(self, c: char) -> bool {
+        match self {
+            ShapeAtom::Digit => c.is_ascii_digit(),
+            ShapeAtom::Word => c.is_alphanumeric() || c == '_',
+            ShapeAtom::Any => true,
+            ShapeAtom::Char(l) => c == l,
+        }
+    }
+}
+
+/// Returns whether the whole of `s` matches `shape`, trying every length of a repetition from the longest.
+fn matches_shape(s: &str, shape: &str) -> bool {
+    let mut shape_chars = shape.chars();
+    let atom = match shape_chars.next() {
+        None => return s.is_empty(),
+        Some('\\') => match shape_chars.next() {
+            Some('d') => ShapeAtom::Digit,
+            Some('w') => ShapeAtom::Word,
+            Some(c) => ShapeAtom::Char(c),
+            None => ShapeAtom::Char('\\'),
+        },
+        Some('.') => ShapeAtom::Any,
+        Some(c) => ShapeAtom::Char(c),
+    };
+    let rest = shape_chars.as_str();
+    match rest.chars().next() {
+        Some(quantifier @ ('*' | '+')) => {
+            let rest = &rest[1..];
+            let longest = s
+                .char_indices()
+                .find(|&(_, c)| !atom.matches(c))
+                .map_or(s.len(), |(idx, _)| idx);
+            core::iter::once(longest)
+                .chain(s[..longest].char_indices().rev().map(|(idx, _)| idx))
+                .filter(|&end| quantifier == '*' || end > 0)
+                .any(|end| matches_shape(&s[end..], rest))
+        }
+        _ => {
+            let mut chars = s.chars();
+            matches!(chars.next(), Some(c) if atom.matches(c))
+                && matches_shape(chars.as_str(), rest)
+        }
+    }
+}
+
+#[doc(hidden)]
+/// Not part of public api, used by the shape modifier to check the captured text before it is parsed.
+pub fn check_shape(s: &str, shape: &str) -> Result<(), ParseError> {
+    if matches_shape(s, shape) {
+        Ok(())
+    } else {
+        Err(ParseError::other(format_args!(
+            "{s:?} does not match the shape `{shape}`."
+        )))
+    }
+}
+
+/// Returns whether `s` looks like a DNS host name: at most 253 bytes of dot separated labels, each made of
+/// 1 to 63 ASCII alphanumerics or hyphens that do not start or end with a hyphen.
+fn is_host(s: &str) -> bool {
+    s.len() <= 253
+        && s.split('.').all(|label| {
+            (1..=63).contains(&label.len())
+                && label
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+        })
+}
+
+#[doc(hidden)]
+/// Not part of public api, used by the host modifier to check the captured text before it is parsed.
+pub fn check_host(s: &str) -> Result<(), ParseError> {
+    if is_host(s) {
+        Ok(())
+    } else {
+        Err(ParseError::other(format_args!(
+            "{s:?} is not a valid host name."
+        )))
+    }
+}
+
+#[doc(hidden)]
+/// Not part of public api, used by the email modifier to check the captured text before it is parsed.
+///
+/// There must be a single `@`, with a local part of at most 64 bytes without whitespace before it
+/// and a valid host name after it.
+pub fn check_email(s: &str) -> Result<(), ParseError> {
+    let valid = match s.split_once('@') {
+        Some((local, domain)) => {
+            (1..=64).contains(&local.len())
+                && !local.contains(|c: char| c.is_whitespace() || c.is_control() || c == '@')
+                && is_host(domain)
+        }
+        None => false,
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(ParseError::other(format_args!(
+            "{s:?} is not a valid email address."
+        )))
+    }
+}
@@ -0,0 +1,42 @@
+/// Where a capture starts in the input, returned next to every capture by [`parse_located!`](crate::parse_located).
+///
+/// Lines and columns start at 1, with the column counted in chars so it matches what an editor shows,
+/// while the offset is the index of the capture's first byte in the input.
+///
+/// ```
+/// # use prse::{parse_located, Location};
+/// let input = "name: alice\nage: 42";
+/// let ((name, at_name), (age, at_age)): ((&str, Location), (u32, Location)) =
+///     parse_located!(input, "name: {}\nage: {}");
+///
+/// assert_eq!(name, "alice");
+/// assert_eq!(at_name, Location { line: 1, col: 7, offset: 6 });
+/// assert_eq!(age, 42);
+/// assert_eq!(at_age, Location { line: 2, col: 6, offset: 17 });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Location {
+    /// The line of the capture, starting at 1.
+    pub line: usize,
+    /// The column of the capture in chars, starting at 1.
+    pub col: usize,
+    /// The byte offset of the capture from the start of the input.
+    pub offset: usize,
+}
+
+impl Location {
+    /// Finds the line and column of a byte offset in `input` by scanning the newlines before it.
+    ///
+    /// # Panics
+    ///
+    /// If `offset` is greater than the length of `input` or not on a char boundary.
+    pub fn new(input: &str, offset: usize) -> Self {
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+        Location {
+            line: before.matches('\n').count() + 1,
+            col: before[line_start..].chars().count() + 1,
+            offset,
+        }
+    }
+}
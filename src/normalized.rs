@@ -0,0 +1,139 @@
+extern crate alloc;
+use alloc::string::String;
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+
+use unicode_normalization::UnicodeNormalization;
+
+use crate::{Parse, ParseError};
+
+/// The normalization that [`Normalized`] applies before parsing.
+///
+/// [`Nfc`] and [`Nfkc`] implement the Unicode normalization forms, other normalizations such as
+/// case folding can be implemented on your own type.
+///
+/// ```
+/// # use prse::{parse, Normalized, Normalizer};
+/// struct Lowercase;
+///
+/// impl Normalizer for Lowercase {
+///     fn normalize(s: &str) -> String {
+///         s.to_lowercase()
+///     }
+/// }
+///
+/// let name: Normalized<String, Lowercase> = parse!("name: ReNé", "name: {}");
+/// assert_eq!(*name, "rené");
+/// ```
+pub trait Normalizer {
+    /// Returns `s` in the normalization form.
+    fn normalize(s: &str) -> String;
+}
+
+/// The canonical composition normalization form, NFC.
+///
+/// It composes the chars that are canonically equivalent, such as an `e` followed by a combining acute
+/// accent into an `é`, while keeping chars that only look alike, such as the `ﬁ` ligature, distinct.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Nfc;
+
+impl Normalizer for Nfc {
+    fn normalize(s: &str) -> String {
+        s.nfc().collect()
+    }
+}
+
+/// The compatibility composition normalization form, NFKC.
+///
+/// Like [`Nfc`], but chars that only differ in their presentation are also made equal,
+/// such as the `ﬁ` ligature and `fi` or the fullwidth `Ａ` and `A`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Nfkc;
+
+impl Normalizer for Nfkc {
+    fn normalize(s: &str) -> String {
+        s.nfkc().collect()
+    }
+}
+
+/// A `T` parsed from the captured string after it has been normalized by `N`, which is [`Nfc`] by default.
+///
+/// Visually identical strings can be made of different chars, such as `é` which is either a single char or
+/// an `e` followed by a combining accent. Normalizing them first means that they are parsed into equal values.
+/// Since the normalized string is a temporary, `T` cannot borrow from it and is usually a [`String`].
+///
+/// This type is only available with the `unicode` feature.
+///
+/// ```
+/// # use prse::{parse, Nfkc, Normalized};
+/// let composed: Normalized<String> = parse!("name: Ren\u{e9}", "name: {}");
+/// let decomposed: Normalized<String> = parse!("name: Rene\u{301}", "name: {}");
+/// assert_eq!(composed, decomposed);
+///
+/// let ligature: Normalized<String, Nfkc> = parse!("name: \u{fb01}nn", "name: {}");
+/// assert_eq!(*ligature, "finn");
+/// ```
+pub struct Normalized<T, N = Nfc> {
+    /// The value parsed from the normalized string.
+    pub value: T,
+    normalizer: PhantomData<fn() -> N>,
+}
+
+impl<T, N> Normalized<T, N> {
+    /// Consumes the `Normalized`, returning the parsed value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, N> Deref for Normalized<T, N> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T, N> DerefMut for Normalized<T, N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+impl<T: core::fmt::Debug, N> core::fmt::Debug for Normalized<T, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("Normalized").field(&self.value).finish()
+    }
+}
+
+impl<T: Clone, N> Clone for Normalized<T, N> {
+    fn clone(&self) -> Self {
+        Normalized {
+            value: self.value.clone(),
+            normalizer: PhantomData,
+        }
+    }
+}
+
+impl<T: PartialEq, N> PartialEq for Normalized<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Eq, N> Eq for Normalized<T, N> {}
+
+impl<T: core::hash::Hash, N> core::hash::Hash for Normalized<T, N> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+impl<'a, T: for<'b> Parse<'b>, N: Normalizer> Parse<'a> for Normalized<T, N> {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        Ok(Normalized {
+            value: T::from_str(&N::normalize(s))?,
+            normalizer: PhantomData,
+        })
+    }
+}
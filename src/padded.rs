@@ -0,0 +1,50 @@
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use crate::ParseError;
+
+/// What [`parse_padded!`](crate::parse_padded) does with the elements past the length it pads to.
+///
+/// This type is only available with the `alloc` feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Overflow {
+    /// The extra elements are dropped without being parsed.
+    #[default]
+    Truncate,
+    /// An input with too many elements is an error, which is returned after parsing the extra elements.
+    Error,
+}
+
+#[doc(hidden)]
+/// Not part of public api, used by `parse_padded!` to collect the elements into a Vec of exactly `len` elements.
+pub fn pad<T, I>(
+    result: Result<I, ParseError>,
+    len: usize,
+    default: T,
+    overflow: Overflow,
+) -> Result<Vec<T>, ParseError>
+where
+    I: Iterator<Item = Result<T, ParseError>>,
+    T: Clone,
+{
+    let mut elements = result?;
+    let mut padded = Vec::with_capacity(len);
+    for element in elements.by_ref().take(len) {
+        padded.push(element?);
+    }
+    if overflow == Overflow::Error {
+        let mut found = len;
+        for element in elements {
+            element?;
+            found += 1;
+        }
+        if found != len {
+            return Err(ParseError::other(format_args!(
+                "expected at most {len} elements, found {found}."
+            )));
+        }
+    }
+    padded.resize(len, default);
+    Ok(padded)
+}
@@ -1,23 +1,78 @@
 use core::char::ParseCharError;
-use core::num::{ParseFloatError, ParseIntError};
-use core::str::ParseBoolError;
+use core::num::{IntErrorKind, ParseFloatError, ParseIntError, TryFromIntError};
+use core::str::{ParseBoolError, Utf8Error};
 
 #[cfg(feature = "alloc")]
 extern crate alloc;
 #[cfg(feature = "alloc")]
 use alloc::boxed::Box;
 #[cfg(feature = "alloc")]
-use alloc::string::String;
-#[cfg(feature = "alloc")]
 use alloc::string::ToString;
+#[cfg(feature = "alloc")]
+use alloc::string::{FromUtf8Error, String};
 
+#[cfg(all(feature = "inline-message", not(feature = "alloc")))]
+use crate::InlineMessage;
 #[cfg(feature = "std")]
 use std::error;
 #[cfg(feature = "std")]
 use std::net::AddrParseError;
 
 /// The error returned when trying to parse a type using [`try_parse`](crate::try_parse) or [`Parse`](crate::Parse).
+///
+/// With the `std` feature, `ParseError` implements [`Error`](std::error::Error) and is `Send + Sync + 'static`,
+/// so it can be wrapped by your own error type, or returned with `?` from a function returning
+/// [`anyhow::Result`](https://docs.rs/anyhow). It can also be converted into an [`io::Error`](std::io::Error),
+/// see [`into_io_error`](ParseError::into_io_error), and [`ParseErrorExt`](crate::ParseErrorExt) has helpers
+/// for converting it into the errors of `std` and, with the `anyhow` feature, of `anyhow`.
+///
+/// The [`Display`](core::fmt::Display) implementation only relies on [`core::fmt`], so without the `alloc`
+/// feature every variant can be written into a fixed buffer implementing [`core::fmt::Write`].
+/// ```
+/// # use prse::{try_parse, ParseError};
+/// #[derive(Debug)]
+/// enum ConfigError {
+///     Parse(ParseError),
+///     MissingKey(&'static str),
+/// }
+///
+/// impl From<ParseError> for ConfigError {
+///     fn from(error: ParseError) -> Self {
+///         ConfigError::Parse(error)
+///     }
+/// }
+///
+/// fn port(line: &str) -> Result<u16, ConfigError> {
+///     Ok(try_parse!(line, "port = {}")?)
+/// }
+///
+/// assert!(matches!(port("port = http"), Err(ConfigError::Parse(_))));
+/// ```
+///
+/// With [`thiserror`](https://docs.rs/thiserror), the recommended pattern is a variant with a `#[from]`
+/// `ParseError` source, which generates the same `From` implementation and keeps the error as the source.
+/// ```
+/// # use prse::{try_parse, ParseError};
+/// # use std::error::Error;
+/// #[derive(Debug, thiserror::Error)]
+/// enum ConfigError {
+///     #[error("invalid config line")]
+///     Parse(#[from] ParseError),
+///     #[error("missing key {0}")]
+///     MissingKey(&'static str),
+/// }
+///
+/// fn port(line: &str) -> Result<u16, ConfigError> {
+///     Ok(try_parse!(line, "port = {}")?)
+/// }
+///
+/// let error = port("port = http").unwrap_err();
+/// assert!(error.source().unwrap().is::<ParseError>());
+/// ```
+///
+/// New variants can be added in a minor release, so a `match` on a `ParseError` needs a wildcard arm.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum ParseError {
     /// The variant returned when an integer cannot be parsed.
     Int(ParseIntError),
@@ -27,6 +82,10 @@ pub enum ParseError {
     Char(ParseCharError),
     /// The variant returned when a float cannot be parsed.
     Float(ParseFloatError),
+    /// The variant returned when an integer does not fit into the type it is converted to.
+    TryFromInt(TryFromIntError),
+    /// The variant returned when bytes are not valid UTF-8.
+    Utf8(Utf8Error),
     #[cfg(feature = "std")]
     /// The variant returned when an ip address cannot be parsed.
     /// This variant is only enabled with the `std` feature.
@@ -39,17 +98,37 @@ pub enum ParseError {
     Dyn(Box<dyn error::Error + Send + Sync>),
     /// The variant returned when [`parse!`](crate::parse) found an unexpected literal.
     /// When not using the `alloc` feature, `Literal` is a unit variant.
+    ///
+    /// The strings are kept as is, but they are displayed with their special chars escaped
+    /// like [`char::escape_debug`], so control chars such as `\n` or `\0` in the input cannot corrupt log output.
     #[cfg(feature = "alloc")]
     Literal {
         /// What it expected.
         expected: String,
-        /// What it actually found.
+        /// What it actually found, starting at the mismatch and cut after
+        /// [`LITERAL_WINDOW`](ParseError::LITERAL_WINDOW) chars, or the window given to
+        /// [`literal_with_window`](ParseError::literal_with_window).
         found: String,
     },
     /// The variant returned when [`parse!`](crate::parse) found an unexpected literal.
     /// When not using the `alloc` feature, `Literal` is a unit variant.
     #[cfg(not(feature = "alloc"))]
     Literal,
+    /// The variant returned when [`parse!`](crate::parse) found a back-reference that does not match
+    /// the text captured by the first occurrence of the same named capture.
+    /// When not using the `alloc` feature, `BackReference` is a unit variant.
+    #[cfg(feature = "alloc")]
+    BackReference {
+        /// The text captured by the first occurrence.
+        expected: String,
+        /// What it actually found.
+        found: String,
+    },
+    /// The variant returned when [`parse!`](crate::parse) found a back-reference that does not match
+    /// the text captured by the first occurrence of the same named capture.
+    /// When not using the `alloc` feature, `BackReference` is a unit variant.
+    #[cfg(not(feature = "alloc"))]
+    BackReference,
     /// The variant returned when parsing an array and finding more or less elements than what was expected.
     Array {
         /// The size of the array it was expecting.
@@ -57,14 +136,62 @@ pub enum ParseError {
         /// The size of the array it found.
         found: u8,
     },
+    /// The variant returned when [`parse!`](crate::parse) found an opening bracket without a matching closing bracket.
+    Unbalanced {
+        /// The opening bracket.
+        open: char,
+        /// The closing bracket that was not found.
+        close: char,
+    },
+    /// The variant returned when a repetition has more elements than the limit set with its `max` modifier.
+    TooManyElements {
+        /// The maximum number of elements that was allowed.
+        max: usize,
+    },
     /// A variant that can be used when you need to return a simple error.
     /// When not using the `alloc` feature, `Other` is a unit variant.
     #[cfg(feature = "alloc")]
     Other(String),
     /// A variant that can be used when you need to return a simple error.
     /// When not using the `alloc` feature, `Other` is a unit variant.
-    #[cfg(not(feature = "alloc"))]
+    /// Unless the `inline-message` feature is enabled, in which case it holds an [`InlineMessage`].
+    #[cfg(all(feature = "inline-message", not(feature = "alloc")))]
+    Other(InlineMessage),
+    /// A variant that can be used when you need to return a simple error.
+    /// When not using the `alloc` feature, `Other` is a unit variant.
+    /// Unless the `inline-message` feature is enabled, in which case it holds an [`InlineMessage`].
+    #[cfg(not(any(feature = "alloc", feature = "inline-message")))]
     Other,
+    /// A variant like `Other` that also holds a numeric code, so that errors can be matched on,
+    /// created with [`with_code`](ParseError::with_code).
+    /// When not using the `alloc` feature, `Coded` only holds the code.
+    #[cfg(feature = "alloc")]
+    Coded {
+        /// The code identifying the error.
+        code: u32,
+        /// The message of the error.
+        message: String,
+    },
+    /// A variant like `Other` that also holds a numeric code, so that errors can be matched on,
+    /// created with [`with_code`](ParseError::with_code).
+    /// When not using the `alloc` feature, `Coded` only holds the code.
+    /// Unless the `inline-message` feature is enabled, in which case it also holds an [`InlineMessage`].
+    #[cfg(all(feature = "inline-message", not(feature = "alloc")))]
+    Coded {
+        /// The code identifying the error.
+        code: u32,
+        /// The message of the error.
+        message: InlineMessage,
+    },
+    /// A variant like `Other` that also holds a numeric code, so that errors can be matched on,
+    /// created with [`with_code`](ParseError::with_code).
+    /// When not using the `alloc` feature, `Coded` only holds the code.
+    /// Unless the `inline-message` feature is enabled, in which case it also holds an [`InlineMessage`].
+    #[cfg(not(any(feature = "alloc", feature = "inline-message")))]
+    Coded {
+        /// The code identifying the error.
+        code: u32,
+    },
     #[cfg(feature = "alloc")]
     /// A variant that wraps a [`ParseError`] to add more context about the error
     /// when parsing repetition sequences.
@@ -85,6 +212,8 @@ pub enum ParseError {
         full_string: String,
         /// The string that cause the parsing to fail
         failed_item: String,
+        /// The name of the capture that failed to parse, if it was a named capture
+        field_name: Option<String>,
         /// The wrapped error
         error: Box<ParseError>,
     },
@@ -123,6 +252,190 @@ impl ParseError {
     }
 }
 
+#[cfg(all(feature = "inline-message", not(feature = "alloc")))]
+impl ParseError {
+    /// Create a new ParseError from a printable error message.
+    ///
+    /// Without the `alloc` feature the message is stored in an [`InlineMessage`],
+    /// and is truncated if it is too long.
+    pub fn new<T: core::fmt::Display>(message: T) -> Self {
+        Self::Other(InlineMessage::new(message))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl ParseError {
+    /// The number of chars of the input kept in the `found` field of the [`ParseError::Literal`] errors
+    /// returned by the macros.
+    ///
+    /// When a literal cannot be matched, `found` starts at the point of the mismatch and is cut after
+    /// this many chars followed by `...`, so that large inputs do not get copied into the error.
+    /// Use [`literal_with_window`](Self::literal_with_window) to create a literal error with another window.
+    /// This constant is only available with the `alloc` feature.
+    ///
+    /// ```
+    /// # use prse::{try_parse, ParseError};
+    /// let input = "a".repeat(100);
+    ///
+    /// let result: Result<&str, _> = try_parse!(input, "{};");
+    /// let ParseError::Literal { found, .. } = result.unwrap_err() else { unreachable!() };
+    /// assert_eq!(found, format!("{}...", "a".repeat(ParseError::LITERAL_WINDOW)));
+    /// ```
+    pub const LITERAL_WINDOW: usize = 64;
+
+    /// Creates a [`ParseError::Literal`] whose `found` is cut after `window` chars followed by `...`.
+    ///
+    /// `found` should start at the point of the mismatch, so that the kept chars are the ones around it.
+    /// A `window` of `usize::MAX` never truncates.
+    /// This function is only available with the `alloc` feature.
+    ///
+    /// ```
+    /// # use prse::ParseError;
+    /// let input = "key: a value that goes on and on";
+    ///
+    /// let error = ParseError::literal_with_window("=", &input[3..], 8);
+    /// assert_eq!(
+    ///     error,
+    ///     ParseError::Literal { expected: "=".into(), found: ": a valu...".into() }
+    /// );
+    /// ```
+    pub fn literal_with_window(expected: impl ToString, found: &str, window: usize) -> Self {
+        let found = match truncate_chars(found, window) {
+            Some(truncated) => {
+                let mut truncated = String::from(truncated);
+                truncated.push_str("...");
+                truncated
+            }
+            None => found.to_string(),
+        };
+        ParseError::Literal {
+            expected: expected.to_string(),
+            found,
+        }
+    }
+}
+
+impl ParseError {
+    /// Returns the kind of integer error if this error was caused by failing to parse an integer.
+    ///
+    /// This looks through the [`Context`](ParseError::Context) and [`MultiContext`](ParseError::MultiContext)
+    /// variants, so it can be used to tell why a capture failed, for example to tell an overflow apart
+    /// from an invalid digit.
+    /// ```
+    /// # use prse::try_parse;
+    /// use core::num::IntErrorKind;
+    ///
+    /// let small: Result<u8, _> = try_parse!("Value: 256", "Value: {}");
+    /// assert_eq!(small.unwrap_err().int_error_kind(), Some(&IntErrorKind::PosOverflow));
+    ///
+    /// let invalid: Result<[u8; 2], _> = try_parse!("Value: 1,a", "Value: {:,:2}");
+    /// assert_eq!(invalid.unwrap_err().int_error_kind(), Some(&IntErrorKind::InvalidDigit));
+    /// ```
+    pub fn int_error_kind(&self) -> Option<&IntErrorKind> {
+        match self {
+            ParseError::Int(e) => Some(e.kind()),
+            #[cfg(feature = "std")]
+            ParseError::Dyn(e) => e.downcast_ref::<ParseIntError>().map(ParseIntError::kind),
+            #[cfg(feature = "alloc")]
+            ParseError::Context { error, .. } | ParseError::MultiContext { error, .. } => {
+                error.int_error_kind()
+            }
+            _ => None,
+        }
+    }
+
+    /// Create a new ParseError with a code that identifies it, as well as a printable error message.
+    ///
+    /// This stores both into the [`Coded`](ParseError::Coded) variant, so a library built on prse can give its
+    /// errors stable codes that can be matched on without defining its own error type.
+    /// The message is discarded when not using the `alloc` or `inline-message` features.
+    /// ```
+    /// # use prse::{try_parse, Parse, ParseError};
+    /// const NOT_EVEN: u32 = 1;
+    ///
+    /// #[derive(Debug)]
+    /// struct Even(u32);
+    ///
+    /// impl<'a> Parse<'a> for Even {
+    ///     fn from_str(s: &'a str) -> Result<Self, ParseError> {
+    ///         match s.parse::<u32>()? {
+    ///             n if n % 2 == 0 => Ok(Even(n)),
+    ///             n => Err(ParseError::with_code(NOT_EVEN, format_args!("{n} is not even."))),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let odd: Result<Even, _> = try_parse!("Count: 3", "Count: {}");
+    /// assert_eq!(odd.unwrap_err().error_code(), Some(NOT_EVEN));
+    /// ```
+    pub fn with_code<T: core::fmt::Display>(code: u32, message: T) -> Self {
+        #[cfg(feature = "alloc")]
+        {
+            Self::Coded {
+                code,
+                message: message.to_string(),
+            }
+        }
+        #[cfg(all(feature = "inline-message", not(feature = "alloc")))]
+        {
+            Self::Coded {
+                code,
+                message: InlineMessage::new(message),
+            }
+        }
+        #[cfg(not(any(feature = "alloc", feature = "inline-message")))]
+        {
+            let _ = message;
+            Self::Coded { code }
+        }
+    }
+
+    /// Returns the code of the error if it was created with [`with_code`](ParseError::with_code).
+    ///
+    /// Like [`root_cause`](ParseError::root_cause), this looks through the [`Context`](ParseError::Context)
+    /// and [`MultiContext`](ParseError::MultiContext) variants.
+    pub fn error_code(&self) -> Option<u32> {
+        match self.root_cause() {
+            ParseError::Coded { code, .. } => Some(*code),
+            _ => None,
+        }
+    }
+
+    /// Returns the innermost error, skipping every [`Context`](ParseError::Context) and
+    /// [`MultiContext`](ParseError::MultiContext) that wraps it.
+    /// ```
+    /// # use prse::{try_parse, ParseError};
+    /// let error = try_parse!("Value: 1.5", "Value: {}").map(|v: u32| v).unwrap_err();
+    /// assert!(matches!(error.root_cause(), ParseError::Int(_)));
+    /// ```
+    pub fn root_cause(&self) -> &ParseError {
+        match self {
+            #[cfg(feature = "alloc")]
+            ParseError::Context { error, .. } | ParseError::MultiContext { error, .. } => {
+                error.root_cause()
+            }
+            error => error,
+        }
+    }
+
+    /// Creates an `Other` error, the message is discarded when not using the `alloc` or `inline-message` features.
+    pub(crate) fn other(message: impl core::fmt::Display) -> Self {
+        #[cfg(feature = "alloc")]
+        {
+            Self::Other(message.to_string())
+        }
+        #[cfg(all(feature = "inline-message", not(feature = "alloc")))]
+        {
+            Self::Other(InlineMessage::new(message))
+        }
+        #[cfg(not(any(feature = "alloc", feature = "inline-message")))]
+        {
+            let _ = message;
+            Self::Other
+        }
+    }
+}
+
 #[cfg(feature = "std")]
 impl error::Error for ParseError {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
@@ -131,15 +444,62 @@ impl error::Error for ParseError {
             ParseError::Bool(source) => Some(source),
             ParseError::Char(source) => Some(source),
             ParseError::Float(source) => Some(source),
+            ParseError::TryFromInt(source) => Some(source),
+            ParseError::Utf8(source) => Some(source),
             ParseError::Addr(source) => Some(source),
             ParseError::Dyn(source) => Some(&**source),
             ParseError::MultiContext { error, .. } => Some(error),
             ParseError::Context { error, .. } => Some(error),
-            ParseError::Literal { .. } | ParseError::Array { .. } | ParseError::Other(_) => None,
+            ParseError::Literal { .. }
+            | ParseError::BackReference { .. }
+            | ParseError::Array { .. }
+            | ParseError::Unbalanced { .. }
+            | ParseError::TooManyElements { .. }
+            | ParseError::Other(_)
+            | ParseError::Coded { .. } => None,
         }
     }
 }
 
+#[cfg(feature = "std")]
+impl ParseError {
+    /// Converts the error into an [`io::Error`](std::io::Error) of kind [`InvalidData`](std::io::ErrorKind::InvalidData).
+    ///
+    /// The `ParseError` is kept as the inner error, so it can be recovered using
+    /// [`get_ref`](std::io::Error::get_ref) and downcasting. `ParseError` also implements
+    /// `Into<io::Error>`, so `?` can be used in functions returning an [`io::Result`](std::io::Result).
+    /// This function is only available with the `std` feature.
+    ///
+    /// ```
+    /// # use prse::{try_parse, ParseError};
+    /// use std::io::{BufRead, ErrorKind};
+    ///
+    /// fn sum_lines(input: impl BufRead) -> std::io::Result<u64> {
+    ///     let mut sum = 0;
+    ///     for line in input.lines() {
+    ///         let n: u64 = try_parse!(line?, "{}")?;
+    ///         sum += n;
+    ///     }
+    ///     Ok(sum)
+    /// }
+    ///
+    /// assert_eq!(sum_lines("1\n2\n3".as_bytes()).unwrap(), 6);
+    /// let error = sum_lines("1\ntwo".as_bytes()).unwrap_err();
+    /// assert_eq!(error.kind(), ErrorKind::InvalidData);
+    /// assert!(error.get_ref().unwrap().is::<ParseError>());
+    /// ```
+    pub fn into_io_error(self) -> std::io::Error {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<ParseError> for std::io::Error {
+    fn from(error: ParseError) -> Self {
+        error.into_io_error()
+    }
+}
+
 impl core::fmt::Display for ParseError {
     fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
         match self {
@@ -147,6 +507,8 @@ impl core::fmt::Display for ParseError {
             ParseError::Bool(_) => write!(fmt, "unable to parse as a boolean"),
             ParseError::Char(_) => write!(fmt, "unable to parse as a character"),
             ParseError::Float(_) => write!(fmt, "unable to parse as a float"),
+            ParseError::TryFromInt(_) => write!(fmt, "integer out of range for the target type"),
+            ParseError::Utf8(_) => write!(fmt, "invalid UTF-8"),
             #[cfg(feature = "std")]
             ParseError::Addr(_) => write!(fmt, "unable to parse as an address"),
             #[cfg(feature = "std")]
@@ -158,36 +520,107 @@ impl core::fmt::Display for ParseError {
             ),
             #[cfg(not(feature = "alloc"))]
             ParseError::Literal => write!(fmt, "invalid literal match"),
+            #[cfg(feature = "alloc")]
+            ParseError::BackReference { expected, found } => write!(
+                fmt,
+                "invalid back-reference match (expected to find {expected:?}, found {found:?})"
+            ),
+            #[cfg(not(feature = "alloc"))]
+            ParseError::BackReference => write!(fmt, "invalid back-reference match"),
             ParseError::Array { expected, found } => write!(
                 fmt,
                 "invalid number of items (expected to find {expected:?}, found {found:?})"
             ),
+            ParseError::Unbalanced { open, close } => write!(
+                fmt,
+                "unbalanced brackets (expected to find {close:?} closing {open:?})"
+            ),
+            ParseError::TooManyElements { max } => {
+                write!(fmt, "too many items (expected at most {max})")
+            }
             #[cfg(feature = "alloc")]
             ParseError::Other(message) => write!(fmt, "{message}"),
-            #[cfg(not(feature = "alloc"))]
+            #[cfg(all(feature = "inline-message", not(feature = "alloc")))]
+            ParseError::Other(message) => write!(fmt, "{message}"),
+            #[cfg(not(any(feature = "alloc", feature = "inline-message")))]
             ParseError::Other => write!(fmt, "unable to parse into type"),
+            #[cfg(any(feature = "alloc", feature = "inline-message"))]
+            ParseError::Coded { message, .. } => write!(fmt, "{message}"),
+            #[cfg(not(any(feature = "alloc", feature = "inline-message")))]
+            ParseError::Coded { code } => write!(fmt, "unable to parse into type (code {code})"),
             #[cfg(feature = "alloc")]
+            ParseError::MultiContext { .. } | ParseError::Context { .. } => {
+                self.write_chain(fmt, ":\n\t")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl ParseError {
+    /// Returns the message of the error and of every error it wraps on a single line, joined by `: `.
+    ///
+    /// The [`Display`](core::fmt::Display) implementation puts each [`Context`](ParseError::Context) and
+    /// [`MultiContext`](ParseError::MultiContext) on its own indented line, which is easier to read
+    /// but does not suit logs that expect one line per entry.
+    /// This function is only available with the `alloc` feature.
+    ///
+    /// ```
+    /// # use prse::try_parse;
+    /// let error = try_parse!("numbers: 1,x", "numbers: {:,:}")
+    ///     .map(|v: Vec<u32>| v)
+    ///     .unwrap_err();
+    /// assert_eq!(
+    ///     error.to_single_line(),
+    ///     r#"unable to parse multi-item "x" when parsing "1,x": unable to parse as an integer"#
+    /// );
+    /// ```
+    pub fn to_single_line(&self) -> String {
+        let mut line = String::new();
+        self.write_chain(&mut line, ": ")
+            .expect("writing to a String does not fail");
+        line
+    }
+
+    /// Writes the message of every context in the chain followed by `separator`, and then the innermost error.
+    fn write_chain(&self, out: &mut dyn core::fmt::Write, separator: &str) -> core::fmt::Result {
+        match self {
             ParseError::MultiContext {
                 multi_string,
                 failed_string: failed_item,
                 error,
             } => {
                 write!(
-                    fmt,
-                    "unable to parse multi-item \"{failed_item}\" when parsing \"{multi_string}\":\n\t{error}"
-                )
+                    out,
+                    "unable to parse multi-item \"{failed_item}\" when parsing \"{multi_string}\"{separator}"
+                )?;
+                error.write_chain(out, separator)
             }
-            #[cfg(feature = "alloc")]
             ParseError::Context {
                 full_string,
                 failed_item,
+                field_name: None,
                 error,
             } => {
                 write!(
-                    fmt,
-                    "unable to parse \"{failed_item}\" when parsing \"{full_string}\":\n\t{error}"
-                )
+                    out,
+                    "unable to parse \"{failed_item}\" when parsing \"{full_string}\"{separator}"
+                )?;
+                error.write_chain(out, separator)
             }
+            ParseError::Context {
+                full_string,
+                failed_item,
+                field_name: Some(field_name),
+                error,
+            } => {
+                write!(
+                    out,
+                    "unable to parse \"{failed_item}\" when parsing \"{full_string}\" while parsing field `{field_name}`{separator}"
+                )?;
+                error.write_chain(out, separator)
+            }
+            error => write!(out, "{error}"),
         }
     }
 }
@@ -201,6 +634,8 @@ impl PartialEq for ParseError {
             (E::Bool(x), E::Bool(y)) => x == y,
             (E::Char(x), E::Char(y)) => x == y,
             (E::Float(x), E::Float(y)) => x == y,
+            (E::TryFromInt(x), E::TryFromInt(y)) => x == y,
+            (E::Utf8(x), E::Utf8(y)) => x == y,
             #[cfg(feature = "std")]
             (E::Addr(x), E::Addr(y)) => x == y,
             #[cfg(feature = "alloc")]
@@ -216,6 +651,19 @@ impl PartialEq for ParseError {
             ) => lx == rx && ly == ry,
             #[cfg(not(feature = "alloc"))]
             (E::Literal, E::Literal) => true,
+            #[cfg(feature = "alloc")]
+            (
+                E::BackReference {
+                    expected: lx,
+                    found: ly,
+                },
+                E::BackReference {
+                    expected: rx,
+                    found: ry,
+                },
+            ) => lx == rx && ly == ry,
+            #[cfg(not(feature = "alloc"))]
+            (E::BackReference, E::BackReference) => true,
             (
                 E::Array {
                     expected: lx,
@@ -226,10 +674,36 @@ impl PartialEq for ParseError {
                     found: ry,
                 },
             ) => lx == rx && ly == ry,
+            (
+                E::Unbalanced {
+                    open: lo,
+                    close: lc,
+                },
+                E::Unbalanced {
+                    open: ro,
+                    close: rc,
+                },
+            ) => lo == ro && lc == rc,
+            (E::TooManyElements { max: x }, E::TooManyElements { max: y }) => x == y,
             #[cfg(feature = "alloc")]
             (E::Other(x), E::Other(y)) => x == y,
-            #[cfg(not(feature = "alloc"))]
+            #[cfg(all(feature = "inline-message", not(feature = "alloc")))]
+            (E::Other(x), E::Other(y)) => x == y,
+            #[cfg(not(any(feature = "alloc", feature = "inline-message")))]
             (E::Other, E::Other) => true,
+            #[cfg(any(feature = "alloc", feature = "inline-message"))]
+            (
+                E::Coded {
+                    code: lc,
+                    message: lm,
+                },
+                E::Coded {
+                    code: rc,
+                    message: rm,
+                },
+            ) => lc == rc && lm == rm,
+            #[cfg(not(any(feature = "alloc", feature = "inline-message")))]
+            (E::Coded { code: lc }, E::Coded { code: rc }) => lc == rc,
             #[cfg(feature = "alloc")]
             (
                 E::MultiContext {
@@ -248,14 +722,16 @@ impl PartialEq for ParseError {
                 E::Context {
                     full_string: ls,
                     failed_item: lf,
+                    field_name: ln,
                     error: le,
                 },
                 E::Context {
                     full_string: rs,
                     failed_item: rf,
+                    field_name: rn,
                     error: re,
                 },
-            ) => ls == rs && lf == rf && le == re,
+            ) => ls == rs && lf == rf && ln == rn && le == re,
             _ => false,
         }
     }
@@ -275,11 +751,20 @@ impl_from_parse_error!(ParseIntError, Int);
 impl_from_parse_error!(ParseBoolError, Bool);
 impl_from_parse_error!(ParseCharError, Char);
 impl_from_parse_error!(ParseFloatError, Float);
+impl_from_parse_error!(TryFromIntError, TryFromInt);
+impl_from_parse_error!(Utf8Error, Utf8);
 #[cfg(feature = "std")]
 impl_from_parse_error!(AddrParseError, Addr);
 #[cfg(feature = "std")]
 impl_from_parse_error!(Box<dyn error::Error + Send + Sync>, Dyn);
 
+#[cfg(feature = "alloc")]
+impl From<FromUtf8Error> for ParseError {
+    fn from(source: FromUtf8Error) -> Self {
+        ParseError::Utf8(source.utf8_error())
+    }
+}
+
 #[cfg(feature = "alloc")]
 impl From<()> for ParseError {
     fn from(_: ()) -> Self {
@@ -290,7 +775,7 @@ impl From<()> for ParseError {
 #[cfg(not(feature = "alloc"))]
 impl From<()> for ParseError {
     fn from(_: ()) -> Self {
-        ParseError::Other
+        ParseError::other("Error: ()")
     }
 }
 
@@ -310,12 +795,133 @@ pub mod __private {
     }
 
     #[doc(hidden)]
-    #[cfg(not(feature = "alloc"))]
+    /// Not part of public api used to run the validator of `try_parse_validated`.
+    pub fn validate<T, F>(result: Result<T, ParseError>, validator: F) -> Result<T, ParseError>
+    where
+        F: FnOnce(&T) -> Result<(), ParseError>,
+    {
+        let value = result?;
+        validator(&value)?;
+        Ok(value)
+    }
+
+    #[doc(hidden)]
+    /// Not part of public api used to pass each element of `parse_for_each` to its callback.
+    pub fn for_each<T, I, F>(
+        result: Result<I, ParseError>,
+        mut callback: F,
+    ) -> Result<(), ParseError>
+    where
+        I: Iterator<Item = Result<T, ParseError>>,
+        F: FnMut(T),
+    {
+        for element in result? {
+            callback(element?);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
+    pub use crate::literal::unquote;
+    pub use crate::literal::{
+        check_email, check_host, check_shape, rsplit_literal, split_balanced, split_char,
+        split_line, split_literal, split_quoted, strip_balanced, strip_literal,
+    };
+    #[cfg(feature = "alloc")]
+    pub use crate::padded::pad;
+    #[cfg(feature = "alloc")]
+    pub use crate::parse_iterators::{split_doubled, split_records};
+    pub use crate::radix::{parse_grouped_radix, parse_radix};
+    pub use crate::wrappers::{check_expected, unmapped_key, MapEntry};
+
+    #[doc(hidden)]
+    #[cfg(feature = "alloc")]
+    /// Not part of public api used to create a literal error with a truncated `found`.
+    pub fn literal_error(expected: impl ToString, found: &str) -> ParseError {
+        ParseError::literal_with_window(expected, found, ParseError::LITERAL_WINDOW)
+    }
+
+    #[doc(hidden)]
+    /// Not part of public api used when a discriminant does not match any variant with `from_repr`.
+    pub fn unknown_discriminant(value: impl core::fmt::Display, name: &str) -> ParseError {
+        ParseError::other(format_args!(
+            "{value} does not match the discriminant of any variant of {name}."
+        ))
+    }
+
+    #[doc(hidden)]
+    /// Not part of public api used when an integer is not in the range of any variant with `range`.
+    pub fn out_of_ranges(value: impl core::fmt::Display, name: &str) -> ParseError {
+        ParseError::other(format_args!(
+            "{value} is not in the range of any variant of {name}."
+        ))
+    }
+
+    #[doc(hidden)]
+    /// Not part of public api used when a struct derived with `flags` finds a flag it does not know.
+    pub fn unknown_flag(flag: &str, name: &str) -> ParseError {
+        ParseError::other(format_args!("{flag:?} is not a flag of {name}."))
+    }
+
+    /// Writes the error of each pattern of a struct, separated by `; `.
+    struct PatternErrors<'a>(&'a [ParseError]);
+
+    impl core::fmt::Display for PatternErrors<'_> {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            for (idx, error) in self.0.iter().enumerate() {
+                if idx != 0 {
+                    f.write_str("; ")?;
+                }
+                #[cfg(feature = "alloc")]
+                error.write_chain(f, ": ")?;
+                #[cfg(not(feature = "alloc"))]
+                write!(f, "{error}")?;
+            }
+            Ok(())
+        }
+    }
+
+    #[doc(hidden)]
+    /// Not part of public api used when none of the patterns of a struct match, keeping the error of each pattern.
+    pub fn no_pattern_matched(s: &str, name: &str, errors: &[ParseError]) -> ParseError {
+        ParseError::other(format_args!(
+            "{s:?} does not match any pattern of {name} ({}).",
+            PatternErrors(errors)
+        ))
+    }
+
+    #[doc(hidden)]
+    /// Not part of public api used when none of the variants of an enum match and none had a matching leading literal.
+    pub fn unknown_variant(s: &str, name: &str) -> ParseError {
+        ParseError::other(format_args!("{s:?} does not match any variant of {name}."))
+    }
+
+    #[doc(hidden)]
+    /// Not part of public api, used by `parse_located!` to find where `capture` starts in `input`.
+    ///
+    /// The capture is always a slice of the input, so its offset is the distance between their pointers.
+    pub fn locate(input: &str, capture: &str) -> crate::Location {
+        crate::Location::new(input, capture.as_ptr() as usize - input.as_ptr() as usize)
+    }
+
+    #[doc(hidden)]
     pub fn try_parse_context<'a, T: Parse<'a>>(
         item: &'a str,
-        _full_string: &'a str,
+        full_string: &'a str,
+        field_name: Option<&'static str>,
+    ) -> Result<T, ParseError> {
+        add_context(item.lending_parse(), item, full_string, field_name)
+    }
+
+    #[doc(hidden)]
+    #[cfg(not(feature = "alloc"))]
+    pub fn add_context<T>(
+        result: Result<T, ParseError>,
+        _item: &str,
+        _full_string: &str,
+        _field_name: Option<&'static str>,
     ) -> Result<T, ParseError> {
-        item.lending_parse()
+        result
     }
 
     #[doc(hidden)]
@@ -328,15 +934,63 @@ pub mod __private {
         result
     }
 
+    #[doc(hidden)]
+    #[cfg(not(feature = "alloc"))]
+    pub fn add_row_context<T>(
+        result: Result<T, ParseError>,
+        _row: &str,
+        _full_string: &str,
+        _index: usize,
+    ) -> Result<T, ParseError> {
+        result
+    }
+
     #[doc(hidden)]
     #[cfg(feature = "alloc")]
-    pub fn try_parse_context<'a, T: Parse<'a>>(
-        item: &'a str,
-        full_string: &'a str,
+    pub fn add_context<T>(
+        result: Result<T, ParseError>,
+        item: &str,
+        full_string: &str,
+        field_name: Option<&'static str>,
     ) -> Result<T, ParseError> {
-        item.lending_parse().map_err(|e| ParseError::Context {
+        result.map_err(|e| ParseError::Context {
             full_string: full_string.to_string(),
             failed_item: item.to_string(),
+            field_name: field_name.map(|f| f.to_string()),
+            error: Box::new(e),
+        })
+    }
+
+    #[doc(hidden)]
+    #[cfg(feature = "alloc")]
+    /// Not part of public api, used by `parse_records!` to name the record that failed by its index.
+    pub fn add_record_context<T>(
+        result: Result<T, ParseError>,
+        record: &str,
+        full_string: &str,
+        index: usize,
+    ) -> Result<T, ParseError> {
+        result.map_err(|e| ParseError::Context {
+            full_string: full_string.to_string(),
+            failed_item: record.to_string(),
+            field_name: Some(super::alloc::format!("record {index}")),
+            error: Box::new(e),
+        })
+    }
+
+    #[doc(hidden)]
+    #[cfg(feature = "alloc")]
+    /// Not part of public api, used to name the row of a repetition of rows that failed by its index.
+    pub fn add_row_context<T>(
+        result: Result<T, ParseError>,
+        row: &str,
+        full_string: &str,
+        index: usize,
+    ) -> Result<T, ParseError> {
+        result.map_err(|e| ParseError::Context {
+            full_string: full_string.to_string(),
+            failed_item: row.to_string(),
+            field_name: Some(super::alloc::format!("row {index}")),
             error: Box::new(e),
         })
     }
@@ -356,10 +1010,31 @@ pub mod __private {
     }
 }
 
+/// Returns the first `chars` chars of `s`, or `None` if it is not longer than that.
+///
+/// The input can hold any text, so the cut is always made on a char boundary and never panics.
+#[cfg(feature = "alloc")]
+fn truncate_chars(s: &str, chars: usize) -> Option<&str> {
+    s.char_indices().nth(chars).map(|(end, _)| &s[..end])
+}
+
 #[cfg(test)]
 mod test {
     use crate::ParseError;
 
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn truncate_on_char_boundary() {
+        use super::truncate_chars;
+
+        assert_eq!(truncate_chars("日本語", 1), Some("日"));
+        assert_eq!(truncate_chars("é!", 1), Some("é"));
+        assert_eq!(truncate_chars("a🦀b", 2), Some("a🦀"));
+        assert_eq!(truncate_chars("a🦀", 2), None);
+        assert_eq!(truncate_chars("", 0), None);
+        assert_eq!(truncate_chars("🦀", 0), Some(""));
+    }
+
     #[test]
     fn check_impl_traits() {
         fn is_send<T: Send>() {}
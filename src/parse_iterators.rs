@@ -1,4 +1,9 @@
-use crate::{Parse, ParseError, __private};
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use alloc::{borrow::Cow, vec::Vec};
+
+use crate::{__private, Parse, ParseError};
 use core::iter::FusedIterator;
 use core::marker::PhantomData;
 use core::str::CharIndices;
@@ -9,6 +14,16 @@ use memchr::memmem::FindIter;
 /// It is produced from [`parse!`](crate::parse) and [`try_parse!`](crate::try_parse)'s Iterator
 /// repetition when given a separator, otherwise [`ParseChars`] is used instead.
 ///
+/// When the separator is `\n`, lines ending in `\r\n` have their `\r` removed like [`str::lines`],
+/// so Windows and Unix line endings are both handled. The last line also has a final `\r` removed.
+/// A separator at the end of the input ends the last element, unless the repetition has the `trailing`
+/// modifier in which case it is followed by an empty element, such as the last one of `"1,,3,"`.
+///
+/// With a separator class such as `[, ]+`, any run of the chars of the class separates two elements,
+/// and runs at the start or end of the input are ignored.
+/// With a separator set such as `[,;]` every one of its chars is a separator on its own,
+/// so the elements are split like with a literal separator.
+///
 /// ```
 /// # use prse::{Parse, ParseIter, parse};
 /// #[derive(Parse)]
@@ -26,11 +41,15 @@ use memchr::memmem::FindIter;
 /// ```
 #[derive(Debug, Clone)]
 pub struct ParseIter<'a, T: Parse<'a>> {
-    finder: FindIter<'a, 'a>,
+    separator: Separator<'a>,
     is_multi: bool,
     separator_size: usize,
     string: &'a str,
     last_match_idx: usize,
+    strip_cr: bool,
+    trailing: bool,
+    max_elements: usize,
+    found: usize,
     phantom: PhantomData<T>,
 }
 
@@ -39,43 +58,141 @@ impl<'a, T: Parse<'a>> ParseIter<'a, T> {
     /// Not part of public api, used to create the iterator.
     pub fn new(string: &'a str, separator: &'a str, is_multi: bool) -> Self {
         Self {
-            finder: memchr::memmem::find_iter(string.as_bytes(), separator.as_bytes()),
-            separator_size: separator.as_bytes().len(),
+            separator: Separator::Literal(memchr::memmem::find_iter(
+                string.as_bytes(),
+                separator.as_bytes(),
+            )),
+            separator_size: separator.len(),
             is_multi,
             string,
             last_match_idx: 0,
+            strip_cr: separator == "\n",
+            trailing: false,
+            max_elements: usize::MAX,
+            found: 0,
             phantom: PhantomData,
         }
     }
-}
 
-impl<'a, T: Parse<'a>> Iterator for ParseIter<'a, T> {
-    type Item = Result<T, ParseError>;
+    #[doc(hidden)]
+    /// Not part of public api, used to create the iterator when the separator is a class of chars.
+    pub fn new_class(string: &'a str, class: &'a str) -> Self {
+        Self {
+            separator: Separator::Class(class),
+            separator_size: 1,
+            is_multi: true,
+            string,
+            last_match_idx: 0,
+            strip_cr: false,
+            trailing: false,
+            max_elements: usize::MAX,
+            found: 0,
+            phantom: PhantomData,
+        }
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        for idx in self.finder.by_ref() {
-            if let Some(slice) = self.string.get(self.last_match_idx..idx) {
+    #[doc(hidden)]
+    /// Not part of public api, used to create the iterator when the separator is a set of chars.
+    pub fn new_set(string: &'a str, set: &'a str, is_multi: bool) -> Self {
+        Self {
+            separator: Separator::Set(set),
+            is_multi,
+            ..Self::new_class(string, set)
+        }
+    }
+
+    #[doc(hidden)]
+    /// Not part of public api, used by the `trailing` modifier to keep the empty element after a separator
+    /// at the end of the input.
+    pub fn with_trailing(mut self) -> Self {
+        self.trailing = true;
+        self
+    }
+
+    #[doc(hidden)]
+    /// Not part of public api, used by the `max` modifier to return an error once there are more than `max` elements.
+    pub fn with_max(mut self, max: usize) -> Self {
+        self.max_elements = max;
+        self
+    }
+
+    /// Returns the next element without parsing it.
+    fn next_slice(&mut self) -> Option<&'a str> {
+        if self.last_match_idx > self.string.len() {
+            return None;
+        }
+        let finder = match &mut self.separator {
+            Separator::Literal(finder) => finder,
+            Separator::Class(class) => {
+                let is_separator = |c: char| in_class(class, c);
+                let rest = self.string[self.last_match_idx..].trim_start_matches(is_separator);
+                let start = self.string.len() - rest.len();
+                if rest.is_empty() {
+                    self.last_match_idx = self.string.len() + 1;
+                    return None;
+                }
+                let end = rest.find(is_separator).unwrap_or(rest.len());
+                self.last_match_idx = start + end;
+                return Some(&rest[..end]);
+            }
+            Separator::Set(set) => {
+                let is_separator = |c: char| in_class(set, c);
+                while let Some(rest) = self.string.get(self.last_match_idx..) {
+                    let Some((idx, sep)) = rest.char_indices().find(|&(_, c)| is_separator(c))
+                    else {
+                        break;
+                    };
+                    self.last_match_idx += idx + sep.len_utf8();
+                    if !self.is_multi || idx != 0 {
+                        return Some(&rest[..idx]);
+                    }
+                }
+                let slice = self.string.get(self.last_match_idx..)?;
+                self.last_match_idx = self.string.len() + 1;
+                let trailing = self.trailing && !self.string.is_empty();
+                return (!slice.is_empty() || trailing).then_some(slice);
+            }
+        };
+        for idx in finder.by_ref() {
+            if let Some(mut slice) = self.string.get(self.last_match_idx..idx) {
                 self.last_match_idx = idx + self.separator_size;
+                if self.strip_cr {
+                    slice = slice.strip_suffix('\r').unwrap_or(slice);
+                }
                 if !self.is_multi || !slice.is_empty() {
-                    return Some(__private::add_err_multi_context(
-                        T::from_str(slice),
-                        self.string,
-                        slice,
-                    ));
+                    return Some(slice);
                 }
             }
         }
-        if let Some(slice) = self.string.get(self.last_match_idx..) {
+        let mut slice = self.string.get(self.last_match_idx..)?;
+        self.last_match_idx = self.string.len() + 1;
+        // The last line can also end in a `\r` when the input is cut right before its `\n`.
+        if self.strip_cr {
+            slice = slice.strip_suffix('\r').unwrap_or(slice);
+        }
+        let trailing = self.trailing && !self.string.is_empty();
+        (!slice.is_empty() || trailing).then_some(slice)
+    }
+}
+
+impl<'a, T: Parse<'a>> Iterator for ParseIter<'a, T> {
+    type Item = Result<T, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let slice = self.next_slice()?;
+        if self.found == self.max_elements {
+            // The rest of the input is skipped so that it is never searched.
             self.last_match_idx = self.string.len() + 1;
-            if !slice.is_empty() {
-                return Some(__private::add_err_multi_context(
-                    T::from_str(slice),
-                    self.string,
-                    slice,
-                ));
-            }
+            return Some(Err(ParseError::TooManyElements {
+                max: self.max_elements,
+            }));
         }
-        None
+        self.found += 1;
+        Some(__private::add_err_multi_context(
+            T::from_str(slice),
+            self.string,
+            slice,
+        ))
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
@@ -83,7 +200,8 @@ impl<'a, T: Parse<'a>> Iterator for ParseIter<'a, T> {
             self.string.len().checked_sub(self.last_match_idx),
             self.is_multi,
         ) {
-            (None | Some(0), _) => (0, Some(0)),
+            (None, _) => (0, Some(0)),
+            (Some(0), _) if !self.trailing || self.string.is_empty() => (0, Some(0)),
             (Some(haystack_len), false) => (0, Some(1 + haystack_len / self.separator_size)),
             (Some(haystack_len), true) => (0, Some(1 + haystack_len / (self.separator_size + 1))),
         }
@@ -92,6 +210,36 @@ impl<'a, T: Parse<'a>> Iterator for ParseIter<'a, T> {
 
 impl<'a, T: Parse<'a>> FusedIterator for ParseIter<'a, T> {}
 
+/// What separates the elements of a [`ParseIter`].
+// An iterator only ever holds one kind of separator, so boxing the finder would only add an allocation.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+enum Separator<'a> {
+    Literal(FindIter<'a, 'a>),
+    /// Any run of the chars of the class, see [`in_class`].
+    Class(&'a str),
+    /// Any single char of the set, which uses the same syntax as a class.
+    Set(&'a str),
+}
+
+/// Returns whether `c` is one of the chars of a separator class, where `\s` stands for any whitespace.
+fn in_class(class: &str, c: char) -> bool {
+    let mut chars = class.chars();
+    while let Some(member) = chars.next() {
+        let found = match member {
+            '\\' => match chars.next() {
+                Some('s') => c.is_whitespace(),
+                escaped => escaped == Some(c),
+            },
+            member => member == c,
+        };
+        if found {
+            return true;
+        }
+    }
+    false
+}
+
 /// An iterator that takes a string and parses all chars individually.
 ///
 /// It is produced from [`parse!`](crate::parse) and [`try_parse!`](crate::try_parse)'s Iterator
@@ -121,6 +269,8 @@ impl<'a, T: Parse<'a>> FusedIterator for ParseIter<'a, T> {}
 pub struct ParseChars<'a, T: Parse<'a>> {
     chars: CharIndices<'a>,
     string: &'a str,
+    max_elements: usize,
+    found: usize,
     phantom: PhantomData<T>,
 }
 
@@ -131,27 +281,55 @@ impl<'a, T: Parse<'a>> ParseChars<'a, T> {
         Self {
             chars: string.char_indices(),
             string,
+            max_elements: usize::MAX,
+            found: 0,
             phantom: PhantomData,
         }
     }
+
+    #[doc(hidden)]
+    /// Not part of public api, used by the `max` modifier to return an error once there are more than `max` elements.
+    pub fn with_max(mut self, max: usize) -> Self {
+        self.max_elements = max;
+        self
+    }
+
+    /// Parses the char starting at `start`, or returns an error if there are already too many elements.
+    fn parse_char(&mut self, start: usize, c: char) -> Result<T, ParseError> {
+        if self.found == self.max_elements {
+            self.chars = "".char_indices();
+            return Err(ParseError::TooManyElements {
+                max: self.max_elements,
+            });
+        }
+        self.found += 1;
+        let slice = self.string.get(start..(start + c.len_utf8())).unwrap();
+        __private::add_err_multi_context(T::from_str(slice), self.string, slice)
+    }
+
+    /// Returns the number of elements left before the limit is reached, counting the error it returns.
+    fn elements_left(&self) -> usize {
+        (self.max_elements - self.found).saturating_add(1)
+    }
 }
 
 impl<'a, T: Parse<'a>> Iterator for ParseChars<'a, T> {
     type Item = Result<T, ParseError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.chars.next().map(|(start, c)| {
-            let slice = self.string.get(start..(start + c.len_utf8())).unwrap();
-            __private::add_err_multi_context(T::from_str(slice), self.string, slice)
-        })
+        let (start, c) = self.chars.next()?;
+        Some(self.parse_char(start, c))
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        self.chars.size_hint()
+        let (lower, upper) = self.chars.size_hint();
+        let left = self.elements_left();
+        (lower.min(left), upper.map(|upper| upper.min(left)))
     }
 
     fn count(self) -> usize {
-        self.chars.count()
+        let left = self.elements_left();
+        self.chars.count().min(left)
     }
 
     fn last(mut self) -> Option<Self::Item> {
@@ -161,11 +339,87 @@ impl<'a, T: Parse<'a>> Iterator for ParseChars<'a, T> {
 
 impl<'a, T: Parse<'a>> DoubleEndedIterator for ParseChars<'a, T> {
     fn next_back(&mut self) -> Option<Result<T, ParseError>> {
-        self.chars.next_back().map(|(start, c)| {
-            let slice = self.string.get(start..(start + c.len_utf8())).unwrap();
-            __private::add_err_multi_context(T::from_str(slice), self.string, slice)
-        })
+        let (start, c) = self.chars.next_back()?;
+        Some(self.parse_char(start, c))
     }
 }
 
 impl<'a, T: Parse<'a>> FusedIterator for ParseChars<'a, T> {}
+
+#[doc(hidden)]
+#[cfg(feature = "alloc")]
+/// Not part of public api, used by the `dd` modifier to split `s` on every separator that is not doubled.
+///
+/// A doubled separator is replaced by a single one, elements without one are borrowed from `s`.
+/// Returns [`ParseError::TooManyElements`] once there are more than `max` elements.
+pub fn split_doubled<'a>(
+    s: &'a str,
+    separator: &str,
+    max: usize,
+) -> Result<Vec<Cow<'a, str>>, ParseError> {
+    fn append<'a>(element: &mut Cow<'a, str>, part: &'a str) {
+        if element.is_empty() {
+            *element = Cow::Borrowed(part);
+        } else {
+            element.to_mut().push_str(part);
+        }
+    }
+
+    let too_many = || ParseError::TooManyElements { max };
+    let mut elements = Vec::new();
+    let mut element = Cow::Borrowed("");
+    let mut start = 0;
+    let mut search = 0;
+    while let Some(offset) = s[search..].find(separator) {
+        let end = search + offset + separator.len();
+        if s[end..].starts_with(separator) {
+            append(&mut element, &s[start..end]);
+            start = end + separator.len();
+        } else {
+            append(&mut element, &s[start..end - separator.len()]);
+            if elements.len() == max {
+                return Err(too_many());
+            }
+            elements.push(core::mem::take(&mut element));
+            start = end;
+        }
+        search = start;
+    }
+    append(&mut element, &s[start..]);
+    if elements.len() == max {
+        return Err(too_many());
+    }
+    elements.push(element);
+    Ok(elements)
+}
+
+#[doc(hidden)]
+#[cfg(feature = "alloc")]
+/// Not part of public api, used by `parse_records!` to split `s` into the records between blank lines.
+///
+/// A blank line only holds whitespace, any number of them separate two records and those at the start
+/// or end of `s` are skipped. Each record keeps the line endings between its lines but not the one after its last line.
+pub fn split_records(s: &str) -> Vec<&str> {
+    let mut records = Vec::new();
+    // The start of the current record and the end of its last line, without the line ending.
+    let mut record: Option<(usize, usize)> = None;
+    let mut start = 0;
+    for line in s.split_inclusive('\n') {
+        let end = start + line.len();
+        if line.trim().is_empty() {
+            if let Some((first, last)) = record.take() {
+                records.push(&s[first..last]);
+            }
+        } else {
+            let content = line.strip_suffix('\n').unwrap_or(line);
+            let content = content.strip_suffix('\r').unwrap_or(content);
+            let first = record.map_or(start, |(first, _)| first);
+            record = Some((first, start + content.len()));
+        }
+        start = end;
+    }
+    if let Some((first, last)) = record {
+        records.push(&s[first..last]);
+    }
+    records
+}
@@ -0,0 +1,55 @@
+/// The literal text of a pattern between its captures, created by [`pattern_literals!`](crate::pattern_literals).
+///
+/// This is meant for tooling such as autocompletion or form generation that needs to know what text
+/// a pattern expects around the values, without parsing anything.
+///
+/// ```
+/// # use prse::pattern_literals;
+/// let literals = pattern_literals!("move {} from {} to {}");
+/// assert!(literals.literals().eq(["move ", " from ", " to "]));
+///
+/// assert_eq!(literals.next_expected(""), Some("move "));
+/// assert_eq!(literals.next_expected("move 3"), Some(" from "));
+/// assert_eq!(literals.next_expected("move 3 from 1 to 2"), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PatternLiterals {
+    /// Each literal in order and whether a capture comes before it.
+    segments: &'static [(bool, &'static str)],
+}
+
+impl PatternLiterals {
+    #[doc(hidden)]
+    /// Not part of public api, used by `pattern_literals!` to create the literals.
+    pub const fn new(segments: &'static [(bool, &'static str)]) -> Self {
+        PatternLiterals { segments }
+    }
+
+    /// Returns every literal of the pattern in order.
+    pub fn literals(&self) -> impl ExactSizeIterator<Item = &'static str> + '_ {
+        self.segments.iter().map(|(_, literal)| *literal)
+    }
+
+    /// Returns the literal that the pattern expects next, given the start of an input.
+    ///
+    /// The input is matched the same way as when parsing: a literal after a capture is searched for,
+    /// while any other literal has to be found right away. The first literal that cannot be found is returned,
+    /// even if the input already contains its start, and `None` is returned once every literal has been matched.
+    pub fn next_expected(&self, partial_input: &str) -> Option<&'static str> {
+        let mut remaining = partial_input;
+        for &(after_capture, literal) in self.segments {
+            let rest = if after_capture {
+                remaining
+                    .find(literal)
+                    .map(|idx| &remaining[idx + literal.len()..])
+            } else {
+                remaining.strip_prefix(literal)
+            };
+            match rest {
+                Some(rest) => remaining = rest,
+                None => return Some(literal),
+            }
+        }
+        None
+    }
+}
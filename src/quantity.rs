@@ -0,0 +1,244 @@
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+use crate::{Parse, ParseError};
+
+/// The units of a dimension that [`Quantity`] accepts, each with the factor that converts it into the base unit.
+///
+/// [`Length`], [`Mass`] and [`Time`] are provided, and any other dimension or table can be added
+/// by implementing this trait.
+///
+/// Only the units of the provided tables can be named as a unit of another dimension in an error:
+/// a unit that is only in a custom table, such as `ml` for a [`Length`], returns the unknown unit error.
+///
+/// ```
+/// # use prse::{parse, Quantity, Units};
+/// struct Volume;
+///
+/// impl Units for Volume {
+///     const DIMENSION: &'static str = "volume";
+///     const UNITS: &'static [(&'static str, f64)] = &[("ml", 0.001), ("cl", 0.01), ("l", 1.0)];
+/// }
+///
+/// let bottle: Quantity<Volume> = parse!("size=75cl", "size={}");
+/// assert_eq!(bottle.value(), 0.75);
+/// ```
+pub trait Units {
+    /// The name of the dimension, such as `"length"`, used in the error messages.
+    const DIMENSION: &'static str;
+    /// Every unit with the factor converting it into the base unit, whose own factor is 1.
+    /// The units are compared exactly, so they are case sensitive.
+    const UNITS: &'static [(&'static str, f64)];
+}
+
+/// The [`Units`] of a length in meters, from `nm` to `km` as well as `in`, `ft`, `yd` and `mi`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Length;
+
+impl Units for Length {
+    const DIMENSION: &'static str = "length";
+    const UNITS: &'static [(&'static str, f64)] = &[
+        ("nm", 1e-9),
+        ("µm", 1e-6),
+        ("um", 1e-6),
+        ("mm", 1e-3),
+        ("cm", 1e-2),
+        ("m", 1.0),
+        ("km", 1e3),
+        ("in", 0.0254),
+        ("ft", 0.3048),
+        ("yd", 0.9144),
+        ("mi", 1609.344),
+    ];
+}
+
+/// The [`Units`] of a mass in kilograms, `mg`, `g`, `kg`, `t`, `oz` and `lb`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Mass;
+
+impl Units for Mass {
+    const DIMENSION: &'static str = "mass";
+    const UNITS: &'static [(&'static str, f64)] = &[
+        ("mg", 1e-6),
+        ("g", 1e-3),
+        ("kg", 1.0),
+        ("t", 1e3),
+        ("oz", 0.028_349_523_125),
+        ("lb", 0.453_592_37),
+    ];
+}
+
+/// The [`Units`] of a time in seconds, from `ns` to `s` as well as `min`, `h` and `d`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Time;
+
+impl Units for Time {
+    const DIMENSION: &'static str = "time";
+    const UNITS: &'static [(&'static str, f64)] = &[
+        ("ns", 1e-9),
+        ("µs", 1e-6),
+        ("us", 1e-6),
+        ("ms", 1e-3),
+        ("s", 1.0),
+        ("min", 60.0),
+        ("h", 3600.0),
+        ("d", 86400.0),
+    ];
+}
+
+/// The provided dimensions, used to tell a unit of another dimension apart from an unknown unit.
+const DIMENSIONS: [(&str, &[(&str, f64)]); 3] = [
+    (Length::DIMENSION, Length::UNITS),
+    (Mass::DIMENSION, Mass::UNITS),
+    (Time::DIMENSION, Time::UNITS),
+];
+
+struct UnitList(&'static [(&'static str, f64)]);
+
+impl fmt::Display for UnitList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, (unit, _)) in self.0.iter().enumerate() {
+            match idx {
+                0 => {}
+                _ if idx + 1 == self.0.len() => f.write_str(" or ")?,
+                _ => f.write_str(", ")?,
+            }
+            f.write_str(unit)?;
+        }
+        Ok(())
+    }
+}
+
+/// A number followed by one of the units of `U`, such as `"5cm"` or `"2 m"`, converted into the base unit.
+///
+/// Like [`WithUnit`](crate::WithUnit), the number is the longest prefix made of an optional sign, ASCII digits and `.`,
+/// the rest of the string is the unit and whitespace around them is ignored, but the unit is looked up in
+/// [`U::UNITS`](Units::UNITS) and the value converted into the base unit. The number is parsed as an [`f64`],
+/// so invalid numbers return [`ParseError::Float`]. A missing or unknown unit returns a [`ParseError::Other`]
+/// listing the units, and a unit of one of the other provided dimensions, such as `kg` for a [`Length`],
+/// returns one naming its dimension instead.
+///
+/// ```
+/// # use prse::{parse, try_parse, Length, Mass, Quantity, Time};
+/// let (a, b): (Quantity<Length>, Quantity<Length>) = parse!("length=5cm width=2 m", "length={} width={}");
+/// assert_eq!((a.value(), *b), (0.05, 2.0));
+/// assert_eq!(b.in_unit("cm"), Some(200.0));
+///
+/// let timeout: Quantity<Time> = parse!("1.5min", "{}");
+/// assert_eq!(timeout.value(), 90.0);
+///
+/// let wrong: Result<Quantity<Mass>, _> = try_parse!("3 ft", "{}");
+/// assert!(wrong.is_err());
+/// ```
+pub struct Quantity<U> {
+    value: f64,
+    units: PhantomData<fn() -> U>,
+}
+
+impl<U> Quantity<U> {
+    /// Creates a new `Quantity` from a value in the base unit.
+    pub fn new(value: f64) -> Self {
+        Quantity {
+            value,
+            units: PhantomData,
+        }
+    }
+
+    /// Returns the value in the base unit.
+    pub fn value(self) -> f64 {
+        self.value
+    }
+}
+
+impl<U: Units> Quantity<U> {
+    /// Returns the value converted into `unit`, or `None` if it is not one of the units of `U`.
+    pub fn in_unit(self, unit: &str) -> Option<f64> {
+        U::UNITS
+            .iter()
+            .find(|(u, _)| *u == unit)
+            .map(|(_, factor)| self.value / factor)
+    }
+}
+
+impl<U> Deref for Quantity<U> {
+    type Target = f64;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<U> From<Quantity<U>> for f64 {
+    fn from(quantity: Quantity<U>) -> Self {
+        quantity.value
+    }
+}
+
+impl<U> fmt::Debug for Quantity<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Quantity").field(&self.value).finish()
+    }
+}
+
+impl<U> Clone for Quantity<U> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<U> Copy for Quantity<U> {}
+
+impl<U> PartialEq for Quantity<U> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<U> PartialOrd for Quantity<U> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+impl<U> Hash for Quantity<U> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.to_bits().hash(state);
+    }
+}
+
+impl<'a, U: Units> Parse<'a> for Quantity<U> {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        let trimmed = s.trim();
+        let sign = usize::from(trimmed.starts_with(['+', '-']));
+        let end = trimmed[sign..]
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .map_or(trimmed.len(), |idx| idx + sign);
+        let (number, unit) = trimmed.split_at(end);
+        let unit = unit.trim_start();
+        let Some((_, factor)) = U::UNITS.iter().find(|(u, _)| *u == unit) else {
+            if unit.is_empty() {
+                return Err(ParseError::other(format_args!(
+                    "{s:?} has no unit, expected one of {}.",
+                    UnitList(U::UNITS)
+                )));
+            }
+            return Err(
+                match DIMENSIONS.iter().find(|(dimension, units)| {
+                    *dimension != U::DIMENSION && units.iter().any(|(u, _)| *u == unit)
+                }) {
+                    Some((dimension, _)) => ParseError::other(format_args!(
+                        "{unit:?} is a unit of {dimension}, expected a unit of {}.",
+                        U::DIMENSION
+                    )),
+                    None => ParseError::other(format_args!(
+                        "unknown unit {unit:?}, expected one of {}.",
+                        UnitList(U::UNITS)
+                    )),
+                },
+            );
+        };
+        Ok(Quantity::new(number.parse::<f64>()? * factor))
+    }
+}
@@ -0,0 +1,138 @@
+use crate::ParseError;
+
+/// Parses a value from a string of digits in a given radix.
+///
+/// It is implemented for every integer type, and can be implemented for other integer-like types
+/// (such as big integers) so that they can be captured using the radix and digit grouping modifiers.
+/// The prefix and any underscores are removed before the digits are passed on, leaving at most a sign
+/// directly in front of them. Negative numbers with a prefix are limited to 128 digits (ignoring leading zeros).
+/// Digits that are not valid in the radix are found before parsing, and `"-"` is passed on instead so that
+/// the type returns the same error as for any other invalid digit.
+///
+/// ```
+/// # use prse::{parse, ParseError, ParseRadix};
+/// #[derive(Debug, PartialEq)]
+/// struct Digits(usize);
+///
+/// impl ParseRadix for Digits {
+///     fn from_radix_str(s: &str, radix: u32) -> Result<Self, ParseError> {
+///         if s.is_empty() || !s.chars().all(|c| c.is_digit(radix)) {
+///             return Err(ParseError::new(format!("{s:?} is not a number.")));
+///         }
+///         Ok(Digits(s.len()))
+///     }
+/// }
+///
+/// let digits: Digits = parse!("0xdead_beef", "{:auto,_}");
+/// assert_eq!(digits, Digits(8));
+/// ```
+pub trait ParseRadix: Sized {
+    /// Parses the digits in `s` using the given `radix`, which is between 2 and 36.
+    fn from_radix_str(s: &str, radix: u32) -> Result<Self, ParseError>;
+}
+
+macro_rules! impl_parse_radix {
+    ($($Ty: ty),*) => {
+        $(
+            impl ParseRadix for $Ty {
+                fn from_radix_str(s: &str, radix: u32) -> Result<Self, ParseError> {
+                    Ok(<$Ty>::from_str_radix(s, radix)?)
+                }
+            }
+        )*
+    };
+}
+
+impl_parse_radix!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// The longest number of digits that could still fit into a 128 bit integer once leading zeros are removed.
+const MAX_DIGITS: usize = 128;
+
+/// Not part of public api, parses an integer using the given radix.
+///
+/// If the radix is `None` it is detected from the prefix (`0x`, `0o` or `0b`) following Rust's literal rules,
+/// otherwise the prefix matching the radix is optional. The number can be preceded by a sign in both cases.
+#[doc(hidden)]
+pub fn parse_radix<T: ParseRadix>(s: &str, radix: Option<u32>) -> Result<T, ParseError> {
+    let (negative, unsigned) = match s.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+    let prefixed = [(16, "0x"), (8, "0o"), (2, "0b")]
+        .into_iter()
+        .find_map(|(r, prefix)| unsigned.strip_prefix(prefix).map(|digits| (r, digits)));
+    let (radix, digits) = match (radix, prefixed) {
+        (None, Some(prefixed)) => prefixed,
+        (None, None) => (10, unsigned),
+        (Some(radix), Some((r, digits))) if r == radix => (radix, digits),
+        (Some(radix), _) => (radix, unsigned),
+    };
+
+    // An overflow is reported before an invalid digit found after it, and a second sign after the prefix
+    // is an invalid digit, so every digit is checked first to always produce the invalid digit error.
+    if !digits.chars().all(|c| c.is_digit(radix)) {
+        return T::from_radix_str("-", radix);
+    }
+    // Without a prefix the sign is still directly in front of the digits.
+    if digits.len() == unsigned.len() {
+        return T::from_radix_str(s, radix);
+    }
+    if !negative || digits.is_empty() {
+        return T::from_radix_str(digits, radix);
+    }
+
+    // Put the sign back in front of the digits without allocating.
+    let trimmed = digits.trim_start_matches('0');
+    let trimmed = if trimmed.is_empty() { "0" } else { trimmed };
+    if trimmed.len() > MAX_DIGITS {
+        return Err(ParseError::other(format_args!(
+            "{s:?} has too many digits."
+        )));
+    }
+    let len = trimmed.len();
+    let mut buf = [b'-'; MAX_DIGITS + 1];
+    buf[1..=len].copy_from_slice(&trimmed.as_bytes()[..len]);
+    let signed = core::str::from_utf8(&buf[..=len]).expect("only whole chars are copied");
+    T::from_radix_str(signed, radix)
+}
+
+/// The longest grouped number that can be parsed, enough for the digits, a sign and a prefix.
+const MAX_GROUPED_LEN: usize = MAX_DIGITS + 3;
+
+/// Not part of public api, parses an integer like [`parse_radix`] after removing the underscores grouping its digits.
+///
+/// Every underscore must be found between two digits or after the prefix, everything else is
+/// rejected with [`ParseError::Other`].
+#[doc(hidden)]
+pub fn parse_grouped_radix<T: ParseRadix>(s: &str, radix: Option<u32>) -> Result<T, ParseError> {
+    if !s.contains('_') {
+        return parse_radix(s, radix);
+    }
+
+    let bytes = s.as_bytes();
+    let mut buf = [0_u8; MAX_GROUPED_LEN];
+    let mut len = 0;
+    for (idx, &b) in bytes.iter().enumerate() {
+        if b != b'_' {
+            let Some(slot) = buf.get_mut(len) else {
+                return Err(ParseError::other(format_args!(
+                    "{s:?} has too many digits."
+                )));
+            };
+            *slot = b;
+            len += 1;
+            continue;
+        }
+        let before = idx.checked_sub(1).map(|i| bytes[i]);
+        let after = bytes.get(idx + 1).copied();
+        if !(before.is_some_and(|b| b.is_ascii_alphanumeric())
+            && after.is_some_and(|b| b.is_ascii_alphanumeric()))
+        {
+            return Err(ParseError::other(format_args!(
+                "misplaced digit separator in {s:?}."
+            )));
+        }
+    }
+    let ungrouped = core::str::from_utf8(&buf[..len]).expect("only whole chars are copied");
+    parse_radix(ungrouped, radix)
+}
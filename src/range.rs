@@ -0,0 +1,68 @@
+use crate::{Parse, ParseError};
+
+/// A range with a step, written as `start..end..step` like the axis spec `"0..10..2"`.
+///
+/// The step must not be zero and has to go from `start` towards `end`, so `"10..0..-2"` counts down
+/// while `"10..0..2"` is invalid. Each part is parsed as a `T`, with `T::default()` used as zero.
+/// Any number of parts other than 3, a zero step, a step in the wrong direction or a part that is NaN
+/// result in [`ParseError::Other`].
+///
+/// ```
+/// # use prse::{parse, try_parse, SteppedRange};
+/// let axis: SteppedRange<i32> = parse!("x: 0..10..2", "x: {}");
+/// assert_eq!(axis, SteppedRange { start: 0, end: 10, step: 2 });
+///
+/// let countdown: SteppedRange<f64> = parse!("1.5..-1.5..-0.5", "{}");
+/// assert_eq!(countdown.step, -0.5);
+///
+/// let stuck: Result<SteppedRange<i32>, _> = try_parse!("0..10..0", "{}");
+/// assert!(stuck.is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SteppedRange<T> {
+    /// The first value of the range.
+    pub start: T,
+    /// The value the range goes towards.
+    pub end: T,
+    /// The distance between each value, negative when `end` is less than `start`.
+    pub step: T,
+}
+
+impl<'a, T> Parse<'a> for SteppedRange<T>
+where
+    T: Parse<'a> + PartialOrd + Default,
+{
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        let s = s.trim();
+        let mut parts = s.split("..");
+        let (Some(start), Some(end), Some(step), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(ParseError::other(format_args!(
+                "expected {s:?} to be of the form start..end..step."
+            )));
+        };
+        let (start, end, step) = (T::from_str(start)?, T::from_str(end)?, T::from_str(step)?);
+
+        // NaN is the only value that cannot be compared to itself, and it would pass both checks below
+        // as every comparison with it is false.
+        let is_nan = |x: &T| x.partial_cmp(x).is_none();
+        if is_nan(&start) || is_nan(&end) || is_nan(&step) {
+            return Err(ParseError::other(format_args!(
+                "{s:?} cannot have a NaN start, end or step."
+            )));
+        }
+        let zero = T::default();
+        if step == zero {
+            return Err(ParseError::other(format_args!(
+                "the step of {s:?} cannot be zero."
+            )));
+        }
+        if (start < end && step < zero) || (start > end && step > zero) {
+            return Err(ParseError::other(format_args!(
+                "the step of {s:?} goes away from its end."
+            )));
+        }
+        Ok(SteppedRange { start, end, step })
+    }
+}
@@ -0,0 +1,159 @@
+use core::fmt::Display;
+
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::ParseError;
+
+/// Serializes a value using its [`Display`] implementation, without needing to allocate.
+struct AsDisplay<'a, T: ?Sized>(&'a T);
+
+impl<T: Display + ?Sized> Serialize for AsDisplay<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self.0)
+    }
+}
+
+fn serialize_message<S: Serializer>(
+    serializer: S,
+    kind: &'static str,
+    message: &dyn Display,
+) -> Result<S::Ok, S::Error> {
+    let mut s = serializer.serialize_struct("ParseError", 2)?;
+    s.serialize_field("kind", kind)?;
+    s.serialize_field("message", &AsDisplay(message))?;
+    s.end()
+}
+
+/// Serializes the error as a struct with a `kind` string and the fields relevant to that kind.
+///
+/// Errors from other types (including [`ParseError::Dyn`]) only hold their `message`,
+/// while prse's own errors keep their fields, such as `expected` and `found` for [`ParseError::Literal`].
+/// The context variants hold the wrapped `error` so the whole chain is kept.
+///
+/// This implementation is only available with the `serde` feature.
+///
+/// ```
+/// # use prse::ParseError;
+/// let error = ParseError::Array { expected: 3, found: 2 };
+/// assert_eq!(
+///     serde_json::to_string(&error).unwrap(),
+///     r#"{"kind":"array","expected":3,"found":2}"#
+/// );
+/// ```
+impl Serialize for ParseError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            ParseError::Int(e) => serialize_message(serializer, "int", e),
+            ParseError::Bool(e) => serialize_message(serializer, "bool", e),
+            ParseError::Char(e) => serialize_message(serializer, "char", e),
+            ParseError::Float(e) => serialize_message(serializer, "float", e),
+            ParseError::TryFromInt(e) => serialize_message(serializer, "try_from_int", e),
+            ParseError::Utf8(e) => serialize_message(serializer, "utf8", e),
+            #[cfg(feature = "std")]
+            ParseError::Addr(e) => serialize_message(serializer, "addr", e),
+            #[cfg(feature = "std")]
+            ParseError::Dyn(e) => serialize_message(serializer, "dyn", e),
+            #[cfg(feature = "alloc")]
+            ParseError::Literal { expected, found } => {
+                let mut s = serializer.serialize_struct("ParseError", 3)?;
+                s.serialize_field("kind", "literal")?;
+                s.serialize_field("expected", expected)?;
+                s.serialize_field("found", found)?;
+                s.end()
+            }
+            #[cfg(not(feature = "alloc"))]
+            ParseError::Literal => {
+                let mut s = serializer.serialize_struct("ParseError", 1)?;
+                s.serialize_field("kind", "literal")?;
+                s.end()
+            }
+            #[cfg(feature = "alloc")]
+            ParseError::BackReference { expected, found } => {
+                let mut s = serializer.serialize_struct("ParseError", 3)?;
+                s.serialize_field("kind", "back_reference")?;
+                s.serialize_field("expected", expected)?;
+                s.serialize_field("found", found)?;
+                s.end()
+            }
+            #[cfg(not(feature = "alloc"))]
+            ParseError::BackReference => {
+                let mut s = serializer.serialize_struct("ParseError", 1)?;
+                s.serialize_field("kind", "back_reference")?;
+                s.end()
+            }
+            ParseError::Array { expected, found } => {
+                let mut s = serializer.serialize_struct("ParseError", 3)?;
+                s.serialize_field("kind", "array")?;
+                s.serialize_field("expected", expected)?;
+                s.serialize_field("found", found)?;
+                s.end()
+            }
+            ParseError::Unbalanced { open, close } => {
+                let mut s = serializer.serialize_struct("ParseError", 3)?;
+                s.serialize_field("kind", "unbalanced")?;
+                s.serialize_field("open", open)?;
+                s.serialize_field("close", close)?;
+                s.end()
+            }
+            ParseError::TooManyElements { max } => {
+                let mut s = serializer.serialize_struct("ParseError", 2)?;
+                s.serialize_field("kind", "too_many_elements")?;
+                s.serialize_field("max", max)?;
+                s.end()
+            }
+            #[cfg(feature = "alloc")]
+            ParseError::Other(m) => serialize_message(serializer, "other", m),
+            #[cfg(all(feature = "inline-message", not(feature = "alloc")))]
+            ParseError::Other(m) => serialize_message(serializer, "other", m),
+            #[cfg(not(any(feature = "alloc", feature = "inline-message")))]
+            ParseError::Other => {
+                let mut s = serializer.serialize_struct("ParseError", 1)?;
+                s.serialize_field("kind", "other")?;
+                s.end()
+            }
+            #[cfg(any(feature = "alloc", feature = "inline-message"))]
+            ParseError::Coded { code, message } => {
+                let mut s = serializer.serialize_struct("ParseError", 3)?;
+                s.serialize_field("kind", "coded")?;
+                s.serialize_field("code", code)?;
+                s.serialize_field("message", &AsDisplay(message))?;
+                s.end()
+            }
+            #[cfg(not(any(feature = "alloc", feature = "inline-message")))]
+            ParseError::Coded { code } => {
+                let mut s = serializer.serialize_struct("ParseError", 2)?;
+                s.serialize_field("kind", "coded")?;
+                s.serialize_field("code", code)?;
+                s.end()
+            }
+            #[cfg(feature = "alloc")]
+            ParseError::MultiContext {
+                multi_string,
+                failed_string,
+                error,
+            } => {
+                let mut s = serializer.serialize_struct("ParseError", 4)?;
+                s.serialize_field("kind", "multi_context")?;
+                s.serialize_field("multi_string", multi_string)?;
+                s.serialize_field("failed_string", failed_string)?;
+                s.serialize_field("error", error)?;
+                s.end()
+            }
+            #[cfg(feature = "alloc")]
+            ParseError::Context {
+                full_string,
+                failed_item,
+                field_name,
+                error,
+            } => {
+                let mut s = serializer.serialize_struct("ParseError", 5)?;
+                s.serialize_field("kind", "context")?;
+                s.serialize_field("full_string", full_string)?;
+                s.serialize_field("failed_item", failed_item)?;
+                s.serialize_field("field_name", field_name)?;
+                s.serialize_field("error", error)?;
+                s.end()
+            }
+        }
+    }
+}
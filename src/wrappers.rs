@@ -0,0 +1,1317 @@
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+
+use crate::{Parse, ParseError};
+
+/// A wrapper that parses any type implementing [`TryFrom<&str>`](TryFrom).
+///
+/// This allows you to parse foreign types that do not implement [`FromStr`](core::str::FromStr) or [`Parse`].
+/// The string is passed to [`TryFrom`] as is, without being trimmed.
+///
+/// The conversion error must implement [`Display`](core::fmt::Display) and its message is stored in
+/// [`ParseError::Other`], so the same types can be parsed whichever features are enabled.
+///
+/// ```
+/// # use prse::{parse, try_parse, TryFromStr};
+/// #[derive(Debug)]
+/// struct NotLowercase;
+///
+/// impl std::fmt::Display for NotLowercase {
+///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         write!(f, "the name must be lowercase")
+///     }
+/// }
+///
+/// impl std::error::Error for NotLowercase {}
+///
+/// // A type from another crate that only implements TryFrom<&str>.
+/// struct Name<'a>(&'a str);
+///
+/// impl<'a> TryFrom<&'a str> for Name<'a> {
+///     type Error = NotLowercase;
+///
+///     fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+///         if s.chars().all(|c| c.is_ascii_lowercase()) {
+///             Ok(Name(s))
+///         } else {
+///             Err(NotLowercase)
+///         }
+///     }
+/// }
+///
+/// let name: TryFromStr<Name> = parse!("name: bob", "name: {}");
+/// assert_eq!(name.0.0, "bob");
+///
+/// let name: Result<TryFromStr<Name>, _> = try_parse!("name: Bob", "name: {}");
+/// assert!(name.is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TryFromStr<T>(pub T);
+
+impl<T> Deref for TryFromStr<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for TryFromStr<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<'a, T> Parse<'a> for TryFromStr<T>
+where
+    T: TryFrom<&'a str>,
+    T::Error: core::fmt::Display,
+{
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        T::try_from(s).map(TryFromStr).map_err(ParseError::other)
+    }
+}
+
+/// A path-like string split into its components on `/`.
+///
+/// The separator is always `/` regardless of the platform (and not [`MAIN_SEPARATOR`](std::path::MAIN_SEPARATOR)),
+/// as it is meant for parsing text such as URLs, configuration files or unix paths.
+/// Empty components (caused by leading, trailing or repeated separators) are skipped by [`iter`](Self::iter),
+/// use [`is_absolute`](Self::is_absolute) and [`has_trailing_separator`](Self::has_trailing_separator)
+/// to find out whether the leading or trailing separators were present.
+///
+/// If you only need the components you can also use a repetition with `/` as the separator, i.e. `{:/:}`.
+///
+/// ```
+/// # use prse::{parse, PathComponents};
+/// let path: PathComponents = parse!("cd /usr/local/bin/", "cd {}");
+///
+/// assert!(path.is_absolute());
+/// assert!(path.has_trailing_separator());
+/// assert_eq!(path.iter().collect::<Vec<_>>(), ["usr", "local", "bin"]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PathComponents<'a> {
+    path: &'a str,
+}
+
+impl<'a> PathComponents<'a> {
+    /// Returns the path that was parsed.
+    pub fn as_str(&self) -> &'a str {
+        self.path
+    }
+
+    /// Returns true if the path starts with a `/`.
+    pub fn is_absolute(&self) -> bool {
+        self.path.starts_with('/')
+    }
+
+    /// Returns true if the path ends with a `/`.
+    pub fn has_trailing_separator(&self) -> bool {
+        self.path.len() > 1 && self.path.ends_with('/')
+    }
+
+    /// Returns an iterator over the non-empty components of the path.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &'a str> + 'a {
+        self.path.split('/').filter(|c| !c.is_empty())
+    }
+}
+
+impl<'a> Parse<'a> for PathComponents<'a> {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        Ok(PathComponents { path: s })
+    }
+}
+
+/// A string slice that is compared and hashed ignoring ASCII case.
+///
+/// This is useful to match keywords or tokens after parsing without allocating,
+/// non-ASCII chars are compared as is.
+/// [`Hash`] is consistent with [`PartialEq`] so it can be used as a key in a map.
+///
+/// ```
+/// # use prse::{parse, CaseInsensitive};
+/// # use std::collections::HashSet;
+/// let token: CaseInsensitive = parse!("HELLO", "{}");
+/// assert_eq!(token, CaseInsensitive("hello"));
+/// assert_eq!(token, "Hello");
+///
+/// let keywords = HashSet::from([CaseInsensitive("let"), CaseInsensitive("fn")]);
+/// assert!(keywords.contains(&parse!("keyword: LET", "keyword: {}")));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct CaseInsensitive<'a>(pub &'a str);
+
+impl<'a> Deref for CaseInsensitive<'a> {
+    type Target = &'a str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl PartialEq for CaseInsensitive<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_ignore_ascii_case(other.0)
+    }
+}
+
+impl Eq for CaseInsensitive<'_> {}
+
+impl PartialEq<str> for CaseInsensitive<'_> {
+    fn eq(&self, other: &str) -> bool {
+        self.0.eq_ignore_ascii_case(other)
+    }
+}
+
+impl PartialEq<&str> for CaseInsensitive<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        self.0.eq_ignore_ascii_case(other)
+    }
+}
+
+impl Hash for CaseInsensitive<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for b in self.0.bytes() {
+            state.write_u8(b.to_ascii_lowercase());
+        }
+        // Same terminator as str so that consecutive slices do not hash the same as their concatenation.
+        state.write_u8(0xff);
+    }
+}
+
+impl<'a> Parse<'a> for CaseInsensitive<'a> {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        Ok(CaseInsensitive(s))
+    }
+}
+
+/// A value parsed after trimming whitespace, that keeps the whitespace that was trimmed off.
+///
+/// The value itself is parsed from the trimmed string, while `leading` and `trailing` hold the
+/// whitespace that surrounded it, so the original text can be reconstructed exactly.
+/// This is mostly useful for tools that need to reformat text without losing its layout.
+///
+/// ```
+/// # use prse::{parse, Spanned};
+/// let [key, value]: [Spanned<&str>; 2] = parse!("  name =   bob ", "{:=:2}");
+///
+/// assert_eq!(*key, "name");
+/// assert_eq!((key.leading, key.trailing), ("  ", " "));
+/// assert_eq!(*value, "bob");
+/// assert_eq!(format!("{key}={value}"), "  name =   bob ");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Spanned<'a, T> {
+    /// The parsed value.
+    pub value: T,
+    /// The whitespace before the value.
+    pub leading: &'a str,
+    /// The whitespace after the value.
+    pub trailing: &'a str,
+}
+
+impl<T> Deref for Spanned<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for Spanned<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+impl<T: core::fmt::Display> core::fmt::Display for Spanned<'_, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}{}{}", self.leading, self.value, self.trailing)
+    }
+}
+
+impl<'a, T: Parse<'a>> Parse<'a> for Spanned<'a, T> {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        let trimmed_start = s.trim_start();
+        let trimmed = trimmed_start.trim_end();
+        Ok(Spanned {
+            value: T::from_str(trimmed)?,
+            leading: &s[..s.len() - trimmed_start.len()],
+            trailing: &trimmed_start[trimmed.len()..],
+        })
+    }
+}
+
+/// A parsed value together with the exact text it was parsed from.
+///
+/// This is useful for logging or writing the input back out unchanged, such as keeping the trailing
+/// zeros of a float. The raw slice is the text given to `T` as is, so it is taken after any trim
+/// [modifiers](crate#capture-modifiers) or [pattern flags](crate#pattern-flags) of the capture have
+/// been applied, as those run before the type is parsed. The radix modifiers cannot be used with it
+/// since they parse integers directly instead of going through [`Parse`].
+///
+/// ```
+/// # use prse::{parse, WithRaw};
+/// let WithRaw(value, raw): WithRaw<f64> = parse!("pi = 3.1400", "pi = {}");
+/// assert_eq!((value, raw), (3.14, "3.1400"));
+///
+/// let number: WithRaw<u32> = parse!("[ 007 ]", "[{:trim}]");
+/// assert_eq!((*number, number.1), (7, "007"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WithRaw<'a, T>(pub T, pub &'a str);
+
+impl<T> Deref for WithRaw<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for WithRaw<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<'a, T: Parse<'a>> Parse<'a> for WithRaw<'a, T> {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        Ok(WithRaw(T::from_str(s)?, s))
+    }
+}
+
+/// A key and a parsed value separated by `SEP`, which defaults to `=`.
+///
+/// The string is split on the first occurrence of `SEP`, so the value can itself contain the separator.
+/// The key is trimmed of whitespace while the value is parsed from the rest of the string as is.
+/// If the separator is not found a [`ParseError::Literal`] is returned.
+///
+/// ```
+/// # use prse::{parse, try_parse, KeyValue};
+/// let kv: KeyValue<u32> = parse!("port=8080", "{}");
+/// assert_eq!((kv.key, kv.value), ("port", 8080));
+///
+/// let kv: KeyValue<&str, ':'> = parse!("Host: localhost:8080", "{}");
+/// assert_eq!((kv.key, kv.value), ("Host", " localhost:8080"));
+///
+/// let missing: Result<KeyValue<u32>, _> = try_parse!("port 8080", "{}");
+/// assert!(missing.is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyValue<'a, V, const SEP: char = '='> {
+    /// The text before the separator.
+    pub key: &'a str,
+    /// The value parsed from the text after the separator.
+    pub value: V,
+}
+
+impl<'a, V: Parse<'a>, const SEP: char> Parse<'a> for KeyValue<'a, V, SEP> {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        let (key, value) = s.split_once(SEP).ok_or_else(|| missing_separator(SEP, s))?;
+        Ok(KeyValue {
+            key: key.trim(),
+            value: V::from_str(value)?,
+        })
+    }
+}
+
+#[doc(hidden)]
+/// Not part of public api, an entry of a map repetition with the key and value parsed from `key=value`.
+///
+/// Both the key and the value are trimmed, like the key of [`KeyValue`].
+pub struct MapEntry<K, V>(pub K, pub V);
+
+impl<'a, K: Parse<'a>, V: Parse<'a>> Parse<'a> for MapEntry<K, V> {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        let (key, value) = s.split_once('=').ok_or_else(|| missing_separator('=', s))?;
+        Ok(MapEntry(
+            K::from_str(key.trim())?,
+            V::from_str(value.trim())?,
+        ))
+    }
+}
+
+/// A percentage such as `"50%"` parsed into the fraction it represents, `0.5`.
+///
+/// The number before the `%` is parsed as an [`f64`] and divided by 100, whitespace around the number is ignored.
+/// Values outside of `0%` to `100%` are kept as is, so `"150%"` is `1.5` and `"-20%"` is `-0.2`.
+/// A missing `%` results in a [`ParseError::Literal`], unless `OPTIONAL` is set in which case
+/// the number is still treated as a percentage. An invalid number results in a [`ParseError::Float`],
+/// while NaN and infinities such as `"inf%"` result in a [`ParseError::Other`] naming the number.
+///
+/// Since dividing by 100 would lose the fractional part of an integer, only a float can be held.
+///
+/// ```
+/// # use prse::{parse, try_parse, Percent};
+/// let opacity: Percent = parse!("opacity=50%", "opacity={}");
+/// assert_eq!(opacity.0, 0.5);
+///
+/// let missing: Result<Percent, _> = try_parse!("opacity=50", "opacity={}");
+/// assert!(missing.is_err());
+///
+/// let optional: Percent<true> = parse!("opacity=50", "opacity={}");
+/// assert_eq!(optional.0, 0.5);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Percent<const OPTIONAL: bool = false>(pub f64);
+
+impl<const OPTIONAL: bool> Deref for Percent<OPTIONAL> {
+    type Target = f64;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<const OPTIONAL: bool> DerefMut for Percent<OPTIONAL> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<'a, const OPTIONAL: bool> Parse<'a> for Percent<OPTIONAL> {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        let trimmed = s.trim();
+        let number = match trimmed.strip_suffix('%') {
+            Some(number) => number.trim_end(),
+            None if OPTIONAL => trimmed,
+            None => return Err(missing_separator('%', s)),
+        };
+        Ok(Percent(parse_finite(number, s)? / 100.0))
+    }
+}
+
+/// A signed ratio written as a percentage or in basis points, such as `"+2.5%"` or `"-50bps"`, parsed into the fraction it represents.
+///
+/// A `%` suffix divides the number by 100 and a `bps` suffix divides it by 10 000, so `"+2.5%"` is `0.025`
+/// and `"-50bps"` is `-0.005`. The number is parsed as an [`f64`] and may start with a sign,
+/// whitespace around the number and the suffix is ignored.
+/// Any other suffix results in [`ParseError::Other`], as does a missing suffix unless `OPTIONAL` is set,
+/// in which case a bare number is already the fraction. Like [`Percent`], NaN and infinities are rejected.
+///
+/// ```
+/// # use prse::{parse, try_parse, Ratio};
+/// let rate: Ratio = parse!("rate=+2.5%", "rate={}");
+/// assert_eq!(rate.0, 0.025);
+///
+/// let spread: Ratio = parse!("spread=-50bps", "spread={}");
+/// assert_eq!(spread.0, -0.005);
+///
+/// let bare: Result<Ratio, _> = try_parse!("rate=0.02", "rate={}");
+/// assert!(bare.is_err());
+/// let bare: Ratio<true> = parse!("rate=0.02", "rate={}");
+/// assert_eq!(bare.0, 0.02);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Ratio<const OPTIONAL: bool = false>(pub f64);
+
+impl<const OPTIONAL: bool> Deref for Ratio<OPTIONAL> {
+    type Target = f64;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<const OPTIONAL: bool> DerefMut for Ratio<OPTIONAL> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<'a, const OPTIONAL: bool> Parse<'a> for Ratio<OPTIONAL> {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        let trimmed = s.trim();
+        let missing_suffix =
+            || ParseError::other(format_args!("expected {s:?} to end with % or bps."));
+        let (number, scale) = if let Some(number) = trimmed.strip_suffix('%') {
+            (number, 100.0)
+        } else if let Some(number) = trimmed.strip_suffix("bps") {
+            (number, 10_000.0)
+        } else if trimmed.parse::<f64>().is_ok() {
+            // A bare number, possibly written as a word like `inf`, so it is not mistaken for a suffix.
+            if !OPTIONAL {
+                return Err(missing_suffix());
+            }
+            (trimmed, 1.0)
+        } else {
+            let number_end = trimmed
+                .rfind(|c: char| c.is_ascii_digit() || c == '.')
+                .map_or(0, |idx| idx + 1);
+            match trimmed[number_end..].trim_start() {
+                "" if OPTIONAL => (trimmed, 1.0),
+                "" => return Err(missing_suffix()),
+                suffix => {
+                    return Err(ParseError::other(format_args!(
+                        "unknown suffix {suffix:?} in {s:?}, expected % or bps."
+                    )))
+                }
+            }
+        };
+        Ok(Ratio(parse_finite(number.trim_end(), s)? / scale))
+    }
+}
+
+/// A number directly followed by its unit, such as `"30mph"`, where the unit is parsed as a `U`.
+///
+/// The number is the longest prefix made of an optional sign, ASCII digits and `.`, the rest
+/// of the string is the unit. Exponents are not supported since they would be mistaken for units like `em`.
+/// Whitespace around the number and the unit is ignored, so `"30 mph"` is also accepted.
+/// Usually `U` is a derived enum with a pattern for each unit, so an unknown unit results in the error of `U`.
+///
+/// ```
+/// # use prse::{parse, try_parse, Parse, WithUnit};
+/// #[derive(Debug, PartialEq, Parse)]
+/// enum Speed {
+///     #[prse = "mph"]
+///     Mph,
+///     #[prse = "kph"]
+///     Kph,
+/// }
+///
+/// let speed: WithUnit<u32, Speed> = parse!("speed=30mph", "speed={}");
+/// assert_eq!(speed, WithUnit { value: 30, unit: Speed::Mph });
+///
+/// let unknown: Result<WithUnit<u32, Speed>, _> = try_parse!("speed=30knots", "speed={}");
+/// assert!(unknown.is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WithUnit<N, U> {
+    /// The number before the unit.
+    pub value: N,
+    /// The unit after the number.
+    pub unit: U,
+}
+
+impl<'a, N: Parse<'a>, U: Parse<'a>> Parse<'a> for WithUnit<N, U> {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        let s = s.trim();
+        let sign = usize::from(s.starts_with(['+', '-']));
+        let end = s[sign..]
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .map_or(s.len(), |idx| idx + sign);
+        let (value, unit) = s.split_at(end);
+        Ok(WithUnit {
+            value: N::from_str(value)?,
+            unit: U::from_str(unit.trim_start())?,
+        })
+    }
+}
+
+/// Parses the number of a [`Percent`] or [`Ratio`], which can never be NaN or infinite.
+fn parse_finite(number: &str, s: &str) -> Result<f64, ParseError> {
+    let value = number.parse::<f64>()?;
+    if !value.is_finite() {
+        return Err(ParseError::other(format_args!(
+            "{number:?} in {s:?} is not a finite number."
+        )));
+    }
+    Ok(value)
+}
+
+#[cfg(feature = "alloc")]
+fn missing_separator(sep: char, s: &str) -> ParseError {
+    extern crate alloc;
+    use alloc::string::ToString;
+
+    ParseError::Literal {
+        expected: sep.to_string(),
+        found: s.to_string(),
+    }
+}
+
+#[cfg(not(feature = "alloc"))]
+fn missing_separator(_sep: char, _s: &str) -> ParseError {
+    ParseError::Literal
+}
+
+/// The set of strings that [`Missing`] parses as `None`.
+///
+/// ```
+/// # use prse::{parse, Missing, Sentinels};
+/// struct Fortran;
+///
+/// impl Sentinels for Fortran {
+///     const SENTINELS: &'static [&'static str] = &["-999", "-999.0"];
+/// }
+///
+/// let depth: Missing<f64, Fortran> = parse!("depth: -999", "depth: {}");
+/// assert_eq!(*depth, None);
+/// ```
+pub trait Sentinels {
+    /// The strings that are parsed as `None`, they are compared to the input after trimming whitespace.
+    const SENTINELS: &'static [&'static str];
+}
+
+/// The default [`Sentinels`] of [`Missing`]: `""`, `"NA"`, `"N/A"`, `"NaN"`, `"null"`, `"-"` and `"."`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NotAvailable;
+
+impl Sentinels for NotAvailable {
+    const SENTINELS: &'static [&'static str] = &["", "NA", "N/A", "NaN", "null", "-", "."];
+}
+
+/// The [`Sentinels`] of an empty or whitespace only string, used to parse the empty elements of sparse rows.
+///
+/// ```
+/// # use prse::{parse, Blank, Missing};
+/// let row: [Missing<i32, Blank>; 4] = parse!("1,,3,", "{:,:4:trailing}");
+/// assert_eq!(row.map(|v| v.into_inner()), [Some(1), None, Some(3), None]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Blank;
+
+impl Sentinels for Blank {
+    const SENTINELS: &'static [&'static str] = &[""];
+}
+
+/// An optional value where a missing value is written using one of the strings of `S`.
+///
+/// If the trimmed input is one of [`S::SENTINELS`](Sentinels::SENTINELS) the value is `None`,
+/// otherwise it is parsed as `Some(T)` and any error from `T` is returned as is.
+/// This is useful for data files that use placeholders such as `NA` or `-999` for missing values.
+///
+/// ```
+/// # use prse::{parse, try_parse, Missing};
+/// let values: [Missing<u32>; 3] = parse!("12,NA,4", "{:,:3}");
+/// assert_eq!(values.map(|v| v.into_inner()), [Some(12), None, Some(4)]);
+///
+/// let invalid: Result<Missing<u32>, _> = try_parse!("twelve", "{}");
+/// assert!(invalid.is_err());
+/// ```
+pub struct Missing<T, S = NotAvailable> {
+    value: Option<T>,
+    sentinels: PhantomData<fn() -> S>,
+}
+
+impl<T, S> Missing<T, S> {
+    /// Creates a new `Missing` from an optional value.
+    pub fn new(value: Option<T>) -> Self {
+        Missing {
+            value,
+            sentinels: PhantomData,
+        }
+    }
+
+    /// Returns the optional value.
+    pub fn into_inner(self) -> Option<T> {
+        self.value
+    }
+}
+
+impl<T, S> Deref for Missing<T, S> {
+    type Target = Option<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T, S> DerefMut for Missing<T, S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+impl<T, S> From<Missing<T, S>> for Option<T> {
+    fn from(missing: Missing<T, S>) -> Self {
+        missing.value
+    }
+}
+
+impl<T: core::fmt::Debug, S> core::fmt::Debug for Missing<T, S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("Missing").field(&self.value).finish()
+    }
+}
+
+impl<T: Clone, S> Clone for Missing<T, S> {
+    fn clone(&self) -> Self {
+        Missing::new(self.value.clone())
+    }
+}
+
+impl<T: Copy, S> Copy for Missing<T, S> {}
+
+impl<T: PartialEq, S> PartialEq for Missing<T, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Eq, S> Eq for Missing<T, S> {}
+
+impl<T: Hash, S> Hash for Missing<T, S> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+impl<'a, T: Parse<'a>, S: Sentinels> Parse<'a> for Missing<T, S> {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        if S::SENTINELS.contains(&s.trim()) {
+            Ok(Missing::new(None))
+        } else {
+            T::from_str(s).map(|v| Missing::new(Some(v)))
+        }
+    }
+}
+
+/// The names of the bits that [`BitFlags`] accepts.
+///
+/// ```
+/// # use prse::{parse, BitFlags, FlagNames};
+/// struct Permissions;
+///
+/// impl FlagNames for Permissions {
+///     const FLAGS: &'static [(&'static str, u64)] = &[("read", 0b100), ("write", 0b010), ("exec", 0b001)];
+/// }
+///
+/// let mode: BitFlags<Permissions> = parse!("mode: read|exec", "mode: {}");
+/// assert_eq!(mode.bits(), 0b101);
+/// ```
+pub trait FlagNames {
+    /// Every name with the bits it sets, the names are compared to the input after trimming whitespace.
+    const FLAGS: &'static [(&'static str, u64)];
+}
+
+/// Lists the names of some flags as `a, b or c`.
+struct FlagList(&'static [(&'static str, u64)]);
+
+impl core::fmt::Display for FlagList {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (idx, (name, _)) in self.0.iter().enumerate() {
+            match idx {
+                0 => {}
+                _ if idx + 1 == self.0.len() => f.write_str(" or ")?,
+                _ => f.write_str(", ")?,
+            }
+            f.write_str(name)?;
+        }
+        Ok(())
+    }
+}
+
+/// A set of bits parsed from a `|` separated list of flag names, such as `"read|write"`.
+///
+/// The bits of every name in [`F::FLAGS`](FlagNames::FLAGS) are OR-ed together and an empty string has no bits set.
+/// A name that is not one of the flags results in a [`ParseError::Other`] listing the valid names.
+///
+/// ```
+/// # use prse::{parse, try_parse, BitFlags, FlagNames};
+/// struct Permissions;
+///
+/// impl FlagNames for Permissions {
+///     const FLAGS: &'static [(&'static str, u64)] = &[("read", 4), ("write", 2), ("exec", 1)];
+/// }
+///
+/// let perms: BitFlags<Permissions> = parse!("read | write", "{}");
+/// assert_eq!(perms.bits(), 6);
+///
+/// let unknown: Result<BitFlags<Permissions>, _> = try_parse!("read|delete", "{}");
+/// assert!(unknown.is_err());
+/// ```
+pub struct BitFlags<F> {
+    bits: u64,
+    names: PhantomData<fn() -> F>,
+}
+
+impl<F> BitFlags<F> {
+    /// Creates a new `BitFlags` with the given bits set.
+    pub fn from_bits(bits: u64) -> Self {
+        BitFlags {
+            bits,
+            names: PhantomData,
+        }
+    }
+
+    /// Returns the bits that are set.
+    pub fn bits(&self) -> u64 {
+        self.bits
+    }
+}
+
+impl<F> From<BitFlags<F>> for u64 {
+    fn from(flags: BitFlags<F>) -> Self {
+        flags.bits
+    }
+}
+
+impl<F> core::fmt::Debug for BitFlags<F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("BitFlags")
+            .field(&format_args!("{:#b}", self.bits))
+            .finish()
+    }
+}
+
+impl<F> Clone for BitFlags<F> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<F> Copy for BitFlags<F> {}
+
+impl<F> PartialEq for BitFlags<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.bits == other.bits
+    }
+}
+
+impl<F> Eq for BitFlags<F> {}
+
+impl<F> Hash for BitFlags<F> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bits.hash(state);
+    }
+}
+
+impl<'a, F: FlagNames> Parse<'a> for BitFlags<F> {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        if s.trim().is_empty() {
+            return Ok(BitFlags::from_bits(0));
+        }
+        s.split('|')
+            .map(str::trim)
+            .try_fold(0, |bits, name| {
+                match F::FLAGS.iter().find(|(n, _)| *n == name) {
+                    Some((_, bit)) => Ok(bits | bit),
+                    None => Err(ParseError::other(format_args!(
+                        "unknown flag {name:?}, expected one of {}.",
+                        FlagList(F::FLAGS)
+                    ))),
+                }
+            })
+            .map(BitFlags::from_bits)
+    }
+}
+
+/// The words for true and false that [`LocaleBool`] accepts.
+///
+/// [`English`], [`German`], [`French`] and [`Spanish`] are provided, and any other language or table can be added
+/// by implementing this trait.
+///
+/// ```
+/// # use prse::{parse, BoolWords, LocaleBool};
+/// struct Dutch;
+///
+/// impl BoolWords for Dutch {
+///     const TRUE: &'static [&'static str] = &["ja", "waar", "aan"];
+///     const FALSE: &'static [&'static str] = &["nee", "onwaar", "uit"];
+/// }
+///
+/// let enabled: LocaleBool<Dutch> = parse!("enabled: Ja", "enabled: {}");
+/// assert!(*enabled);
+/// ```
+pub trait BoolWords {
+    /// The words parsed as `true`, they are compared to the trimmed input ignoring case.
+    const TRUE: &'static [&'static str];
+    /// The words parsed as `false`, they are compared to the trimmed input ignoring case.
+    const FALSE: &'static [&'static str];
+}
+
+/// The English [`BoolWords`]: `yes`, `y`, `true`, `on` and `no`, `n`, `false`, `off`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct English;
+
+impl BoolWords for English {
+    const TRUE: &'static [&'static str] = &["yes", "y", "true", "on"];
+    const FALSE: &'static [&'static str] = &["no", "n", "false", "off"];
+}
+
+/// The German [`BoolWords`]: `ja`, `j`, `wahr`, `an` and `nein`, `n`, `falsch`, `aus`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct German;
+
+impl BoolWords for German {
+    const TRUE: &'static [&'static str] = &["ja", "j", "wahr", "an"];
+    const FALSE: &'static [&'static str] = &["nein", "n", "falsch", "aus"];
+}
+
+/// The French [`BoolWords`]: `oui`, `o`, `vrai` and `non`, `n`, `faux`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct French;
+
+impl BoolWords for French {
+    const TRUE: &'static [&'static str] = &["oui", "o", "vrai"];
+    const FALSE: &'static [&'static str] = &["non", "n", "faux"];
+}
+
+/// The Spanish [`BoolWords`]: `sí`, `si`, `s`, `verdadero` and `no`, `n`, `falso`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Spanish;
+
+impl BoolWords for Spanish {
+    const TRUE: &'static [&'static str] = &["sí", "si", "s", "verdadero"];
+    const FALSE: &'static [&'static str] = &["no", "n", "falso"];
+}
+
+/// Lists the words of a [`BoolWords`] as `a, b or c`.
+struct WordList(&'static [&'static str], &'static [&'static str]);
+
+impl core::fmt::Display for WordList {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let len = self.0.len() + self.1.len();
+        for (idx, word) in self.0.iter().chain(self.1).enumerate() {
+            match idx {
+                0 => {}
+                _ if idx + 1 == len => f.write_str(" or ")?,
+                _ => f.write_str(", ")?,
+            }
+            f.write_str(word)?;
+        }
+        Ok(())
+    }
+}
+
+#[doc(hidden)]
+/// Not part of public api, used by the map modifier when the capture is not one of its keys.
+pub fn unmapped_key(s: &str, keys: &'static [&'static str]) -> ParseError {
+    ParseError::other(format_args!(
+        "{s:?} is not a mapped key, expected one of {}.",
+        WordList(keys, &[])
+    ))
+}
+
+#[doc(hidden)]
+/// Not part of public api, used by the == modifier to check the capture against the value parsed from `expected`.
+///
+/// A capture written exactly like the expected value is equal to it, so `expected` is only parsed when they differ.
+pub fn check_expected<'e, T: PartialEq>(
+    value: T,
+    found: &str,
+    expected: &'e str,
+    parse: impl FnOnce(&'e str) -> Result<T, ParseError>,
+) -> Result<T, ParseError> {
+    if found == expected {
+        return Ok(value);
+    }
+    match parse(expected) {
+        Ok(expected_value) if value == expected_value => Ok(value),
+        Ok(_) => Err(ParseError::other(format_args!(
+            "expected {expected:?} but found {found:?}."
+        ))),
+        Err(_) => Err(ParseError::other(format_args!(
+            "the expected value {expected:?} cannot be parsed into the type of the capture."
+        ))),
+    }
+}
+
+/// A bool written as a word of the language `L`, such as `"ja"` or `"nein"` for [`German`].
+///
+/// The trimmed input is compared to the words of [`L::TRUE`](BoolWords::TRUE) and [`L::FALSE`](BoolWords::FALSE)
+/// ignoring case, including for non ASCII chars. Any other input results in a [`ParseError::Other`] listing the words.
+///
+/// ```
+/// # use prse::{parse, try_parse, French, German, LocaleBool};
+/// let (debug, verbose): (LocaleBool<German>, LocaleBool<German>) =
+///     parse!("debug=Ja verbose=nein", "debug={} verbose={}");
+/// assert_eq!((*debug, *verbose), (true, false));
+///
+/// let confirmed: LocaleBool = parse!("YES", "{}");
+/// assert!(confirmed.into_inner());
+///
+/// let unknown: Result<LocaleBool<French>, _> = try_parse!("peut-être", "{}");
+/// assert!(unknown.is_err());
+/// ```
+pub struct LocaleBool<L = English> {
+    value: bool,
+    locale: PhantomData<fn() -> L>,
+}
+
+impl<L> LocaleBool<L> {
+    /// Creates a new `LocaleBool` from a bool.
+    pub fn new(value: bool) -> Self {
+        LocaleBool {
+            value,
+            locale: PhantomData,
+        }
+    }
+
+    /// Returns the bool.
+    pub fn into_inner(self) -> bool {
+        self.value
+    }
+}
+
+impl<L> Deref for LocaleBool<L> {
+    type Target = bool;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<L> From<LocaleBool<L>> for bool {
+    fn from(value: LocaleBool<L>) -> Self {
+        value.value
+    }
+}
+
+impl<L> core::fmt::Debug for LocaleBool<L> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("LocaleBool").field(&self.value).finish()
+    }
+}
+
+impl<L> Clone for LocaleBool<L> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<L> Copy for LocaleBool<L> {}
+
+impl<L> PartialEq for LocaleBool<L> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<L> Eq for LocaleBool<L> {}
+
+impl<L> Hash for LocaleBool<L> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+impl<'a, L: BoolWords> Parse<'a> for LocaleBool<L> {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        let word = s.trim();
+        let matches = |w: &&str| {
+            w.chars()
+                .flat_map(char::to_lowercase)
+                .eq(word.chars().flat_map(char::to_lowercase))
+        };
+        if L::TRUE.iter().any(matches) {
+            Ok(LocaleBool::new(true))
+        } else if L::FALSE.iter().any(matches) {
+            Ok(LocaleBool::new(false))
+        } else {
+            Err(ParseError::other(format_args!(
+                "{word:?} is not a bool, expected one of {}.",
+                WordList(L::TRUE, L::FALSE)
+            )))
+        }
+    }
+}
+
+/// A value that is either an `A` or a `B`, such as a column holding both numbers and labels.
+///
+/// `A` is tried first and `B` is only tried if `A` fails, returning the first of them that succeeds.
+/// If both fail, the error of `A` is returned. Using `&str` as `B` means parsing never fails,
+/// as any input that is not an `A` is kept as is.
+///
+/// ```
+/// # use prse::{parse, OneOf2};
+/// let cells: [OneOf2<f64, &str>; 3] = parse!("1.5,N/A,-2", "{:,:3}");
+/// assert_eq!(cells, [OneOf2::First(1.5), OneOf2::Second("N/A"), OneOf2::First(-2.0)]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OneOf2<A, B> {
+    /// The input was parsed as an `A`.
+    First(A),
+    /// The input was not an `A` but was parsed as a `B`.
+    Second(B),
+}
+
+impl<'a, A: Parse<'a>, B: Parse<'a>> Parse<'a> for OneOf2<A, B> {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        match A::from_str(s) {
+            Ok(a) => Ok(OneOf2::First(a)),
+            Err(e) => B::from_str(s).map(OneOf2::Second).map_err(|_| e),
+        }
+    }
+}
+
+/// A value that is either an `A`, a `B` or a `C`.
+///
+/// The types are tried in order and the first of them that succeeds is returned, like [`OneOf2`].
+/// If all of them fail, the error of `A` is returned.
+///
+/// ```
+/// # use prse::{parse, OneOf3};
+/// let cells: [OneOf3<u8, bool, &str>; 3] = parse!("7 true seven", "{: :3}");
+/// assert_eq!(cells, [OneOf3::First(7), OneOf3::Second(true), OneOf3::Third("seven")]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OneOf3<A, B, C> {
+    /// The input was parsed as an `A`.
+    First(A),
+    /// The input was not an `A` but was parsed as a `B`.
+    Second(B),
+    /// The input was neither an `A` nor a `B` but was parsed as a `C`.
+    Third(C),
+}
+
+impl<'a, A: Parse<'a>, B: Parse<'a>, C: Parse<'a>> Parse<'a> for OneOf3<A, B, C> {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        match A::from_str(s) {
+            Ok(a) => Ok(OneOf3::First(a)),
+            Err(e) => B::from_str(s)
+                .map(OneOf3::Second)
+                .or_else(|_| C::from_str(s).map(OneOf3::Third))
+                .map_err(|_| e),
+        }
+    }
+}
+
+/// A `Vec` of elements separated by `SEP`, which defaults to `,`, that is sorted once it has been parsed.
+///
+/// The elements are parsed like the elements of a repetition, so an empty string or a trailing separator
+/// do not add an element, and are then sorted with a stable sort. Use [`Vec::dedup`] to also remove duplicates,
+/// or parse into a `BTreeSet` instead. `SortedVec` also implements [`FromIterator`], so it can collect a
+/// [`ParseIter`](crate::ParseIter) or be used as a `#[prse(multi)]` field.
+///
+/// This type is only available with the `alloc` feature.
+///
+/// ```
+/// # use prse::{parse, ParseIter, SortedVec};
+/// let ports: SortedVec<u16> = parse!("ports: 443,80,8080", "ports: {}");
+/// assert_eq!(ports.0, [80, 443, 8080]);
+///
+/// let mut names: SortedVec<&str, ' '> = parse!("dave alice dave bob", "{}");
+/// names.dedup();
+/// assert_eq!(*names, ["alice", "bob", "dave"]);
+///
+/// let sizes: Result<SortedVec<u32>, _> = parse!("3-1-2", "{:-:0}").collect();
+/// assert_eq!(sizes.unwrap().0, [1, 2, 3]);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct SortedVec<T, const SEP: char = ','>(pub Vec<T>);
+
+#[cfg(feature = "alloc")]
+impl<T, const SEP: char> Deref for SortedVec<T, SEP> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, const SEP: char> DerefMut for SortedVec<T, SEP> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, const SEP: char> From<SortedVec<T, SEP>> for Vec<T> {
+    fn from(sorted: SortedVec<T, SEP>) -> Self {
+        sorted.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Ord, const SEP: char> FromIterator<T> for SortedVec<T, SEP> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut elements: Vec<T> = iter.into_iter().collect();
+        elements.sort();
+        SortedVec(elements)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T: Parse<'a> + Ord, const SEP: char> Parse<'a> for SortedVec<T, SEP> {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        s.split_terminator(SEP)
+            .map(|element| {
+                crate::__private::add_err_multi_context(T::from_str(element), s, element)
+            })
+            .collect()
+    }
+}
+
+/// An [`Ipv6Addr`](std::net::Ipv6Addr) that may be followed by a `%` and a zone identifier, such as `fe80::1%eth0`.
+///
+/// [`Ipv6Addr`](std::net::Ipv6Addr) itself rejects zone identifiers, which are needed to tell apart
+/// link-local addresses on different interfaces. The zone is kept as an opaque string as its meaning
+/// depends on the platform: it can be an interface name or its index. A malformed address results in
+/// [`ParseError::Addr`] and a `%` that is not followed by a zone is an error.
+///
+/// This type is only available with the `std` feature.
+///
+/// ```
+/// # use prse::{parse, ScopedIpv6};
+/// # use std::net::Ipv6Addr;
+/// let scoped: ScopedIpv6 = parse!("via fe80::1%eth0", "via {}");
+/// assert_eq!(scoped.addr, Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1));
+/// assert_eq!(scoped.zone.as_deref(), Some("eth0"));
+///
+/// let plain: ScopedIpv6 = parse!("via ::1", "via {}");
+/// let (addr, zone): (Ipv6Addr, Option<String>) = plain.into();
+/// assert_eq!((addr, zone), (Ipv6Addr::LOCALHOST, None));
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ScopedIpv6 {
+    /// The address without its zone.
+    pub addr: std::net::Ipv6Addr,
+    /// The zone identifier after the `%`, if there was one.
+    pub zone: Option<String>,
+}
+
+#[cfg(feature = "std")]
+impl From<ScopedIpv6> for (std::net::Ipv6Addr, Option<String>) {
+    fn from(scoped: ScopedIpv6) -> Self {
+        (scoped.addr, scoped.zone)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<(std::net::Ipv6Addr, Option<String>)> for ScopedIpv6 {
+    fn from((addr, zone): (std::net::Ipv6Addr, Option<String>)) -> Self {
+        ScopedIpv6 { addr, zone }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> Parse<'a> for ScopedIpv6 {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        let (addr, zone) = match s.split_once('%') {
+            Some((_, "")) => {
+                return Err(ParseError::other(format_args!(
+                    "expected a zone identifier after the % of {s:?}."
+                )))
+            }
+            Some((addr, zone)) => (addr, Some(zone.to_string())),
+            None => (s, None),
+        };
+        Ok(ScopedIpv6 {
+            addr: addr.parse()?,
+            zone,
+        })
+    }
+}
+
+/// A string with its backslash escapes replaced by the characters they stand for, such as a value in a
+/// `.env` or properties file.
+///
+/// The supported escapes are the same as in a Rust string literal:
+/// - `\n`, `\r` and `\t` for a newline, a carriage return and a tab.
+/// - `\\` and `\"` for a backslash and a double quote.
+/// - `\xNN` for the ASCII char with the two hex digits `NN`, which must be at most `7f`.
+/// - `\u{...}` for the unicode char with 1 to 6 hex digits inside the braces.
+///
+/// Any other escape, including a trailing backslash, results in [`ParseError::Other`].
+/// Quotes around the string are not removed, use the `qe` modifier or a literal in the pattern for that.
+///
+/// This type is only available with the `alloc` feature.
+///
+/// ```
+/// # use prse::{parse, try_parse, Unescaped};
+/// let value: Unescaped = parse!(r"GREETING=hello\tworld\n", "GREETING={}");
+/// assert_eq!(*value, "hello\tworld\n");
+///
+/// let value: Unescaped = parse!(r"\x41\u{e9}\\", "{}");
+/// assert_eq!(value.0, "Aé\\");
+///
+/// let value: Result<Unescaped, _> = try_parse!(r"C:\path", "{}");
+/// assert!(value.is_err());
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Unescaped(pub String);
+
+#[cfg(feature = "alloc")]
+impl Deref for Unescaped {
+    type Target = String;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl DerefMut for Unescaped {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Unescaped> for String {
+    fn from(unescaped: Unescaped) -> Self {
+        unescaped.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Parse<'a> for Unescaped {
+    fn from_str(s: &'a str) -> Result<Self, ParseError> {
+        let mut unescaped = String::with_capacity(s.len());
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                unescaped.push(c);
+                continue;
+            }
+            let escaped = match chars.next() {
+                Some('n') => '\n',
+                Some('r') => '\r',
+                Some('t') => '\t',
+                Some('\\') => '\\',
+                Some('"') => '"',
+                Some('x') => chars
+                    .as_str()
+                    .get(..2)
+                    .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                    .filter(u8::is_ascii)
+                    .map(|byte| {
+                        chars.nth(1);
+                        char::from(byte)
+                    })
+                    .ok_or_else(|| {
+                        ParseError::other(format_args!(
+                            "expected two hex digits up to 7f after \\x in {s:?}."
+                        ))
+                    })?,
+                Some('u') => chars
+                    .as_str()
+                    .strip_prefix('{')
+                    .and_then(|rest| rest.split_once('}'))
+                    .filter(|(hex, _)| {
+                        (1..=6).contains(&hex.len()) && hex.bytes().all(|b| b.is_ascii_hexdigit())
+                    })
+                    .and_then(|(hex, rest)| {
+                        let c = char::from_u32(u32::from_str_radix(hex, 16).ok()?)?;
+                        Some((c, rest))
+                    })
+                    .map(|(c, rest)| {
+                        chars = rest.chars();
+                        c
+                    })
+                    .ok_or_else(|| {
+                        ParseError::other(format_args!(
+                            "expected 1 to 6 hex digits of a unicode char in braces after \\u in {s:?}."
+                        ))
+                    })?,
+                Some(c) => {
+                    return Err(ParseError::other(format_args!(
+                        "unknown escape \\{c} in {s:?}, expected one of \\n, \\r, \\t, \\\\, \\\", \\x or \\u."
+                    )))
+                }
+                None => {
+                    return Err(ParseError::other(format_args!(
+                        "expected an escape after the trailing \\ of {s:?}."
+                    )))
+                }
+            };
+            unescaped.push(escaped);
+        }
+        Ok(Unescaped(unescaped))
+    }
+}
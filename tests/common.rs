@@ -1,6 +1,15 @@
 mod common {
-    use prse::{parse, Parse};
-    use prse::{ParseChars, ParseIter};
+    use prse::{
+        parse, parse_advance, parse_fn, parse_for_each, parse_located, parse_or, parse_prefix,
+        pattern_literals, try_parse, try_parse_advance, try_parse_located, try_parse_prefix,
+        which_matches, Parse,
+    };
+    use prse::{
+        BitFlags, Blank, BoolWords, CaseInsensitive, English, Fixed, FlagNames, Fraction, French,
+        German, Grouped, HexArray, Hms, KeyValue, Length, LocaleBool, Location, Mass, Missing,
+        OneOf2, OneOf3, ParseChars, ParseIter, PathComponents, Quantity, Rgb, Rgba, Sentinels,
+        SignedDuration, Spanish, Spanned, SteppedRange, Time, Units, WithRaw, WithUnit,
+    };
 
     #[test]
     fn empty_literal() {
@@ -10,6 +19,32 @@ mod common {
         parse!(input, "Test")
     }
 
+    #[test]
+    fn empty_input() {
+        let unit: Result<(), _> = try_parse!("", "");
+        assert!(unit.is_ok());
+        let trailing: Result<(), _> = try_parse!("x", "");
+        assert!(matches!(trailing, Err(prse::ParseError::Literal { .. })));
+        let trailing: Result<(), _> = try_parse!(" ", "");
+        assert!(matches!(trailing, Err(prse::ParseError::Literal { .. })));
+
+        let s: Result<&str, _> = try_parse!("", "{}");
+        assert_eq!(s, Ok(""));
+        let s: &str = parse!("key=", "key={}");
+        assert_eq!(s, "");
+
+        let n: Result<u32, _> = try_parse!("", "{}");
+        assert!(matches!(
+            n.unwrap_err().root_cause(),
+            prse::ParseError::Int(_)
+        ));
+        let n: Result<i64, _> = try_parse!("key=", "key={}");
+        assert!(matches!(
+            n.unwrap_err().root_cause(),
+            prse::ParseError::Int(_)
+        ));
+    }
+
     #[derive(Parse, Eq, PartialEq, Debug)]
     enum SimpleAlphabet {
         #[prse = "A"]
@@ -91,6 +126,298 @@ mod common {
         assert_eq!(MultiSep { arr: [23, 1] }, parse!(":::23::::1", "{}"))
     }
 
+    #[derive(Parse, Eq, PartialEq, Debug)]
+    #[prse = r"{arr:[,\s]+:3}"]
+    struct ClassSep {
+        arr: [u32; 3],
+    }
+
+    #[test]
+    fn parse_separator_class() {
+        let arr: [u8; 4] = parse!("1, 2  3,4", "{:[, ]+:4}");
+        assert_eq!([1, 2, 3, 4], arr);
+        let arr: [u8; 4] = parse!("1,\t2 \t,3\t4", r"{:[,\s]+:4}");
+        assert_eq!([1, 2, 3, 4], arr);
+        let arr: [u8; 2] = parse!(", 1 ,2, ", "{:[, ]+:2}");
+        assert_eq!([1, 2], arr);
+        let arr: [&str; 3] = parse!("a]-b]c", r"{:[\]-]+:3}");
+        assert_eq!(["a", "b", "c"], arr);
+        assert_eq!(ClassSep { arr: [5, 6, 7] }, parse!("\t5,6 7\n", "{}"));
+
+        let mut iter: ParseIter<u8> = parse!(" 1,2 ,, 3 ", "{:[, ]+:0}");
+        assert_eq!(iter.next(), Some(Ok(1)));
+        assert_eq!(iter.next(), Some(Ok(2)));
+        assert_eq!(iter.next(), Some(Ok(3)));
+        assert_eq!(iter.next(), None);
+        let mut iter: ParseIter<u8> = parse!(" ,, ", "{:[, ]+:0}");
+        assert_eq!(iter.next(), None);
+
+        assert!(try_parse!("1, 2, 3", "{:[, ]+:2}")
+            .map(|_: [u8; 2]| ())
+            .is_err());
+        assert!(try_parse!("1; 2", "{:[, ]+:2}")
+            .map(|_: [u8; 2]| ())
+            .is_err());
+    }
+
+    #[test]
+    fn parse_separator_set() {
+        let arr: [u8; 4] = parse!("1,2;3,4", "{:[,;]:4}");
+        assert_eq!([1, 2, 3, 4], arr);
+        let arr: [&str; 5] = parse!("a,;b;;c", "{:[,;]:5}");
+        assert_eq!(["a", "", "b", "", "c"], arr);
+        let arr: [&str; 3] = parse!("a,;b;;c", "{:[,;]:!3}");
+        assert_eq!(["a", "b", "c"], arr);
+        let arr: [u8; 3] = parse!("1 ,2\t; 3", "{:[,;]:3:trim}");
+        assert_eq!([1, 2, 3], arr);
+        let arr: [&str; 3] = parse!("a]b-c", r"{:[\]-]:3}");
+        assert_eq!(["a", "b", "c"], arr);
+
+        let mut iter: ParseIter<&str> = parse!(";a,", "{:[,;]:0}");
+        assert_eq!(iter.next(), Some(Ok("")));
+        assert_eq!(iter.next(), Some(Ok("a")));
+        assert_eq!(iter.next(), None);
+        let mut iter: ParseIter<&str> = parse!(";a,", "{:[,;]:0:trailing}");
+        assert_eq!(iter.next(), Some(Ok("")));
+        assert_eq!(iter.next(), Some(Ok("a")));
+        assert_eq!(iter.next(), Some(Ok("")));
+        assert_eq!(iter.next(), None);
+        let mut iter: ParseIter<&str> = parse!(";a,", "{:[,;]:!0}");
+        assert_eq!(iter.next(), Some(Ok("a")));
+        assert_eq!(iter.next(), None);
+        let mut iter: ParseIter<&str> = parse!("", "{:[,;]:0}");
+        assert_eq!(iter.next(), None);
+
+        assert!(try_parse!("1,;2", "{:[,;]:2}")
+            .map(|_: [u8; 2]| ())
+            .is_err());
+    }
+
+    #[derive(Parse, Debug, PartialEq)]
+    #[prse = "{method} {path}{[ {version}]}"]
+    struct Request<'a> {
+        method: &'a str,
+        path: &'a str,
+        #[prse(default = "HTTP/1.0")]
+        version: &'a str,
+        #[prse(default = "80")]
+        port: u16,
+    }
+
+    #[derive(Parse, Debug, PartialEq)]
+    #[prse = "{}{[:{}]}"]
+    struct Host<'a>(&'a str, Option<u16>);
+
+    #[derive(Parse, Debug, PartialEq)]
+    #[prse = "{host}{[:{port:default=80}]}"]
+    struct Server<'a> {
+        host: &'a str,
+        port: u16,
+    }
+
+    #[test]
+    fn parse_escaped_bracket_separator() {
+        let nums: [u32; 3] = parse!("1[,]2[,]3", r"{:\[,]:3}");
+        assert_eq!(nums, [1, 2, 3]);
+        let nums: [u32; 2] = parse!("1[, ]+2", r"{:\[, ]+:2}");
+        assert_eq!(nums, [1, 2]);
+        let nums: [u32; 2] = parse!("1;:[,:2]2", r"{:;:\[,:2]:2}");
+        assert_eq!(nums, [1, 2]);
+    }
+
+    #[test]
+    fn parse_optional_groups() {
+        let (method, path, version): (&str, &str, Option<&str>) =
+            parse!("GET /index.html HTTP/1.1", "{} {}{[ {}]}");
+        assert_eq!(
+            (method, path, version),
+            ("GET", "/index.html", Some("HTTP/1.1"))
+        );
+        let (method, path, version): (&str, &str, Option<&str>) =
+            parse!("GET /index.html", "{} {}{[ {}]}");
+        assert_eq!((method, path, version), ("GET", "/index.html", None));
+
+        let mut version = "HTTP/1.0";
+        let path: &str = parse!("GET /", "GET {}{[ {version}]}");
+        assert_eq!((path, version), ("/", "HTTP/1.0"));
+        let path: &str = parse!("GET / HTTP/2", "GET {}{[ {version}]}");
+        assert_eq!((path, version), ("/", "HTTP/2"));
+
+        let request: Request = parse!("POST /login HTTP/1.1", "{}");
+        assert_eq!(
+            request,
+            Request {
+                method: "POST",
+                path: "/login",
+                version: "HTTP/1.1",
+                port: 80,
+            }
+        );
+        let request: Request = parse!("GET /", "{}");
+        assert_eq!(request.version, "HTTP/1.0");
+
+        let version: &str;
+        let path: &str = parse!("GET /", "GET {}{[ {version:default=HTTP/1.0}]}");
+        assert_eq!((path, version), ("/", "HTTP/1.0"));
+        let (path, version): (&str, &str) =
+            parse!("GET / HTTP/2", "GET {}{[ {:default=HTTP/1.0}]}");
+        assert_eq!((path, version), ("/", "HTTP/2"));
+        let servers: [Server; 2] = parse!("a:8080 b", "{: :2}");
+        assert_eq!(
+            servers,
+            [
+                Server {
+                    host: "a",
+                    port: 8080
+                },
+                Server {
+                    host: "b",
+                    port: 80
+                }
+            ]
+        );
+        // The default is only parsed when the group is absent.
+        let port: u8 = parse!("host:80", "host{[:{:default=x}]}");
+        assert_eq!(port, 80);
+        let invalid: Result<u8, _> = try_parse!("host", "host{[:{:default=x}]}");
+        assert!(invalid.is_err());
+
+        let hosts: [Host; 2] = parse!("localhost:8080, example.com", "{:, :2}");
+        assert_eq!(
+            hosts,
+            [Host("localhost", Some(8080)), Host("example.com", None)]
+        );
+
+        let (port, path): (Option<u16>, Option<&str>) = parse!("host:80", "host{[:{}]}{[/{}]}");
+        assert_eq!((port, path), (Some(80), None));
+        let (port, path): (Option<u16>, Option<&str>) = parse!("host/a/b", "host{[:{}]}{[/{}]}");
+        assert_eq!((port, path), (None, Some("a/b")));
+        let (port, path): (Option<u16>, Option<&str>) = parse!("host", "host{[:{}]}{[/{}]}");
+        assert_eq!((port, path), (None, None));
+
+        let n: u32 = parse!("5 items", "{}{[ items]}");
+        assert_eq!(n, 5);
+        let n: u32 = parse!("5", "{}{[ items]}");
+        assert_eq!(n, 5);
+
+        // Without the group the whole input is captured, so it is only an error if both fail.
+        let fallback: Result<(&str, Option<u16>), _> = try_parse!("host:http", "{}{[:{}]}");
+        assert_eq!(fallback, Ok(("host:http", None)));
+        let invalid: Result<(u8, Option<u8>), _> = try_parse!("1:x", "{}{[:{}]}");
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn parse_tuple_groups() {
+        let point: Option<(i32, i32)> = parse!("pt=(1,-2)", "pt={(({},{}))}");
+        assert_eq!(point, Some((1, -2)));
+        let point: Option<(i32, i32)> = parse!("pt=", "pt={(({},{}))}");
+        assert_eq!(point, None);
+        let point: Result<Option<(i32, i32)>, _> = try_parse!("pt=(1,x)", "pt={(({},{}))}");
+        assert!(point.is_err());
+
+        let (name, port): (&str, Option<u16>) = parse!("localhost:80", "{}{(:{})}");
+        assert_eq!((name, port), ("localhost", Some(80)));
+        let (name, size, color): (&str, Option<(u32, u32)>, Option<&str>) =
+            parse!("logo 16x32 red", "{}{( {}x{})}{[ {}]}");
+        assert_eq!((name, size, color), ("logo", Some((16, 32)), Some("red")));
+        let (name, size, color): (&str, Option<(u32, u32)>, Option<&str>) =
+            parse!("logo red", "{}{( {}x{})}{[ {}]}");
+        assert_eq!((name, size, color), ("logo", None, Some("red")));
+    }
+
+    #[derive(Parse, Debug, PartialEq)]
+    enum Shape {
+        #[prse = "circle r={r}"]
+        Circle { r: f64 },
+        #[prse = "rect w={w} h={h}"]
+        Rect { w: f64, h: f64 },
+        #[prse = "rect {}"]
+        Square(f64),
+        #[prse = "point"]
+        Point,
+    }
+
+    #[test]
+    fn parse_tagged_enum() {
+        let shapes: [Shape; 4] = parse!("circle r=5; rect w=2 h=3; rect 4; point", "{:; :4}");
+        assert_eq!(
+            shapes,
+            [
+                Shape::Circle { r: 5.0 },
+                Shape::Rect { w: 2.0, h: 3.0 },
+                Shape::Square(4.0),
+                Shape::Point
+            ]
+        );
+
+        let unknown: Result<Shape, _> = try_parse!("triangle a=1", "{}");
+        assert!(unknown.is_err());
+        let invalid: Result<Shape, _> = try_parse!("circle r=big", "{}");
+        assert!(invalid.is_err());
+    }
+
+    #[derive(Parse, Debug, Eq, PartialEq)]
+    enum Setting<'a> {
+        #[prse = "{key}={value}"]
+        Value {
+            key: &'a str,
+            value: u32,
+            #[prse(default)]
+            enabled: bool,
+        },
+        #[prse = "{key}"]
+        Flag {
+            key: &'a str,
+            #[prse(default)]
+            value: u32,
+        },
+    }
+
+    #[test]
+    fn parse_default_fields() {
+        let s: Setting = parse!("depth=3", "{}");
+        assert_eq!(
+            s,
+            Setting::Value {
+                key: "depth",
+                value: 3,
+                enabled: false
+            }
+        );
+        let s: Setting = parse!("verbose", "{}");
+        assert_eq!(
+            s,
+            Setting::Flag {
+                key: "verbose",
+                value: 0
+            }
+        );
+    }
+
+    #[derive(Parse, Debug, Eq, PartialEq)]
+    #[prse = "<{tag}>{content}</{tag}>"]
+    struct Element<'a> {
+        tag: &'a str,
+        content: &'a str,
+    }
+
+    #[test]
+    fn parse_back_reference() {
+        let tag: &str;
+        let content: u32 = parse!("<<b>>42<<b>>", "<<{tag}>>{}<<{tag}>>");
+        assert_eq!((tag, content), ("b", 42));
+        assert_eq!(
+            Element {
+                tag: "p",
+                content: "Hello"
+            },
+            parse!("<p>Hello</p>", "{}")
+        );
+        let element: Result<Element, _> = prse::try_parse!("<p>Hello</b>", "{}");
+        assert!(element.is_err());
+    }
+
     #[derive(Parse)]
     #[prse = "({a:,:!0};{b::0})"]
     struct TwoIters<'a> {
@@ -105,4 +432,1978 @@ mod common {
         assert_eq!(48, x.a.map(|x| x.unwrap()).sum::<u32>());
         assert_eq!(15, x.b.map(|x| x.unwrap()).sum::<u32>());
     }
+
+    #[test]
+    fn parse_hex_array() {
+        let mac: HexArray<6> = parse!("MAC 00:1A:2b:3c:4d:5e", "MAC {}");
+        assert_eq!(mac.0, [0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e]);
+        let key: HexArray<2> = parse!("cafe", "{}");
+        assert_eq!(key.0, [0xca, 0xfe]);
+        assert!(prse::try_parse!("cafe", "{}")
+            .map(|k: HexArray<3>| k)
+            .is_err());
+        assert!(prse::try_parse!("c:afe", "{}")
+            .map(|k: HexArray<2>| k)
+            .is_err());
+    }
+
+    #[test]
+    fn parse_colours() {
+        let colours: [Rgb; 2] = parse!("#F80 #ff8800", "{: :2}");
+        assert_eq!(colours, [Rgb(0xff, 0x88, 0x00); 2]);
+        let overlay: Rgba = parse!("overlay: #FF8800AA", "overlay: {}");
+        assert_eq!(overlay, Rgba(0xff, 0x88, 0x00, 0xaa));
+        let short: Rgba = parse!("f80a", "{}");
+        assert_eq!(short, Rgba(0xff, 0x88, 0x00, 0xaa));
+        let opaque: Rgba = parse!("#ff8800", "{}");
+        assert_eq!(opaque, Rgba(0xff, 0x88, 0x00, 0xff));
+
+        assert!(prse::try_parse!("#ff8800aa", "{}").map(|c: Rgb| c).is_err());
+        assert!(prse::try_parse!("#ff88", "{}").map(|c: Rgb| c).is_err());
+        assert!(prse::try_parse!("#ff88g0", "{}").map(|c: Rgba| c).is_err());
+        assert!(prse::try_parse!("#ff880", "{}").map(|c: Rgba| c).is_err());
+        assert!(prse::try_parse!("##f80", "{}").map(|c: Rgba| c).is_err());
+    }
+
+    #[test]
+    fn parse_path_components() {
+        let path: PathComponents = parse!("/usr/local/bin", "{}");
+        assert!(path.is_absolute());
+        assert!(!path.has_trailing_separator());
+        let mut components = path.iter();
+        assert_eq!(components.next(), Some("usr"));
+        assert_eq!(components.next(), Some("local"));
+        assert_eq!(components.next(), Some("bin"));
+        assert_eq!(components.next(), None);
+
+        let path: PathComponents = parse!("path = src//parse/", "path = {}");
+        assert!(!path.is_absolute());
+        assert!(path.has_trailing_separator());
+        assert_eq!(path.iter().next_back(), Some("parse"));
+        assert_eq!(path.iter().count(), 2);
+
+        let root: PathComponents = parse!("/", "{}");
+        assert!(root.is_absolute());
+        assert!(!root.has_trailing_separator());
+        assert_eq!(root.iter().next(), None);
+    }
+
+    #[test]
+    fn parse_prefix() {
+        let path: &str = parse_prefix!("GET /index.html HTTP/1.1", "GET {} ");
+        assert_eq!(path, "/index.html");
+        let path: &str = parse_prefix!("GET /index.html ", "GET {} ");
+        assert_eq!(path, "/index.html");
+
+        let (a, b): (u32, u32) = parse_prefix!("1-2-3-4", "{}-{}-");
+        assert_eq!((a, b), (1, 2));
+
+        // A trailing capture still takes the rest of the input.
+        let rest: &str = parse_prefix!("key: some value", "key: {}");
+        assert_eq!(rest, "some value");
+
+        parse_prefix!("Hello world", "Hello");
+        parse_prefix!("Hello", "Hello");
+        parse_prefix!("Say Hello world", "Hello");
+        assert!(try_parse_prefix!("Hell", "Hello").is_err());
+        let missing: Result<u32, _> = try_parse_prefix!("1", "{}-");
+        assert!(missing.is_err());
+    }
+
+    #[test]
+    fn parse_advance() {
+        let mut cursor = "id=7; name=ferris; tags=a,b";
+        let id: u32 = parse_advance!(&mut cursor, "id={}; ");
+        assert_eq!(cursor, "name=ferris; tags=a,b");
+        let name: &str = parse_advance!(&mut cursor, "name={}; ");
+        assert_eq!(cursor, "tags=a,b");
+        let [a, b]: [char; 2] = parse_advance!(&mut cursor, "tags={:,:2}");
+        assert_eq!((id, name, a, b), (7, "ferris", 'a', 'b'));
+        assert_eq!(cursor, "");
+
+        // A cursor can also be passed on as a `&mut &str`.
+        fn key<'a>(cursor: &mut &'a str) -> Result<&'a str, prse::ParseError> {
+            try_parse_advance!(cursor, "{}=")
+        }
+        let mut cursor = "x=1";
+        assert_eq!(key(&mut cursor), Ok("x"));
+        let value: u8;
+        parse_advance!(&mut cursor, "{value}");
+        assert_eq!(value, 1);
+
+        let mut cursor = "1,2,";
+        let mut sum = 0_u32;
+        while let Ok::<u32, _>(n) = try_parse_advance!(&mut cursor, "{},") {
+            sum += n;
+        }
+        assert_eq!(sum, 3);
+        parse_advance!(&mut cursor, "");
+    }
+
+    #[test]
+    fn parse_advance_errors() {
+        let mut cursor = "a=1;b=x;";
+        let a: Result<u32, _> = try_parse_advance!(&mut cursor, "a={};");
+        assert_eq!(cursor, "b=x;");
+        // The cursor is left where it was when the pattern does not match or a capture fails to parse.
+        let a2: Result<u32, _> = try_parse_advance!(&mut cursor, "a={};");
+        assert_eq!(cursor, "b=x;");
+        let b: Result<u32, _> = try_parse_advance!(&mut cursor, "b={};");
+        assert_eq!(cursor, "b=x;");
+        assert!(a.is_ok() && a2.is_err() && b.is_err());
+
+        let mut cursor = "k=v;k;";
+        let first: Option<&str> = parse_advance!(&mut cursor, "k{[={}]};");
+        let second: Option<&str> = parse_advance!(&mut cursor, "k{[={}]};");
+        assert_eq!((first, second, cursor), (Some("v"), None, ""));
+    }
+
+    #[test]
+    fn parse_fallback() {
+        let mut fallbacks = 0;
+        let n: u32 = parse_or!("n=12", "n={}", {
+            fallbacks += 1;
+            0
+        });
+        assert_eq!(n, 12);
+        let n: u32 = parse_or!("n=-1", "n={}", {
+            fallbacks += 1;
+            0
+        });
+        assert_eq!(n, 0);
+        let (x, y): (i32, i32) = parse_or!("(1, 2", "({}, {})", {
+            fallbacks += 1;
+            (-1, -1)
+        });
+        assert_eq!((x, y), (-1, -1));
+        assert_eq!(fallbacks, 2);
+    }
+
+    #[test]
+    fn parse_for_each() {
+        // The input is built in a buffer so that the test does not need to allocate either.
+        let mut buf = [b','; 2001];
+        for digit in buf.iter_mut().step_by(2) {
+            *digit = b'7';
+        }
+        let input = core::str::from_utf8(&buf).unwrap();
+        let mut total = 0_u64;
+        let mut count = 0;
+        parse_for_each!(input, "{:,:0}", |n: u64| {
+            total += n;
+            count += 1;
+        })
+        .unwrap();
+        assert_eq!((total, count), (7007, 1001));
+
+        let mut seen = 0;
+        let result = parse_for_each!("values: 1, 2, x, 4", "values: {:, :0}", |_: u8| seen += 1);
+        assert!(result.is_err());
+        assert_eq!(seen, 2);
+
+        let mut chars = 0;
+        parse_for_each!("[abc]", "[{::0}]", |_: char| chars += 1).unwrap();
+        assert_eq!(chars, 3);
+        let prefix: Result<(), _> = parse_for_each!("1,2", "n={:,:0}", |_: u8| {});
+        assert!(prefix.is_err());
+    }
+
+    #[derive(Parse, Debug, PartialEq)]
+    #[prse = "x={x},y={y}"]
+    #[prse = "{x},{y}"]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[derive(Parse, Debug, PartialEq, Clone, Copy)]
+    #[prse = "{0}/{1}"]
+    #[prse = "{1} below {0}"]
+    struct Ratio(u32, u32);
+
+    #[derive(Parse, Debug, PartialEq, Clone, Copy)]
+    #[prse = "yes"]
+    #[prse = "y"]
+    struct Yes;
+
+    #[test]
+    fn derive_alternative_patterns() {
+        let named: Point = parse!("x=1,y=-2", "{}");
+        let positional: Point = parse!("1,-2", "{}");
+        assert_eq!(named, Point { x: 1, y: -2 });
+        assert_eq!(named, positional);
+        let points: [Point; 2] = parse!("x=1,y=2 3,4", "{: :2}");
+        assert_eq!(points, [Point { x: 1, y: 2 }, Point { x: 3, y: 4 }]);
+
+        let ratios: [Ratio; 2] = parse!("50/100, 100 below 50", "{:, :2}");
+        assert_eq!(ratios, [Ratio(50, 100); 2]);
+        assert!(try_parse!("50%", "{}").map(|r: Ratio| r).is_err());
+        let answers: [Yes; 2] = parse!("y yes", "{: :2}");
+        assert_eq!(answers, [Yes, Yes]);
+
+        for invalid in ["x=1", "x=1,y=2,", "1", "1,a", "no"] {
+            let result: Result<Point, _> = try_parse!(invalid, "{}");
+            assert!(result.is_err(), "{invalid:?} should not parse");
+        }
+        assert!(try_parse!("no", "{}").map(|y: Yes| y).is_err());
+    }
+
+    #[derive(Parse, Debug, PartialEq)]
+    #[prse("{}°C")]
+    struct Celsius(f64);
+
+    #[derive(Parse, Debug, PartialEq)]
+    #[prse("ACK")]
+    struct Ack;
+
+    #[derive(Parse, Debug, PartialEq)]
+    #[prse("{1} <- {0}")]
+    struct Edge(u8, char);
+
+    #[test]
+    fn parse_struct_shapes() {
+        assert_eq!(Celsius(20.0), parse!("20°C", "{}"));
+        assert_eq!(Ack, parse!("ACK", "{}"));
+        assert!(try_parse!("NAK", "{}").map(|a: Ack| a).is_err());
+        assert_eq!(Edge(4, 'a'), parse!("a <- 4", "{}"));
+
+        let temps: [Celsius; 2] = parse!("-1.5°C 3°C", "{: :2}");
+        assert_eq!(temps, [Celsius(-1.5), Celsius(3.0)]);
+    }
+
+    #[test]
+    fn parse_spanned() {
+        let n: Spanned<u32> = parse!("x = \t42  ", "x ={}");
+        assert_eq!(n.value, 42);
+        assert_eq!(n.leading, " \t");
+        assert_eq!(n.trailing, "  ");
+
+        let n: Spanned<u32> = parse!("42", "{}");
+        assert_eq!((n.value, n.leading, n.trailing), (42, "", ""));
+
+        let s: Spanned<&str> = parse!("   ", "{}");
+        assert_eq!((s.value, s.leading, s.trailing), ("", "   ", ""));
+
+        let [a, b]: [Spanned<i32>; 2] = parse!(" 1,  -2 ", "{:,:2}");
+        assert_eq!((*a, a.leading, a.trailing), (1, " ", ""));
+        assert_eq!((*b, b.leading, b.trailing), (-2, "  ", " "));
+    }
+
+    #[derive(Parse, Debug, PartialEq)]
+    #[prse = "pid={pid} mem={mem} mask={mask:,:2}"]
+    struct Process {
+        #[prse(radix = 16)]
+        pid: u32,
+        mem: u32,
+        #[prse(radix = 2)]
+        mask: [u8; 2],
+    }
+
+    #[derive(Parse, Debug, PartialEq)]
+    #[prse = "{2}:{0}:{1}"]
+    struct Mixed(#[prse(radix = 16)] u8, u16, #[prse(radix = 8)] u16);
+
+    #[derive(Parse, Debug, PartialEq)]
+    enum Register {
+        #[prse = "r{} = {}"]
+        Set(u8, #[prse(radix = 16)] u32),
+        #[prse = "clear {reg}"]
+        Clear {
+            #[prse(radix = 16)]
+            reg: u8,
+        },
+    }
+
+    #[test]
+    fn derive_field_radix() {
+        let process: Process = parse!("pid=0x1f mem=1024 mask=101,0b11", "{}");
+        assert_eq!(
+            process,
+            Process {
+                pid: 0x1f,
+                mem: 1024,
+                mask: [0b101, 0b11]
+            }
+        );
+        let mixed: Mixed = parse!("17:ff:10", "{}");
+        assert_eq!(mixed, Mixed(0xff, 10, 0o17));
+        let register: Register = parse!("r3 = beef", "{}");
+        assert_eq!(register, Register::Set(3, 0xbeef));
+        let register: Register = parse!("clear 1a", "{}");
+        assert_eq!(register, Register::Clear { reg: 0x1a });
+
+        assert!(try_parse!("pid=1024 mem=0x1f mask=1,1", "{}")
+            .map(|_: Process| ())
+            .is_err());
+    }
+
+    #[test]
+    fn parse_radix() {
+        use core::num::IntErrorKind;
+
+        let n: (u32, u32, u32, u32) =
+            parse!("0xff 0o17 0b101 42", "{:auto} {:auto} {:auto} {:auto}");
+        assert_eq!(n, (255, 15, 5, 42));
+        let n: i64 = parse!("-0x8000000000000000", "{:auto}");
+        assert_eq!(n, i64::MIN);
+        let n: i8 = parse!("+0b1111111", "{:auto}");
+        assert_eq!(n, 127);
+        let n: i8 = parse!("-0x00080", "{:auto}");
+        assert_eq!(n, -128);
+
+        let (a, b, c, d): (u8, u16, i32, u8) = parse!("ff 0xBEEF -17 0b11", "{:x} {:x} {:o} {:b}");
+        assert_eq!((a, b, c, d), (0xff, 0xbeef, -0o17, 3));
+        let x: u8;
+        parse!("x=0o177", "x={x:o }");
+        assert_eq!(x, 0o177);
+
+        let n: Result<u32, _> = try_parse!("0b102", "{:auto}");
+        assert_eq!(
+            n.unwrap_err().int_error_kind(),
+            Some(&IntErrorKind::InvalidDigit)
+        );
+        let n: Result<u32, _> = try_parse!("0x", "{:auto}");
+        assert_eq!(n.unwrap_err().int_error_kind(), Some(&IntErrorKind::Empty));
+        let n: Result<i32, _> = try_parse!("0x-1", "{:auto}");
+        assert_eq!(
+            n.unwrap_err().int_error_kind(),
+            Some(&IntErrorKind::InvalidDigit)
+        );
+        let n: Result<u32, _> = try_parse!("-0x1", "{:x}");
+        assert_eq!(
+            n.unwrap_err().int_error_kind(),
+            Some(&IntErrorKind::InvalidDigit)
+        );
+        let n: Result<i8, _> = try_parse!("-0b10000001", "{:b}");
+        assert_eq!(
+            n.unwrap_err().int_error_kind(),
+            Some(&IntErrorKind::NegOverflow)
+        );
+        let n: Result<i8, _> = try_parse!("-0x1000z", "{:auto}");
+        assert_eq!(
+            n.unwrap_err().int_error_kind(),
+            Some(&IntErrorKind::InvalidDigit)
+        );
+        let n: Result<u8, _> = try_parse!("1000z", "{:x}");
+        assert_eq!(
+            n.unwrap_err().int_error_kind(),
+            Some(&IntErrorKind::InvalidDigit)
+        );
+        // The prefix must match the radix, otherwise it is parsed as digits.
+        let n: Result<u32, _> = try_parse!("0b11", "{:o}");
+        assert!(n.is_err());
+        let n: u32 = parse!("0b11", "{:x}");
+        assert_eq!(n, 0xb11);
+    }
+
+    struct Percent(u8);
+
+    impl<'a> Parse<'a> for Percent {
+        fn from_str(s: &'a str) -> Result<Self, prse::ParseError> {
+            let n: u32 = s.strip_suffix('%').unwrap_or(s).parse()?;
+            Ok(Percent(n.try_into()?))
+        }
+    }
+
+    struct TwoBytes<'a>(&'a str);
+
+    impl<'a> Parse<'a> for TwoBytes<'a> {
+        fn from_str(s: &'a str) -> Result<Self, prse::ParseError> {
+            let bytes = s.as_bytes();
+            Ok(TwoBytes(core::str::from_utf8(
+                &bytes[..bytes.len().min(2)],
+            )?))
+        }
+    }
+
+    #[test]
+    fn conversion_errors() {
+        let p: Percent = parse!("50%", "{}");
+        assert_eq!(p.0, 50);
+        assert!(matches!(
+            Percent::from_str("256%"),
+            Err(prse::ParseError::TryFromInt(_))
+        ));
+
+        let a: TwoBytes = parse!("abc", "{}");
+        assert_eq!(a.0, "ab");
+        assert!(matches!(
+            TwoBytes::from_str("aé"),
+            Err(prse::ParseError::Utf8(_))
+        ));
+    }
+
+    #[test]
+    fn parse_trimmed_columns() {
+        let row = "  Alice|   42|ok    |";
+        let (name, age, status): (&str, u32, &str) = parse!(row, "{:ltrim}|{:trim}|{:rtrim}|");
+        assert_eq!((name, age, status), ("Alice", 42, "ok"));
+
+        // The trimmed captures are sub-slices of the input.
+        let start = row.as_ptr() as usize;
+        assert_eq!(name.as_ptr() as usize - start, 2);
+        assert_eq!(status.as_ptr() as usize - start, 14);
+
+        // Only the requested side is trimmed.
+        let (a, b): (&str, &str) = parse!(" a | b ", "{:rtrim}|{:ltrim}");
+        assert_eq!((a, b), (" a", "b "));
+
+        let n: u8 = parse!("[ 0x1f ]", "[{:x, trim}]");
+        assert_eq!(n, 0x1f);
+        let n: Result<u8, _> = try_parse!("[ 0x1f ]", "[{:x}]");
+        assert!(n.is_err());
+    }
+
+    parse_fn!(parse_point, "({}, {})", (i32, i32));
+    parse_fn!(pub(crate) parse_entry, "{1}: {0:x}", (u16, &'a str));
+
+    #[test]
+    fn generated_parse_fn() {
+        assert_eq!(parse_point("(1, -2)"), Ok((1, -2)));
+        assert!(parse_point("(1, -2").is_err());
+        assert!(parse_point("(1, x)").is_err());
+
+        let mut points = ["(0, 0)", "(3, 4)"].into_iter().map(parse_point);
+        assert_eq!(points.next_back(), Some(Ok((3, 4))));
+
+        let line = "id: ff";
+        let entry = parse_entry(line).unwrap();
+        assert_eq!(entry, (0xff, "id"));
+    }
+
+    #[derive(Debug, PartialEq, Parse)]
+    #[prse(from_repr)]
+    #[repr(i8)]
+    enum Status {
+        Idle = 0,
+        Running = 1,
+        Failed = -4,
+        Restarting,
+    }
+
+    #[test]
+    fn parse_from_repr() {
+        let status: Status = parse!("status=1", "status={}");
+        assert_eq!(status, Status::Running);
+        let status: Status = parse!("status=-4", "status={}");
+        assert_eq!(status, Status::Failed);
+        let status: Status = parse!("status=-3", "status={}");
+        assert_eq!(status, Status::Restarting);
+        let status: Status = parse!(" 0 ", "{}");
+        assert_eq!(status, Status::Idle);
+
+        let unknown: Result<Status, _> = try_parse!("status=2", "status={}");
+        assert!(unknown.is_err());
+        assert!(matches!(
+            Status::from_str("2"),
+            Err(prse::ParseError::Other { .. })
+        ));
+        let out_of_range: Result<Status, _> = try_parse!("status=200", "status={}");
+        assert!(out_of_range.unwrap_err().int_error_kind().is_some());
+    }
+
+    #[derive(Debug, PartialEq, Parse)]
+    #[prse(range)]
+    #[repr(i16)]
+    enum Reading {
+        #[prse(range = "..-40")]
+        TooCold,
+        #[prse(range = "-40..0")]
+        Freezing,
+        #[prse(range = "0..=25")]
+        Mild,
+        #[prse(range = "20..50")]
+        Hot,
+    }
+
+    #[test]
+    fn parse_range() {
+        let reading: Reading = parse!("t=-100", "t={}");
+        assert_eq!(reading, Reading::TooCold);
+        let reading: Reading = parse!("t=-40", "t={}");
+        assert_eq!(reading, Reading::Freezing);
+        let reading: Reading = parse!("t=25", "t={}");
+        assert_eq!(reading, Reading::Mild);
+        let reading: Reading = parse!("t=26", "t={}");
+        assert_eq!(reading, Reading::Hot);
+        let reading: Reading = parse!("t=49", "t={}");
+        assert_eq!(reading, Reading::Hot);
+
+        let out_of_range: Result<Reading, _> = try_parse!("t=50", "t={}");
+        assert!(matches!(
+            out_of_range.unwrap_err().root_cause(),
+            prse::ParseError::Other { .. }
+        ));
+        assert!(Reading::from_str("t").is_err());
+    }
+
+    #[derive(Debug, PartialEq, Parse)]
+    #[prse(flags)]
+    struct Options {
+        verbose: bool,
+        debug: bool,
+        r#static: bool,
+    }
+
+    #[derive(Debug, PartialEq, Parse)]
+    #[prse(flags(ignore_unknown))]
+    struct LenientOptions {
+        verbose: bool,
+        debug: bool,
+    }
+
+    #[test]
+    fn parse_flags() {
+        let options: Options = parse!("[static  verbose debug]", "[{}]");
+        assert_eq!(
+            options,
+            Options {
+                verbose: true,
+                debug: true,
+                r#static: true
+            }
+        );
+        let options: Options = parse!("[]", "[{}]");
+        assert_eq!(
+            options,
+            Options {
+                verbose: false,
+                debug: false,
+                r#static: false
+            }
+        );
+        let options: Options = parse!("debug debug", "{}");
+        assert!(options.debug && !options.verbose);
+
+        let unknown: Result<Options, _> = try_parse!("verbose color", "{}");
+        assert!(unknown.is_err());
+        assert!(matches!(
+            Options::from_str("verbose color"),
+            Err(prse::ParseError::Other { .. })
+        ));
+        let lenient: LenientOptions = parse!("verbose color", "{}");
+        assert_eq!(
+            lenient,
+            LenientOptions {
+                verbose: true,
+                debug: false
+            }
+        );
+    }
+
+    #[derive(Debug, PartialEq, Parse)]
+    enum SpeedUnit {
+        #[prse = "mph"]
+        Mph,
+        #[prse = "kph"]
+        Kph,
+        #[prse = "m/s"]
+        MetresPerSecond,
+    }
+
+    #[test]
+    fn parse_with_unit() {
+        let speed: WithUnit<u32, SpeedUnit> = parse!("speed=30mph", "speed={}");
+        assert_eq!(
+            speed,
+            WithUnit {
+                value: 30,
+                unit: SpeedUnit::Mph
+            }
+        );
+        let speed: WithUnit<u32, SpeedUnit> = parse!("speed=50kph", "speed={}");
+        assert_eq!(
+            speed,
+            WithUnit {
+                value: 50,
+                unit: SpeedUnit::Kph
+            }
+        );
+        let speed: WithUnit<f64, SpeedUnit> = parse!("speed= -2.5 m/s", "speed={}");
+        assert_eq!(
+            speed,
+            WithUnit {
+                value: -2.5,
+                unit: SpeedUnit::MetresPerSecond
+            }
+        );
+
+        let unknown: Result<WithUnit<u32, SpeedUnit>, _> = try_parse!("speed=30knots", "speed={}");
+        assert!(unknown.is_err());
+        let missing: Result<WithUnit<u32, SpeedUnit>, _> = try_parse!("speed=30", "speed={}");
+        assert!(missing.is_err());
+        let no_number: Result<WithUnit<u32, SpeedUnit>, _> = try_parse!("speed=mph", "speed={}");
+        assert!(no_number.unwrap_err().int_error_kind().is_some());
+    }
+
+    #[test]
+    fn repetition_before_literal() {
+        let (items, rest): ([u8; 2], &str) = parse!("1,2;rest", "{:,:2};{}");
+        assert_eq!(items, [1, 2]);
+        assert_eq!(rest, "rest");
+
+        let (mut items, rest): (ParseIter<u8>, &str) = parse!(";rest", "{:,:0};{}");
+        assert!(items.next().is_none());
+        assert_eq!(rest, "rest");
+
+        let (mut items, rest): (ParseIter<u8>, &str) = parse!("4;", "{:,:0};{}");
+        assert_eq!(items.next(), Some(Ok(4)));
+        assert!(items.next().is_none());
+        assert_eq!(rest, "");
+    }
+
+    #[test]
+    fn windows_line_endings() {
+        let input = "name: alice\r\nname: bob\r\nname: carol\n";
+
+        for (line, expected) in input.lines().zip(["alice", "bob", "carol"]) {
+            let name: &str = parse!(line, "name: {}");
+            assert_eq!(name, expected);
+        }
+
+        let mut lines: ParseIter<&str> = parse!(input, "{:\n:0}");
+        assert_eq!(lines.next(), Some(Ok("name: alice")));
+        assert_eq!(lines.next(), Some(Ok("name: bob")));
+        assert_eq!(lines.next(), Some(Ok("name: carol")));
+        assert_eq!(lines.next(), None);
+
+        let (first, second, rest): (&str, &str, &str) = parse!(input, "name: {}\nname: {}\n{}");
+        assert_eq!((first, second, rest), ("alice", "bob", "name: carol\n"));
+
+        let lines: [&str; 3] = parse!("a\r\n\r\nb\nc\r", "{:\n:!3}");
+        assert_eq!(lines, ["a", "b", "c"]);
+        let lines: [&str; 2] = parse!("a\r\nb\r\n\r", "{:\n:!2}");
+        assert_eq!(lines, ["a", "b"]);
+    }
+
+    #[test]
+    fn repetition_element_modifiers() {
+        let bytes: [u8; 3] = parse!(" 0x1 , 0x2 ,0x3", "{:,:3:x,trim}");
+        assert_eq!(bytes, [1, 2, 3]);
+
+        let masks: [u8; 3] = parse!("0b1, 0b10,  0b100", "{:, :!3:b,ltrim}");
+        assert_eq!(masks, [1, 2, 4]);
+
+        let digits: [u8; 4] = parse!("c0fe", "{::4:x}");
+        assert_eq!(digits, [0xc, 0, 0xf, 0xe]);
+        let digits: Result<[u8; 2], _> = try_parse!("7g", "{::2:x}");
+        assert!(digits.is_err());
+
+        let [a, b]: [&str; 2] = parse!(" a | b ", "(?t){:|:2}");
+        assert_eq!((a, b), ("a", "b"));
+
+        let untrimmed: Result<[u8; 2], _> = try_parse!("0x1, 0x2", "{:,:2:x}");
+        assert!(untrimmed.is_err());
+    }
+
+    #[test]
+    fn parse_signed_duration() {
+        use core::time::Duration;
+
+        let offsets: [SignedDuration; 3] = parse!("-30s, +1h, 0s", "{:, :3}");
+        assert_eq!(
+            offsets,
+            [
+                SignedDuration(-30_000_000_000),
+                SignedDuration(3_600_000_000_000),
+                SignedDuration(0)
+            ]
+        );
+        assert!(offsets[0].is_negative() && !offsets[2].is_negative());
+        assert_eq!(offsets[0].unsigned_abs(), Duration::from_secs(30));
+        let [ns, us, ms, d]: [SignedDuration; 4] = parse!("7ns 2µs -5ms 1d", "{: :4}");
+        assert_eq!(
+            [ns.0, us.0, ms.0, d.0],
+            [7, 2_000, -5_000_000, 86_400_000_000_000]
+        );
+
+        let max: SignedDuration = parse!("9223372036854775807ns", "{}");
+        assert_eq!(max, SignedDuration(i64::MAX));
+        let min: SignedDuration = parse!("-9223372036854775808ns", "{}");
+        assert_eq!(min.unsigned_abs(), Duration::from_nanos(1 << 63));
+        for invalid in [
+            "",
+            "30",
+            "-",
+            "30x",
+            "1.5s",
+            "--1s",
+            "s",
+            "9223372036854775808ns",
+            "107000d",
+        ] {
+            let result: Result<SignedDuration, _> = try_parse!(invalid, "{}");
+            assert!(result.is_err(), "{invalid:?} should not parse");
+        }
+    }
+
+    #[test]
+    fn parse_sparse_array() {
+        type Sparse<T> = Missing<T, Blank>;
+        let row: [Sparse<i32>; 4] = parse!("1,2,3,4", "{:,:4:trailing}");
+        assert_eq!(
+            row.map(Sparse::into_inner),
+            [Some(1), Some(2), Some(3), Some(4)]
+        );
+        let row: [Sparse<i32>; 4] = parse!(",,,", "{:,:4:trailing}");
+        assert_eq!(row.map(Sparse::into_inner), [None; 4]);
+        let row: [Sparse<i32>; 4] = parse!("1,, 3 ,", "{:,:4:trailing}");
+        assert_eq!(row.map(Sparse::into_inner), [Some(1), None, Some(3), None]);
+        let (id, row): (u8, [Sparse<&str>; 3]) = parse!("7: a;;c", "{}: {:;:3:trailing}");
+        assert_eq!(
+            (id, row.map(Sparse::into_inner)),
+            (7, [Some("a"), None, Some("c")])
+        );
+
+        let short: Result<[Sparse<i32>; 4], _> = try_parse!("1,,3", "{:,:4:trailing}");
+        assert!(matches!(
+            short,
+            Err(prse::ParseError::Array {
+                expected: 4,
+                found: 3
+            })
+        ));
+        let long: Result<[Sparse<i32>; 4], _> = try_parse!("1,,3,,", "{:,:4:trailing}");
+        assert!(matches!(long, Err(prse::ParseError::Array { .. })));
+        let invalid: Result<[Sparse<i32>; 2], _> = try_parse!("1,x", "{:,:2:trailing}");
+        assert!(invalid.is_err());
+
+        let mut elements: ParseIter<Sparse<u8>> = parse!("1,", "{:,:0:trailing}");
+        assert_eq!(elements.next(), Some(Ok(Missing::new(Some(1)))));
+        assert_eq!(elements.size_hint(), (0, Some(1)));
+        assert_eq!(elements.next(), Some(Ok(Missing::new(None))));
+        assert_eq!(elements.size_hint(), (0, Some(0)));
+        assert_eq!(elements.next(), None);
+
+        // Without the modifier a separator at the end only ends the last element.
+        let row: [u32; 3] = parse!("1,2,3,", "{:,:3}");
+        assert_eq!(row, [1, 2, 3]);
+        let mut elements: ParseIter<u8> = parse!("1,", "{:,:0}");
+        assert_eq!(elements.next(), Some(Ok(1)));
+        assert_eq!(elements.size_hint(), (0, Some(0)));
+        assert_eq!(elements.next(), None);
+    }
+
+    #[test]
+    fn parse_stepped_range() {
+        let axis: SteppedRange<u32> = parse!("axis 0..10..2", "axis {}");
+        assert_eq!(
+            axis,
+            SteppedRange {
+                start: 0,
+                end: 10,
+                step: 2
+            }
+        );
+        let down: SteppedRange<i8> = parse!("5..-5..-1", "{}");
+        assert_eq!((down.start, down.end, down.step), (5, -5, -1));
+        let empty: SteppedRange<i8> = parse!("3..3..-1", "{}");
+        assert_eq!(empty.step, -1);
+        let fine: SteppedRange<f32> = parse!("0..1..0.25", "{}");
+        assert_eq!(fine.step, 0.25);
+
+        let zero: Result<SteppedRange<i32>, _> = try_parse!("0..10..0", "{}");
+        assert!(zero.is_err());
+        let backwards: Result<SteppedRange<i32>, _> = try_parse!("10..0..2", "{}");
+        assert!(backwards.is_err());
+        let nan: Result<SteppedRange<f32>, _> = try_parse!("0..1..NaN", "{}");
+        assert!(nan.is_err());
+        let short: Result<SteppedRange<i32>, _> = try_parse!("0..10", "{}");
+        assert!(short.is_err());
+        let long: Result<SteppedRange<i32>, _> = try_parse!("0..10..2..1", "{}");
+        assert!(long.is_err());
+    }
+
+    #[test]
+    fn parse_grouped() {
+        let [a, b, c]: [Grouped<f64>; 3] = parse!("1,234.56 999 -12,345,678.9", "{: :3}");
+        assert_eq!([a.0, b.0, c.0], [1234.56, 999.0, -12_345_678.9]);
+        let [a, b]: [Grouped<f64, '.', ','>; 2] = parse!("1.234,56; +7,5", "{:; :2}");
+        assert_eq!([a.0, b.0], [1234.56, 7.5]);
+        let [a, b]: [Grouped<i64, ' '>; 2] = parse!("1 000 000|-42", "{:|:2}");
+        assert_eq!([a.0, b.0], [1_000_000, -42]);
+        let ungrouped: Grouped<u32> = parse!(" 1234567 ", "{}");
+        assert_eq!(ungrouped.0, 1_234_567);
+
+        for invalid in [
+            "1,23,456",
+            "1234,567",
+            ",123",
+            "123,",
+            "1,,234",
+            "1,234.567,8",
+            "1.234,56",
+            "1,2a4",
+            "",
+        ] {
+            let result: Result<Grouped<f64>, _> = try_parse!(invalid, "{}");
+            assert!(result.is_err(), "{invalid:?} should not parse");
+        }
+        let european: Result<Grouped<f64, '.', ','>, _> = try_parse!("1,234.56", "{}");
+        assert!(european.is_err());
+        let fraction: Result<Grouped<u32>, _> = try_parse!("1,234.5", "{}");
+        assert!(fraction.is_err());
+    }
+
+    #[test]
+    fn parse_hms() {
+        use core::time::Duration;
+
+        let [lap, elapsed]: [Hms; 2] = parse!("02:03, 01:30:45", "{:, :2}");
+        assert_eq!(lap.0, Duration::from_secs(2 * 60 + 3));
+        assert_eq!(Duration::from(elapsed), Duration::from_secs(5445));
+        let long: Hms = parse!("100:00:00", "{}");
+        assert_eq!(long.0, Duration::from_secs(100 * 60 * 60));
+        let [half, precise]: [Hms; 2] = parse!("00:00:01.500 00:00.000000001", "{: :2}");
+        assert_eq!(half.0, Duration::from_millis(1500));
+        assert_eq!(precise.0, Duration::from_nanos(1));
+
+        for invalid in [
+            "",
+            "12",
+            "1:2:3:4",
+            "01:60",
+            "00:01:60",
+            "-01:00",
+            "01::00",
+            "01:00.",
+            "01:00.1234567890",
+            "01:0a",
+            "99999999999999999999:00:00",
+        ] {
+            let result: Result<Hms, _> = try_parse!(invalid, "{}");
+            assert!(result.is_err(), "{invalid:?} should not parse");
+        }
+    }
+
+    #[test]
+    fn parse_shape() {
+        let (code, sum): (&str, u32) = parse!("AB-12 12", r"{:~\w+-\d+} {:~\d+}");
+        assert_eq!((code, sum), ("AB-12", 12));
+        let [a, b]: [&str; 2] = parse!("aaab, b", r"{:, :2:~a*b}");
+        assert_eq!([a, b], ["aaab", "b"]);
+        let padded: u32 = parse!("[  7 ]", r"[{:trim,~\d}]");
+        assert_eq!(padded, 7);
+        let dotted: &str = parse!("1.2.3", r"{:~\d+\.\d+.\d}");
+        assert_eq!(dotted, "1.2.3");
+        let backtracked: &str = parse!("a-b-c", r"{:~.*-c}");
+        assert_eq!(backtracked, "a-b-c");
+        let commas: &str = parse!("1,2", r"{:~\d,\d}");
+        assert_eq!(commas, "1,2");
+
+        let underscore: &str = parse!("A_B-1", r"{:~\w+-\d+}");
+        assert_eq!(underscore, "A_B-1");
+
+        for invalid in ["", "12-AB", "AB-", "-12", "AB-12x", "A B-12"] {
+            let result: Result<&str, _> = try_parse!(invalid, r"{:~\w+-\d+}");
+            assert!(result.is_err(), "{invalid:?} should not match");
+        }
+        let unmatched: Result<[u32; 2], _> = try_parse!("1 22", r"{: :2:~\d}");
+        assert!(unmatched.is_err());
+    }
+
+    #[test]
+    fn parse_fraction() {
+        use core::num::IntErrorKind;
+
+        let fraction = |numerator, denominator| Fraction {
+            numerator,
+            denominator,
+        };
+        let cases = [
+            ("3/4", fraction(3, 4)),
+            (" 1 / 2 ", fraction(1, 2)),
+            ("6/8", fraction(3, 4)),
+            ("3 1/4", fraction(13, 4)),
+            ("-3 1/4", fraction(-13, 4)),
+            ("+1\t2/3", fraction(5, 3)),
+            ("-2/4", fraction(-1, 2)),
+            ("0/5", fraction(0, 1)),
+            ("-0", fraction(0, 1)),
+            ("7", fraction(7, 1)),
+            ("1 5/4", fraction(9, 4)),
+        ];
+        for (input, expected) in cases {
+            let parsed: Fraction = parse!(input, "{}");
+            assert_eq!(parsed, expected, "{input:?}");
+        }
+        let (sugar, flour): (Fraction, Fraction) = parse!("1/2 cup, 2 3/4 cups", "{} cup, {} cups");
+        assert_eq!(sugar.to_f64(), 0.5);
+        assert_eq!(f64::from(flour), 2.75);
+        assert_eq!(flour.trunc(), 2);
+        assert_eq!(fraction(-13, 4).trunc(), -3);
+        assert_eq!(Fraction::default(), fraction(0, 1));
+
+        let min: Fraction = parse!("-9223372036854775808", "{}");
+        assert_eq!(min, fraction(i64::MIN, 1));
+        let large: Fraction = parse!("18446744073709551614/2", "{}");
+        assert_eq!(large, fraction(i64::MAX, 1));
+
+        for input in [
+            "1/0",
+            "3 1/0",
+            "0/0",
+            "9223372036854775808",
+            "1 1/18446744073709551615",
+        ] {
+            let error: Result<Fraction, _> = try_parse!(input, "{}");
+            let error = error.unwrap_err();
+            assert!(
+                matches!(error.root_cause(), prse::ParseError::Other { .. }),
+                "{input:?}"
+            );
+        }
+        for input in [
+            "", "1/", "/2", "1/-2", "3 -1/4", "3 +1/4", "1.5/2", "a/b", "1 2", "1/2/3",
+        ] {
+            let error: Result<Fraction, _> = try_parse!(input, "{}");
+            let error = error.unwrap_err();
+            assert!(
+                matches!(error.root_cause(), prse::ParseError::Int(_)),
+                "{input:?}"
+            );
+        }
+        let overflow: Result<Fraction, _> = try_parse!("18446744073709551616/2", "{}");
+        assert_eq!(
+            overflow.unwrap_err().int_error_kind(),
+            Some(&IntErrorKind::PosOverflow)
+        );
+        let signed: Result<Fraction, _> = try_parse!("3 +1/4", "{}");
+        assert_eq!(
+            signed.unwrap_err().int_error_kind(),
+            Some(&IntErrorKind::InvalidDigit)
+        );
+    }
+
+    struct Area;
+
+    impl Units for Area {
+        const DIMENSION: &'static str = "area";
+        const UNITS: &'static [(&'static str, f64)] = &[("cm2", 1e-4), ("m2", 1.0), ("ha", 1e4)];
+    }
+
+    #[test]
+    fn parse_quantity() {
+        let lengths = [
+            ("5cm", 0.05),
+            ("2m", 2.0),
+            (" 2 m ", 2.0),
+            ("1.5km", 1500.0),
+            ("-3mm", -0.003),
+            ("10 in", 0.254),
+            ("250µm", 0.00025),
+        ];
+        for (input, meters) in lengths {
+            let length: Quantity<Length> = parse!(input, "{}");
+            assert!((length.value() - meters).abs() < 1e-12, "{input:?}");
+        }
+        let (length, mass, time): (Quantity<Length>, Quantity<Mass>, Quantity<Time>) =
+            parse!("length=5cm mass=500g time=2h", "length={} mass={} time={}");
+        assert_eq!((*length, *mass, *time), (0.05, 0.5, 7200.0));
+        let area: Quantity<Area> = parse!("3 ha", "{}");
+        assert_eq!(area.value(), 30000.0);
+
+        let unknown: Result<Quantity<Length>, _> = try_parse!("5 parsecs", "{}");
+        assert!(matches!(
+            unknown.unwrap_err().root_cause(),
+            prse::ParseError::Other { .. }
+        ));
+    }
+
+    #[test]
+    fn parse_fixed() {
+        use core::num::IntErrorKind;
+
+        let cents: Fixed<2> = parse!("12.34", "{}");
+        assert_eq!(cents, Fixed(1234));
+        let [a, b, c, d]: [Fixed<2>; 4] = parse!("-12.34 +5 .5 -0.07", "{: :4}");
+        assert_eq!([a.0, b.0, c.0, d.0], [-1234, 500, 50, -7]);
+        let zero: Fixed<4> = parse!("-000.0", "{}");
+        assert_eq!(zero, Fixed(0));
+        let whole: Fixed<0> = parse!("42", "{}");
+        assert_eq!(whole, Fixed(42));
+
+        // Extra digits are rejected instead of being rounded or truncated.
+        let too_precise: Result<Fixed<2>, _> = try_parse!("1.999", "{}");
+        assert!(too_precise.is_err());
+        let too_precise: Result<Fixed<0>, _> = try_parse!("1.0", "{}");
+        assert!(too_precise.is_err());
+
+        let max: Fixed<2> = parse!("92233720368547758.07", "{}");
+        assert_eq!(max, Fixed(i64::MAX));
+        let min: Fixed<2> = parse!("-92233720368547758.08", "{}");
+        assert_eq!(min, Fixed(i64::MIN));
+        let overflow: Result<Fixed<2>, _> = try_parse!("92233720368547758.08", "{}");
+        assert_eq!(
+            overflow.unwrap_err().int_error_kind(),
+            Some(&IntErrorKind::PosOverflow)
+        );
+        let overflow: Result<Fixed<18>, _> = try_parse!("-100000000000000000000000", "{}");
+        assert_eq!(
+            overflow.unwrap_err().int_error_kind(),
+            Some(&IntErrorKind::NegOverflow)
+        );
+
+        for invalid in ["", ".", "-", "1..2", "1.-2", "1e3", "--1", "0x10"] {
+            let result: Result<Fixed<2>, _> = try_parse!(invalid, "{}");
+            assert!(result.is_err(), "{invalid:?} should not parse");
+        }
+    }
+
+    #[test]
+    fn pattern_flags() {
+        let (a, b): (u32, u32) = parse!("From 1 TO 2", "(?i)from {} to {}");
+        assert_eq!((a, b), (1, 2));
+        let unit: Result<(), _> = try_parse!("From", "from");
+        assert!(unit.is_err());
+
+        let (a, b): (&str, &str) = parse!("x \t=\n y", "(?w){} = {}");
+        assert_eq!((a, b), ("x", "y"));
+        let no_space: Result<(&str, &str), _> = try_parse!("x=y", "(?w){} = {}");
+        assert!(no_space.is_err());
+
+        let (a, b, c): (&str, &str, &str) = parse!("| a | b |c |", "(?t)|{}|{:rtrim}|{}|");
+        assert_eq!((a, b, c), ("a", " b", "c"));
+
+        let n: u8 = parse_prefix!("LEN= 0x1F; ...", "(?it)len={:x};");
+        assert_eq!(n, 31);
+
+        let days: u16 = parse!("ÉTÉ: 93", "(?i)été: {}");
+        assert_eq!(days, 93);
+
+        // An empty flag cluster lets the pattern start with a literal (?.
+        let n: u32 = parse!("(?x) 5", "(?)(?x) {}");
+        assert_eq!(n, 5);
+        let n: u32 = parse!("(?x) 5", "(?t)(?x){}");
+        assert_eq!(n, 5);
+
+        // Back-references compare the trimmed captures.
+        let tag: &str;
+        let values: (u32, u32) = parse!("<b >1;< b>2", "(?t)<{tag}>{};<{tag}>{}");
+        assert_eq!((tag, values), ("b", (1, 2)));
+        let mut tag: &str = "";
+        let mismatch: Result<(u32, u32), _> = try_parse!("<b>1;<i>2", "(?t)<{tag}>{};<{tag}>{}");
+        assert!(mismatch.is_err());
+        assert_eq!(tag, "");
+    }
+
+    #[test]
+    fn whitespace_columns() {
+        let (user, pid, cpu, command): (&str, u32, f32, &str) =
+            parse!("root   1234   0.5   /sbin/init", "(?c){} {} {} {}");
+        assert_eq!((user, pid, cpu, command), ("root", 1234, 0.5, "/sbin/init"));
+        let (user, pid): (&str, u32) = parse!("  \tdaemon\t\t 1 \n", "(?c){} {}");
+        assert_eq!((user, pid), ("daemon", 1));
+        let (user, pid): (&str, u32) = parse!("www 80", "(?c){} {}");
+        assert_eq!((user, pid), ("www", 80));
+
+        let (name, size): (&str, u64) = parse!("  /dev/sda1   4096K  ", "(?c){} {}K");
+        assert_eq!((name, size), ("/dev/sda1", 4096));
+        let [a, b]: [u8; 2] = parse!(" 1,2   ", "(?c){:,:2}");
+        assert_eq!([a, b], [1, 2]);
+        let mut cursor = "  7  next";
+        let x: u8 = parse_advance!(&mut cursor, "(?c){} ");
+        assert_eq!((x, cursor), (7, "next"));
+
+        let missing: Result<(&str, u32), _> = try_parse!("  root  ", "(?c){} {}");
+        assert!(missing.is_err());
+    }
+
+    #[derive(Parse, Debug, PartialEq)]
+    #[prse = "(?r){stem}.{ext}"]
+    struct FileName<'a> {
+        stem: &'a str,
+        ext: &'a str,
+    }
+
+    #[test]
+    fn right_greedy_captures() {
+        let (stem, ext): (&str, &str) = parse!("report.2021.final.pdf", "(?r){}.{}");
+        assert_eq!((stem, ext), ("report.2021.final", "pdf"));
+        let (stem, ext): (&str, &str) = parse!("notes.txt", "(?r){}.{}");
+        assert_eq!((stem, ext), ("notes", "txt"));
+        let (stem, ext): (&str, &str) = parse!(".bashrc", "(?r){}.{}");
+        assert_eq!((stem, ext), ("", "bashrc"));
+        let (stem, ext): (&str, &str) = parse!("archive.tar.", "(?r){}.{}");
+        assert_eq!((stem, ext), ("archive.tar", ""));
+
+        let no_dot: Result<(&str, &str), _> = try_parse!("README", "(?r){}.{}");
+        assert!(no_dot.is_err());
+
+        assert_eq!(
+            FileName {
+                stem: "photo.2024.01",
+                ext: "jpg"
+            },
+            parse!("photo.2024.01.jpg", "{}")
+        );
+
+        let name: &str = parse!("a.tar.gz.TAR.GZ", "(?ri){}.tar.gz");
+        assert_eq!(name, "a.tar.gz");
+        let (head, tail): (&str, &str) = parse!("a b  c   d", "(?rw){} {}");
+        assert_eq!((head, tail), ("a b  c", "d"));
+        let (version, patch): ([u8; 3], u8) = parse!("1.2.3.4", "(?r){:.:3}.{}");
+        assert_eq!((version, patch), ([1, 2, 3], 4));
+    }
+
+    struct NoData;
+
+    impl Sentinels for NoData {
+        const SENTINELS: &'static [&'static str] = &["-999", "?"];
+    }
+
+    #[test]
+    fn parse_missing() {
+        let [a, b, c]: [Missing<f32>; 3] = parse!("NA, 2.5, .", "{:,:3}");
+        assert_eq!((*a, *b, *c), (None, Some(2.5), None));
+
+        let [a, b, c]: [Missing<i32, NoData>; 3] = parse!("-999 ? -998", "{: :3}");
+        assert_eq!(
+            [a.into_inner(), b.into_inner(), c.into_inner()],
+            [None, None, Some(-998)]
+        );
+
+        let invalid: Result<Missing<u32>, _> = try_parse!("seven", "{}");
+        assert!(invalid.is_err());
+        let not_a_sentinel: Result<Missing<u32, NoData>, _> = try_parse!("NA", "{}");
+        assert!(not_a_sentinel.is_err());
+    }
+
+    #[test]
+    fn parse_key_value() {
+        let kv: KeyValue<u32> = parse!("port=8080", "{}");
+        assert_eq!((kv.key, kv.value), ("port", 8080));
+
+        let [a, b]: [KeyValue<i8>; 2] = parse!("x = -1, y = 2", "{:, :2}");
+        assert_eq!((a.key, a.value, b.key, b.value), ("x", -1, "y", 2));
+
+        let kv: KeyValue<&str, ':'> = parse!("url: https://example.com", "{}");
+        assert_eq!((kv.key, kv.value), ("url", " https://example.com"));
+
+        let kv: KeyValue<&str> = parse!("a==b", "{}");
+        assert_eq!((kv.key, kv.value), ("a", "=b"));
+
+        let missing: Result<KeyValue<u32>, _> = try_parse!("port", "{}");
+        assert!(missing.is_err());
+        let invalid: Result<KeyValue<u32>, _> = try_parse!("port=http", "{}");
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn parse_percent() {
+        let [half, zero, over]: [prse::Percent; 3] = parse!("50%, 0%, 150 %", "{:, :3}");
+        assert_eq!((half.0, zero.0, over.0), (0.5, 0.0, 1.5));
+
+        let p: prse::Percent = parse!("opacity= -12.5% ", "opacity={}");
+        assert_eq!(*p, -0.125);
+        let p: prse::Percent<true> = parse!("75", "{}");
+        assert_eq!(*p, 0.75);
+        let p: prse::Percent<true> = parse!("75%", "{}");
+        assert_eq!(*p, 0.75);
+
+        let missing: Result<prse::Percent, _> = try_parse!("50", "{}");
+        assert!(missing.is_err());
+        let invalid: Result<prse::Percent, _> = try_parse!("half%", "{}");
+        assert!(invalid.is_err());
+        let invalid: Result<prse::Percent<true>, _> = try_parse!("%", "{}");
+        assert!(invalid.is_err());
+        let infinite: Result<prse::Percent, _> = try_parse!("inf%", "{}");
+        assert!(infinite.is_err());
+    }
+
+    #[test]
+    fn parse_ratio() {
+        let [up, down, zero]: [prse::Ratio; 3] = parse!("+2.5%, -50bps, 0%", "{:, :3}");
+        assert_eq!((up.0, down.0, zero.0), (0.025, -0.005, 0.0));
+        let r: prse::Ratio = parse!("spread = 12 bps ", "spread ={}");
+        assert_eq!(*r, 0.0012);
+
+        let bare: Result<prse::Ratio, _> = try_parse!("0.25", "{}");
+        assert!(bare.is_err());
+        let bare: prse::Ratio<true> = parse!("-0.25", "{}");
+        assert_eq!(*bare, -0.25);
+        let r: prse::Ratio<true> = parse!("25%", "{}");
+        assert_eq!(*r, 0.25);
+
+        assert!(matches!(
+            prse::Ratio::<true>::from_str("5bp"),
+            Err(prse::ParseError::Other { .. })
+        ));
+        let invalid: Result<prse::Ratio, _> = try_parse!("+%", "{}");
+        assert!(invalid.is_err());
+        let nan: Result<prse::Ratio, _> = try_parse!("NaN bps", "{}");
+        assert!(nan.is_err());
+    }
+
+    #[test]
+    fn parse_with_raw() {
+        let WithRaw(value, raw): WithRaw<f64> = parse!("2.5000", "{}");
+        assert_eq!((value, raw), (2.5, "2.5000"));
+
+        let [a, b, c]: [WithRaw<i32>; 3] = parse!("+1,-02,  3", "{:,:3}");
+        assert_eq!((a.0, a.1), (1, "+1"));
+        assert_eq!((b.0, b.1), (-2, "-02"));
+        assert_eq!((c.0, c.1), (3, "  3"));
+
+        let trimmed: WithRaw<u8> = parse!("( 0010 )", "({:trim})");
+        assert_eq!((*trimmed, trimmed.1), (10, "0010"));
+
+        let text: WithRaw<&str> = parse!("name: Bob ", "name: {}");
+        assert_eq!((text.0, text.1), ("Bob ", "Bob "));
+
+        let invalid: Result<WithRaw<u32>, _> = try_parse!("1.0", "{}");
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn int_error_kind() {
+        use core::num::IntErrorKind;
+
+        let n: Result<i8, _> = try_parse!("-129", "{}");
+        assert_eq!(
+            n.unwrap_err().int_error_kind(),
+            Some(&IntErrorKind::NegOverflow)
+        );
+        let n: Result<u16, _> = try_parse!("x=70000", "x={}");
+        assert_eq!(
+            n.unwrap_err().int_error_kind(),
+            Some(&IntErrorKind::PosOverflow)
+        );
+        let n: Result<u16, _> = try_parse!("x=7a", "x={}");
+        assert_eq!(
+            n.unwrap_err().int_error_kind(),
+            Some(&IntErrorKind::InvalidDigit)
+        );
+        let n: Result<u16, _> = try_parse!("x=", "x={}");
+        assert_eq!(n.unwrap_err().int_error_kind(), Some(&IntErrorKind::Empty));
+        let b: Result<bool, _> = try_parse!("yes", "{}");
+        assert_eq!(b.unwrap_err().int_error_kind(), None);
+    }
+
+    #[test]
+    fn error_codes() {
+        use prse::ParseError;
+
+        #[derive(Debug)]
+        struct Even(u32);
+
+        impl<'a> Parse<'a> for Even {
+            fn from_str(s: &'a str) -> Result<Self, ParseError> {
+                match s.parse::<u32>()? {
+                    n if n % 2 == 0 => Ok(Even(n)),
+                    n => Err(ParseError::with_code(7, format_args!("{n} is not even."))),
+                }
+            }
+        }
+
+        let even: Even = parse!("n=4", "n={}");
+        assert_eq!(even.0, 4);
+        let odd: Result<Even, _> = try_parse!("n=3", "n={}");
+        let odd = odd.unwrap_err();
+        assert_eq!(odd.error_code(), Some(7));
+        assert!(matches!(
+            odd.root_cause(),
+            ParseError::Coded { code: 7, .. }
+        ));
+        let odd: Result<[Even; 2], _> = try_parse!("2,3", "{:,:2}");
+        assert_eq!(odd.unwrap_err().error_code(), Some(7));
+
+        let uncoded: Result<Even, _> = try_parse!("n=x", "n={}");
+        let uncoded = uncoded.unwrap_err();
+        assert_eq!(uncoded.error_code(), None);
+        assert!(matches!(uncoded.root_cause(), ParseError::Int(_)));
+        assert_eq!(ParseError::with_code(3, "").error_code(), Some(3));
+    }
+
+    #[test]
+    fn text_before_leading_literal_is_skipped() {
+        let n: u32 = parse!("zzabc5", "abc{}");
+        assert_eq!(n, 5);
+        let n: u32 = parse!("abc5", "abc{}");
+        assert_eq!(n, 5);
+    }
+
+    #[test]
+    fn parse_case_insensitive() {
+        let token: CaseInsensitive = parse!("HELLO", "{}");
+        assert_eq!(token, CaseInsensitive("hello"));
+        assert_eq!(token, "hElLo");
+        assert_ne!(token, "hell");
+
+        let [a, b]: [CaseInsensitive; 2] = parse!("Straße STRAßE", "{: :2}");
+        assert_eq!(a, b);
+        assert_ne!(CaseInsensitive("STRASSE"), "straße");
+    }
+
+    #[test]
+    fn padded_captures() {
+        let row = " Alice , 42 ,  x ";
+        let (name, score, raw): (&str, &str, &str) = parse!(row, "{},{},{:notrim}");
+        assert_eq!((name, score, raw), (" Alice ", " 42 ", "  x "));
+
+        let (name, score, raw): (&str, u32, &str) = parse!(row, "(?t){},{},{:notrim}");
+        assert_eq!((name, score, raw), ("Alice", 42, "  x "));
+
+        // Trimmed captures are still borrowed from the input.
+        let start = row.as_ptr() as usize;
+        assert_eq!(name.as_ptr() as usize - start, 1);
+        assert_eq!(raw.as_ptr() as usize - start, 13);
+    }
+
+    #[test]
+    fn parse_grouped_radix() {
+        let n: u32 = parse!("0xFF_FF", "{:x,_}");
+        assert_eq!(n, 65535);
+        let n: u16 = parse!("FF_FF", "{:x,_}");
+        assert_eq!(n, 0xffff);
+        let n: i16 = parse!("-0b1111_0000", "{:_,b}");
+        assert_eq!(n, -0b1111_0000);
+        let n: u32 = parse!("0o7_7_7", "{:auto,_}");
+        assert_eq!(n, 0o777);
+        let n: u64 = parse!("1_000_000", "{:_}");
+        assert_eq!(n, 1_000_000);
+        let n: u8 = parse!("0x_ff", "{:x,_}");
+        assert_eq!(n, 0xff);
+        let [a, b]: [u16; 2] = parse!("1_0,0x2_0", "{:,:2:auto,_}");
+        assert_eq!((a, b), (10, 0x20));
+
+        for misplaced in ["_FF", "FF_", "F__F", "-_1"] {
+            let n: Result<u32, _> = try_parse!(misplaced, "{:x,_}");
+            assert!(n.is_err(), "{misplaced}");
+        }
+        let n: Result<u8, _> = try_parse!("0x1_00", "{:x,_}");
+        assert!(n.is_err());
+    }
+
+    struct Permissions;
+
+    impl FlagNames for Permissions {
+        const FLAGS: &'static [(&'static str, u64)] = &[("read", 4), ("write", 2), ("exec", 1)];
+    }
+
+    #[test]
+    fn parse_bit_flags() {
+        let perms: BitFlags<Permissions> = parse!("perms=read|write|exec", "perms={}");
+        assert_eq!(perms.bits(), 7);
+        let [a, b]: [BitFlags<Permissions>; 2] = parse!("exec, read | exec", "{:,:2}");
+        assert_eq!((a.bits(), b.bits()), (1, 5));
+        let none: BitFlags<Permissions> = parse!("perms=", "perms={}");
+        assert_eq!(none, BitFlags::from_bits(0));
+
+        let unknown: Result<BitFlags<Permissions>, _> = try_parse!("read|delete", "{}");
+        assert!(unknown.is_err());
+        let empty_name: Result<BitFlags<Permissions>, _> = try_parse!("read||exec", "{}");
+        assert!(empty_name.is_err());
+    }
+
+    #[test]
+    fn parse_one_of() {
+        let cell: OneOf2<f64, &str> = parse!("value: 2.5", "value: {}");
+        assert_eq!(cell, OneOf2::First(2.5));
+        let cell: OneOf2<f64, &str> = parse!("value: pending", "value: {}");
+        assert_eq!(cell, OneOf2::Second("pending"));
+
+        // The first type that succeeds is used, even if a later one would too.
+        let cell: OneOf2<u8, u16> = parse!("200", "{}");
+        assert_eq!(cell, OneOf2::First(200));
+        let cell: OneOf2<u8, u16> = parse!("300", "{}");
+        assert_eq!(cell, OneOf2::Second(300));
+        let cell: Result<OneOf2<u8, bool>, _> = try_parse!("300", "{}");
+        assert!(cell.is_err());
+
+        let cells: [OneOf3<i32, f32, &str>; 3] = parse!("-4 0.5 x", "{: :3}");
+        assert_eq!(
+            cells,
+            [OneOf3::First(-4), OneOf3::Second(0.5), OneOf3::Third("x")]
+        );
+        let cell: Result<OneOf3<u8, bool, char>, _> = try_parse!("maybe", "{}");
+        assert!(cell.is_err());
+    }
+
+    #[test]
+    fn parse_count() {
+        let headings = [
+            "# One",
+            "## Two",
+            "### Three",
+            "#### Four",
+            "##### Five",
+            "###### Six",
+        ];
+        for (level, heading) in (1..=6).zip(headings) {
+            let (count, title): (usize, &str) = parse!(heading, "{:#:count} {}");
+            assert_eq!(count, level);
+            assert_eq!(title, heading.trim_start_matches(['#', ' ']));
+        }
+
+        let (count, rest): (usize, &str) = parse!("no heading", "{:#:count}{}");
+        assert_eq!((count, rest), (0, "no heading"));
+        let level: usize;
+        parse!("==>", "{level:=:count}>");
+        assert_eq!(level, 2);
+        let (ab, c): (usize, usize) = parse!("ababc", "{:ab:count}{:c:count}");
+        assert_eq!((ab, c), (2, 1));
+
+        let missing: Result<(usize, &str), _> = try_parse!("Title", "#{:#:count} {}");
+        assert!(missing.is_err());
+        let no_space: Result<(usize, &str), _> = try_parse!("##Title", "{:#:count} {}");
+        assert!(no_space.is_err());
+    }
+
+    #[derive(Parse, Debug, PartialEq)]
+    #[prse = "{name}{args:()}"]
+    struct Call<'a> {
+        name: &'a str,
+        args: &'a str,
+    }
+
+    fn nesting(s: &str) -> usize {
+        let group: Result<(&str, &str, &str), _> = try_parse!(s, "{}{:()}{}");
+        match group {
+            Ok((_, inner, rest)) => (1 + nesting(inner)).max(nesting(rest)),
+            Err(_) => 0,
+        }
+    }
+
+    #[test]
+    fn parse_balanced() {
+        let call: Call = parse!("f(a,(b,c),d)", "{}");
+        assert_eq!(
+            call,
+            Call {
+                name: "f",
+                args: "a,(b,c),d"
+            }
+        );
+        let (inner, rest): (&str, &str) = parse!("(a,(b,c),d) rest", "{:()} {}");
+        assert_eq!((inner, rest), ("a,(b,c),d", "rest"));
+        let (first, second): (&str, &str) = parse!("[[1]] and [2]", "{:[]} and {:[]}");
+        assert_eq!((first, second), ("[1]", "2"));
+        let (items, tail): (&str, &str) = parse!("<1,<2>,3><>", "{:<>}{}");
+        assert_eq!((items, tail), ("1,<2>,3", "<>"));
+
+        assert_eq!(nesting("(a,(b,(c)),d)"), 3);
+        assert_eq!(nesting("x(y)(((z)))"), 3);
+        assert_eq!(nesting("no brackets"), 0);
+
+        let unbalanced = Call::from_str("f(a,(b,c)");
+        assert_eq!(
+            unbalanced.unwrap_err(),
+            prse::ParseError::Unbalanced {
+                open: '(',
+                close: ')'
+            }
+        );
+        let unbalanced: Result<&str, _> = try_parse!("((a)", "{:()}");
+        assert!(matches!(
+            unbalanced,
+            Err(prse::ParseError::Unbalanced { .. })
+        ));
+        let missing: Result<&str, _> = try_parse!("a)", "{:()}");
+        assert!(matches!(missing, Err(prse::ParseError::Literal { .. })));
+        let trailing: Result<&str, _> = try_parse!("(a))", "{:()}");
+        assert!(trailing.is_err());
+    }
+
+    #[derive(Parse, Debug, PartialEq)]
+    #[prse = "{sign:c}{amount}{unit:()}"]
+    struct Amount<'a> {
+        sign: char,
+        amount: u32,
+        unit: &'a str,
+    }
+
+    #[test]
+    fn adjacent_captures_that_end_on_their_own() {
+        let amount: Amount = parse!("-12(kg)", "{}");
+        assert_eq!(
+            amount,
+            Amount {
+                sign: '-',
+                amount: 12,
+                unit: "kg"
+            }
+        );
+        let (depth, sign, rest): (usize, char, &str) = parse!(">>+1", "{:>:count}{:c}{}");
+        assert_eq!((depth, sign, rest), (2, '+', "1"));
+    }
+
+    #[test]
+    fn parse_single_char() {
+        let (flag, n): (char, u32) = parse!("a1", "{:c}{}");
+        assert_eq!((flag, n), ('a', 1));
+        let (a, x, b, y): (char, u8, char, u8) = parse!("a1b2", "{:c}{:c}{:c}{:c}");
+        assert_eq!((a, x, b, y), ('a', 1, 'b', 2));
+        let (accent, crab, rest): (char, &str, &str) = parse!("é🦀 ok", "{:c}{:c} {}");
+        assert_eq!((accent, crab, rest), ('é', "🦀", "ok"));
+        let (lead, sign): (&str, char) = parse!("-> ±", "{} {:c}");
+        assert_eq!((lead, sign), ("->", '±'));
+        let (y, x): (char, char) = parse!("xy", "{1:c}{0:c}");
+        assert_eq!((x, y), ('x', 'y'));
+
+        let empty: Result<char, _> = try_parse!("", "{:c}");
+        assert!(matches!(empty, Err(prse::ParseError::Other { .. })));
+        let missing: Result<(char, char), _> = try_parse!("ab", "{:c}b{:c}");
+        assert!(missing.is_err());
+        let too_long: Result<char, _> = try_parse!("ab", "{:c}");
+        assert!(too_long.is_err());
+        let not_a_digit: Result<u8, _> = try_parse!("x", "{:c}");
+        assert!(not_a_digit.is_err());
+    }
+
+    #[test]
+    fn parse_line() {
+        let (a, b): (&str, &str) = parse!("a: hello\nb: world", "a: {:line}\nb: {:line}");
+        assert_eq!((a, b), ("hello", "world"));
+        let (a, b): (&str, u32) = parse!("a: hello\r\nb: 42\r", "a: {:line}\nb: {:line}");
+        assert_eq!((a, b), ("hello", 42));
+        let (name, rest): (&str, &str) = parse!("name: x = 1\n\nrest", "name: {:line}\n\n{}");
+        assert_eq!((name, rest), ("x = 1", "rest"));
+        let (empty, last): (&str, &str) = parse!("\r\nlast", "{:line}\n{}");
+        assert_eq!((empty, last), ("", "last"));
+
+        let more_lines: Result<&str, _> = try_parse!("hello\nworld", "{:line}");
+        assert!(more_lines.is_err());
+        let no_newline: Result<(&str, &str), _> =
+            try_parse!("a: hello b: world", "a: {:line} b: {}");
+        assert!(no_newline.is_err());
+        let not_a_number: Result<u32, _> = try_parse!("4 2\n", "{:line}\n");
+        assert!(not_a_number.is_err());
+    }
+
+    #[test]
+    fn pattern_literal_hints() {
+        let literals = pattern_literals!("[{}] {}: {{{}}}");
+        assert_eq!(literals.literals().len(), 4);
+        assert!(literals.literals().eq(["[", "] ", ": {", "}"]));
+        assert_eq!(literals.next_expected(""), Some("["));
+        assert_eq!(literals.next_expected("("), Some("["));
+        assert_eq!(literals.next_expected("[INFO"), Some("] "));
+        assert_eq!(literals.next_expected("[INFO] main"), Some(": {"));
+        assert_eq!(literals.next_expected("[INFO] main: {a"), Some("}"));
+        assert_eq!(literals.next_expected("[INFO] main: {a}"), None);
+
+        let literals = pattern_literals!("{}");
+        assert_eq!(literals.literals().len(), 0);
+        assert_eq!(literals.next_expected("anything"), None);
+
+        let literals = pattern_literals!("{x} = {x}");
+        assert!(literals.literals().eq([" = "]));
+        assert_eq!(literals.next_expected("a"), Some(" = "));
+    }
+
+    #[test]
+    fn parse_length() {
+        let (key, value): (usize, usize) = parse!("name=Zoë", "{:len}={:len}");
+        assert_eq!((key, value), (4, 4));
+        let (kind, rest): (&str, usize) = parse!("str:🦀 crab", "{}:{:len}");
+        assert_eq!((kind, rest), ("str", 9));
+        let empty: usize = parse!("[]", "[{:len}]");
+        assert_eq!(empty, 0);
+        let padded: usize = parse!("|  ab  |", "|{:trim,len}|");
+        assert_eq!(padded, 2);
+        let digits: usize = parse!("id 12345", r"id {:len,~\d+}");
+        assert_eq!(digits, 5);
+        let (first, second): (usize, Option<usize>) = parse!("ab", "{:len}{[,{:len}]}");
+        assert_eq!((first, second), (2, None));
+
+        let mut cursor = "ab cde f";
+        let tokens: [usize; 3] = [
+            parse_advance!(&mut cursor, "{:len} "),
+            parse_advance!(&mut cursor, "{:len} "),
+            parse_advance!(&mut cursor, "{:len}"),
+        ];
+        assert_eq!(tokens, [2, 3, 1]);
+
+        let not_a_digit: Result<usize, _> = try_parse!("12a", r"{:len,~\d+}");
+        assert!(not_a_digit.is_err());
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Direction {
+        North,
+        South,
+    }
+
+    #[derive(Parse, Debug, PartialEq)]
+    #[prse = "{steps} {direction:map(n => Direction::North, s => Direction::South)}"]
+    struct Move {
+        steps: u32,
+        direction: Direction,
+    }
+
+    #[test]
+    fn map_modifier() {
+        let direction = parse!("s", "{:map(n => Direction::North, s => Direction::South)}");
+        assert_eq!(direction, Direction::South);
+        let (level, code): (u8, u16) = parse!("warn 404", "{:map(info => 1, warn => 2)} {}");
+        assert_eq!((level, code), (2, 404));
+        let pair = parse!(" b ", "{:map(a => (1, \"a,b\"), b => (2, \"c\")),trim}");
+        assert_eq!(pair, (2, "c"));
+        let word: &str;
+        let bits = parse!("x on", "{word} {:map(on => Some(1 | 2), off => None)}");
+        assert_eq!((word, bits), ("x", Some(3)));
+
+        let step: Move = parse!("3 n", "{}");
+        assert_eq!(
+            step,
+            Move {
+                steps: 3,
+                direction: Direction::North
+            }
+        );
+        let sign = parse!("1", "1{[ {:map(+ => 1, - => -1)}]}");
+        assert_eq!(sign, None::<i32>);
+        let sign = parse!("1 -", "1{[ {:map(+ => 1, - => -1)}]}");
+        assert_eq!(sign, Some(-1));
+        let (name, direction) = parse_located!("ab\ns", "{}\n{:map(n => 'n', s => 's')}");
+        assert_eq!(name, ("ab", Location::new("ab\ns", 0)));
+        assert_eq!(direction, ('s', Location::new("ab\ns", 3)));
+
+        // The key must match the whole capture.
+        assert!(try_parse!("north", "{:map(n => 1, s => 2)}")
+            .map(|_: i32| ())
+            .is_err());
+        assert!(try_parse!(" n", "{:map(n => 1, s => 2)}")
+            .map(|_: i32| ())
+            .is_err());
+        assert!(try_parse!("N", "{:map(n => 1, s => 2)}")
+            .map(|_: i32| ())
+            .is_err());
+        assert!(try_parse!("4 e", "{}").map(|_: Move| ()).is_err());
+    }
+
+    #[test]
+    fn expected_value() {
+        let greeting: &str = parse!("HELLO 1", "{:== HELLO} 1");
+        assert_eq!(greeting, "HELLO");
+        let (version, flags): (u32, u8) = parse!("v 002: 0xFF", "v {:== 2}: 0x{:x,== ff}");
+        assert_eq!((version, flags), (2, 255));
+        let (time, name): (&str, &str) =
+            parse!("at 12:30 by bob", "at {:trim, == \"12:30\"} by {}");
+        assert_eq!((time, name), ("12:30", "bob"));
+        let padded: &str = parse!("[ a ]", "[{:== \" a \"}]");
+        assert_eq!(padded, " a ");
+        let sep: [u8; 2] = parse!("1==2", "{:==:2}");
+        assert_eq!(sep, [1, 2]);
+        let name: &str;
+        let ok: bool = parse!("PING ok=true", "{name:== PING} ok={:== true}");
+        assert_eq!((name, ok), ("PING", true));
+
+        let mismatch: Result<&str, _> = try_parse!("HELO", "{:== HELLO}");
+        assert!(matches!(
+            mismatch.unwrap_err().root_cause(),
+            prse::ParseError::Other { .. }
+        ));
+        let mismatch: Result<u32, _> = try_parse!("3", "{:== 2}");
+        assert!(mismatch.is_err());
+        let not_a_number: Result<u32, _> = try_parse!("x", "{:== 2}");
+        assert!(matches!(
+            not_a_number.unwrap_err().root_cause(),
+            prse::ParseError::Int(_)
+        ));
+        let unparsable: Result<u32, _> = try_parse!("2", "{:== two}");
+        assert!(unparsable.is_err());
+    }
+
+    #[derive(Parse, Eq, PartialEq, Debug)]
+    #[prse = "{cells: / :2:[ :2]}"]
+    struct Grid {
+        cells: [[char; 2]; 2],
+    }
+
+    #[test]
+    fn parse_rows() {
+        let matrix: [[i32; 3]; 2] = parse!("1,2,3;4,5,6", "{:;:2:[,:3]}");
+        assert_eq!(matrix, [[1, 2, 3], [4, 5, 6]]);
+        let matrix: [[u8; 2]; 2] = parse!("[ff  0a| 01 10]", "[{:|:2:[ :!2]:trim,x}]");
+        assert_eq!(matrix, [[255, 10], [1, 16]]);
+        let matrix: [[u8; 2]; 2] = parse!("1,2;;3,4", "{:;:!2:[,:2]}");
+        assert_eq!(matrix, [[1, 2], [3, 4]]);
+        assert_eq!(
+            Grid {
+                cells: [['a', 'b'], ['c', 'd']]
+            },
+            parse!("a b / c d", "{}")
+        );
+
+        let short_row: Result<[[i32; 3]; 2], _> = try_parse!("1,2,3;4,5", "{:;:2:[,:3]}");
+        assert!(matches!(
+            short_row.unwrap_err().root_cause(),
+            prse::ParseError::Array {
+                expected: 3,
+                found: 2
+            }
+        ));
+        let long_row: Result<[[i32; 2]; 2], _> = try_parse!("1,2;3,4,5", "{:;:2:[,:2]}");
+        assert!(matches!(
+            long_row.unwrap_err().root_cause(),
+            prse::ParseError::Array {
+                expected: 2,
+                found: 3
+            }
+        ));
+        let missing_row: Result<[[i32; 2]; 2], _> = try_parse!("1,2", "{:;:2:[,:2]}");
+        assert!(matches!(
+            missing_row.unwrap_err().root_cause(),
+            prse::ParseError::Array {
+                expected: 2,
+                found: 1
+            }
+        ));
+        let not_a_number: Result<[[i32; 2]; 1], _> = try_parse!("1,x", "{:;:1:[,:2]}");
+        assert!(matches!(
+            not_a_number.unwrap_err().root_cause(),
+            prse::ParseError::Int(_)
+        ));
+    }
+
+    #[test]
+    fn parse_hosts_and_emails() {
+        const LONGEST: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+        for host in [
+            "localhost",
+            "example.org",
+            "a-b.c0.EXAMPLE",
+            "xn--bcher-kva.de",
+            "1.2.3.4",
+            LONGEST,
+        ] {
+            let parsed: &str = parse!(host, "{:host}");
+            assert_eq!(parsed, host);
+        }
+        let invalid_hosts = ["", "-a.org", "a-.org", "a..org", ".org", "org.", "a_b.org", "bü.de", "a b", "[::1]", "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa.org", "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"];
+        for host in invalid_hosts {
+            let result: Result<&str, _> = try_parse!(host, "{:host}");
+            assert!(result.is_err(), "{host:?} should not be a host");
+        }
+
+        for email in ["a@b", "alice.smith+tag@example.org", "Ünï@mail.example.org"] {
+            let parsed: &str = parse!(email, "{:email}");
+            assert_eq!(parsed, email);
+        }
+        let invalid_emails = [
+            "",
+            "alice",
+            "@example.org",
+            "alice@",
+            "a@b@c",
+            "al ice@example.org",
+            "alice@-x.org",
+            "alice@ex ample.org",
+        ];
+        for email in invalid_emails {
+            let result: Result<&str, _> = try_parse!(email, "{:email}");
+            assert!(result.is_err(), "{email:?} should not be an email");
+        }
+
+        let (name, email, host, port): (&str, &str, &str, u16) = parse!(
+            "Alice < alice@example.org > via smtp.example.org:25",
+            "{} <{:trim,email}> via {:host}:{}"
+        );
+        assert_eq!(
+            (name, email, host, port),
+            ("Alice", "alice@example.org", "smtp.example.org", 25)
+        );
+        let hosts: [&str; 2] = parse!("a.org, b.org", "{:,:2:trim,host}");
+        assert_eq!(hosts, ["a.org", "b.org"]);
+        let len: usize = parse!("mail.example.org", "{:len,host}");
+        assert_eq!(len, 16);
+    }
+
+    #[derive(Parse, Debug, PartialEq)]
+    #[prse = "{name:len}: {value}"]
+    struct FieldWidth {
+        name: usize,
+        value: u32,
+    }
+
+    #[test]
+    fn parse_derived_length() {
+        let width: FieldWidth = parse!("height: 12", "{}");
+        assert_eq!(width, FieldWidth { name: 6, value: 12 });
+    }
+
+    #[derive(Parse, Debug, PartialEq)]
+    #[prse = "{date}"]
+    #[prse(sub(date = "{year}-{month}-{day}"))]
+    struct Date {
+        year: u16,
+        month: u8,
+        day: u8,
+    }
+
+    #[derive(Parse, Debug, PartialEq)]
+    #[prse = "{date}T{time} {level}"]
+    #[prse = "{date} {level}"]
+    #[prse(sub(date = "{year}-{month}-{day}", time = "{hour}:{minute}{[:{second}]}"))]
+    struct Entry<'a> {
+        year: u16,
+        month: u8,
+        day: u8,
+        #[prse(default)]
+        hour: u8,
+        #[prse(default)]
+        minute: u8,
+        #[prse(default = "59")]
+        second: u8,
+        level: &'a str,
+    }
+
+    #[test]
+    fn parse_sub_patterns() {
+        let date: Date = parse!("2021-01-02", "{}");
+        assert_eq!(
+            date,
+            Date {
+                year: 2021,
+                month: 1,
+                day: 2
+            }
+        );
+
+        let entry: Entry = parse!("2021-01-02T10:30 info", "{}");
+        assert_eq!(
+            entry,
+            Entry {
+                year: 2021,
+                month: 1,
+                day: 2,
+                hour: 10,
+                minute: 30,
+                second: 59,
+                level: "info"
+            }
+        );
+        let entry: Entry = parse!("2021-01-02T10:30:05 warn", "{}");
+        assert_eq!((entry.second, entry.level), (5, "warn"));
+        let entry: Entry = parse!("1999-12-31 error", "{}");
+        assert_eq!(
+            entry,
+            Entry {
+                year: 1999,
+                month: 12,
+                day: 31,
+                hour: 0,
+                minute: 0,
+                second: 59,
+                level: "error"
+            }
+        );
+
+        let missing_day: Result<Date, _> = try_parse!("2021-01", "{}");
+        assert!(missing_day.is_err());
+        let invalid_month: Result<Date, _> = try_parse!("2021-x-02", "{}");
+        assert!(invalid_month.is_err());
+        let invalid_time: Result<Entry, _> = try_parse!("2021-01-02T10 info", "{}");
+        assert!(invalid_time.is_err());
+    }
+
+    #[test]
+    fn parse_locale_bool() {
+        let english: [LocaleBool; 4] = parse!("yes, No, TRUE, off", "{:, :4}");
+        assert_eq!(
+            english.map(LocaleBool::into_inner),
+            [true, false, true, false]
+        );
+        let german: [LocaleBool<German>; 4] = parse!("Ja,nein,wahr,AUS", "{:,:4}");
+        assert_eq!(german.map(bool::from), [true, false, true, false]);
+        let french: [LocaleBool<French>; 3] = parse!("oui non VRAI", "{: :3}");
+        assert_eq!(french.map(|b| *b), [true, false, true]);
+        let spanish: [LocaleBool<Spanish>; 4] = parse!("sí|SÍ|si|No", "{:|:4}");
+        assert_eq!(spanish.map(|b| *b), [true, true, true, false]);
+        let padded: LocaleBool<English> = parse!("enabled:  y ", "enabled:{}");
+        assert!(*padded);
+
+        struct Binary;
+        impl BoolWords for Binary {
+            const TRUE: &'static [&'static str] = &["1"];
+            const FALSE: &'static [&'static str] = &["0"];
+        }
+        let bits: [LocaleBool<Binary>; 3] = parse!("101", "{::3}");
+        assert_eq!(bits.map(|b| *b), [true, false, true]);
+
+        let not_german: Result<LocaleBool<German>, _> = try_parse!("yes", "{}");
+        assert!(not_german.is_err());
+        let not_binary: Result<LocaleBool<Binary>, _> = try_parse!("true", "{}");
+        assert!(not_binary.is_err());
+        let empty: Result<LocaleBool, _> = try_parse!("", "{}");
+        assert!(empty.is_err());
+    }
+
+    #[test]
+    fn which_pattern_matches() {
+        let route = |line: &str| {
+            which_matches!(
+                line,
+                [
+                    "move {} from {} to {}",
+                    "rotate {}",
+                    r"id={:~\d+}",
+                    "id={}",
+                    "{:#:count} {}",
+                    "list [{:,:3}]{[ {}]}",
+                ]
+            )
+        };
+        assert_eq!(route("move three from a to b"), Some(0));
+        assert_eq!(route("rotate left"), Some(1));
+        assert_eq!(route("id=42"), Some(2));
+        assert_eq!(route("id=x42"), Some(3));
+        assert_eq!(route("## Title"), Some(4));
+        assert_eq!(route("list [a,b,c]"), Some(5));
+        assert_eq!(route("list [a,b,c] sorted"), Some(5));
+        assert_eq!(route("list [a,b]"), None);
+        assert_eq!(route("move three from a"), None);
+        assert_eq!(route(""), None);
+
+        let input = "0xff";
+        assert_eq!(which_matches!(input, ["{:x}", "{}"]), Some(0));
+        assert_eq!(which_matches!("hello", ["hi", "bye"]), None);
+        assert_eq!(which_matches!("a, b", ["{:, :2:trim}",]), Some(0));
+    }
+
+    /// A capture returned by `parse_located!`.
+    type Located<T> = (T, Location);
+
+    const fn at(line: usize, col: usize, offset: usize) -> Location {
+        Location { line, col, offset }
+    }
+
+    #[test]
+    fn located_captures() {
+        let input = "[server]\nhost = example.org\nport = 8080\n";
+        let (section, host, port): (Located<&str>, Located<&str>, Located<u16>) =
+            parse_located!(input, "[{}]\nhost = {}\nport = {}\n");
+        assert_eq!(section, ("server", at(1, 2, 1)));
+        assert_eq!(host, ("example.org", at(2, 8, 16)));
+        assert_eq!(port, (8080, at(3, 8, 35)));
+
+        let (tags, count, first): (Located<[u8; 3]>, Located<usize>, Located<char>) =
+            parse_located!("x\n  1,2,3\n##z", "x\n{:,:3:trim}\n{:#:count}{}");
+        assert_eq!(tags, ([1, 2, 3], at(2, 1, 2)));
+        assert_eq!(count, (2, at(3, 1, 10)));
+        assert_eq!(first, ('z', at(3, 3, 12)));
+
+        let (inner, note): (Located<&str>, Option<Located<&str>>) =
+            parse_located!("é: (a (b))\n", "é: {:()}\n{[note: {}]}");
+        assert_eq!(inner, ("a (b)", at(1, 5, 5)));
+        assert_eq!(note, None);
+        let (_, note): (Located<&str>, Option<Located<&str>>) =
+            parse_located!("é: ()\nnote: hi", "é: {:()}\n{[note: {}]}");
+        assert_eq!(note, Some(("hi", at(2, 7, 13))));
+
+        let name: Located<&str>;
+        let value: Located<u8> = parse_located!("a\r\n\tb=1", "a\r\n\t{name}={}");
+        assert_eq!(name, ("b", at(2, 2, 4)));
+        assert_eq!(value, (1, at(2, 4, 6)));
+
+        let only: Result<Located<u8>, _> = try_parse_located!("\n\n7", "{:trim}");
+        assert_eq!(only.unwrap(), (7, at(1, 1, 0)));
+        let error: Result<Located<u8>, _> = try_parse_located!("n\nx", "n\n{}");
+        assert!(error.is_err());
+
+        assert_eq!(Location::new("ab\ncd", 3), at(2, 1, 3));
+        assert_eq!(Location::new("ab\ncd", 5), at(2, 3, 5));
+    }
 }
@@ -0,0 +1,67 @@
+#![no_std]
+
+#[cfg(test)]
+mod tests {
+    use prse::{try_parse, HexArray, InlineMessage, ParseError, INLINE_MESSAGE_CAPACITY};
+
+    #[test]
+    fn errors() {
+        let l = "I love the following: bananas, apples, oranges.";
+        let case: Result<&str, _> = try_parse!(l, "I love the followin: {}.");
+        assert_eq!(case, Err(ParseError::Literal));
+
+        let hex: Result<HexArray<2>, _> = try_parse!("be:eg", "{}");
+        assert_eq!(
+            hex,
+            Err(ParseError::new(r#"invalid hex digit 'g' in "be:eg"."#))
+        );
+    }
+
+    #[test]
+    fn other_messages() {
+        let e = ParseError::new(format_args!("expected {} but found {}", 1, 2));
+        let ParseError::Other(message) = &e else {
+            panic!("expected an Other error")
+        };
+        assert_eq!(message.as_str(), "expected 1 but found 2");
+        assert!(!message.is_truncated());
+
+        let mut buf = [0_u8; 128];
+        let mut w = Buf(&mut buf, 0);
+        core::fmt::write(&mut w, format_args!("{e}")).unwrap();
+        let len = w.1;
+        assert_eq!(&buf[..len], b"expected 1 but found 2");
+    }
+
+    #[test]
+    fn truncated_messages() {
+        let message = InlineMessage::new(format_args!("{:>80}", "end"));
+        assert_eq!(message.as_str().len(), INLINE_MESSAGE_CAPACITY);
+        assert!(message.as_str().bytes().all(|b| b == b' '));
+        assert!(message.is_truncated());
+
+        // Multi-byte chars are never split in half.
+        let message = InlineMessage::new(format_args!("{:é>70}", ""));
+        assert_eq!(message.as_str().len(), INLINE_MESSAGE_CAPACITY);
+        let message = InlineMessage::new(format_args!("ab{:€>70}", ""));
+        assert_eq!(message.as_str().len(), INLINE_MESSAGE_CAPACITY - 2);
+        assert!(message.as_str().ends_with('€'));
+
+        assert_eq!(
+            InlineMessage::new(format_args!("{:>80}", "")),
+            InlineMessage::new(format_args!("{:>64}", ""))
+        );
+    }
+
+    struct Buf<'a>(&'a mut [u8], usize);
+
+    impl core::fmt::Write for Buf<'_> {
+        fn write_str(&mut self, s: &str) -> core::fmt::Result {
+            self.0[self.1..self.1 + s.len()].copy_from_slice(s.as_bytes());
+            self.1 += s.len();
+            Ok(())
+        }
+    }
+
+    include!("../common.rs");
+}
@@ -2,7 +2,7 @@
 
 #[cfg(test)]
 mod tests {
-    use prse::{parse, try_parse, ParseError};
+    use prse::{parse, try_parse, LatLon, ParseError};
 
     #[test]
     fn ui() {
@@ -24,6 +24,21 @@ mod tests {
         assert_eq!(char, 'a');
     }
 
+    #[test]
+    fn parse_lat_lon() {
+        let pos: LatLon = parse!("40.7128°N, 74.0060°W", "{}");
+        assert_eq!(
+            pos,
+            LatLon {
+                lat: 40.7128,
+                lon: -74.006
+            }
+        );
+
+        let pos: Result<LatLon, _> = try_parse!("-90.1,0", "{}");
+        assert_eq!(pos, Err(ParseError::Other));
+    }
+
     #[test]
     fn errors() {
         let l = "I love the following: bananas, apples, oranges.";
@@ -83,6 +98,100 @@ mod tests {
         assert_eq!(var, "test:")
     }
 
+    /// A fixed size buffer that fails instead of allocating when it is full.
+    struct Buffer {
+        bytes: [u8; 128],
+        len: usize,
+    }
+
+    impl Buffer {
+        fn format(error: &ParseError) -> Self {
+            use core::fmt::Write;
+
+            let mut buffer = Buffer {
+                bytes: [0; 128],
+                len: 0,
+            };
+            write!(buffer, "{error}").unwrap();
+            buffer
+        }
+
+        fn as_str(&self) -> &str {
+            core::str::from_utf8(&self.bytes[..self.len]).unwrap()
+        }
+    }
+
+    impl core::fmt::Write for Buffer {
+        fn write_str(&mut self, s: &str) -> core::fmt::Result {
+            let end = self.len + s.len();
+            self.bytes
+                .get_mut(self.len..end)
+                .ok_or(core::fmt::Error)?
+                .copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn display_without_alloc() {
+        let invalid_utf8 = core::hint::black_box([0xff_u8]);
+        let errors = [
+            (
+                ParseError::Int("x".parse::<u32>().unwrap_err()),
+                "unable to parse as an integer",
+            ),
+            (
+                ParseError::Bool("x".parse::<bool>().unwrap_err()),
+                "unable to parse as a boolean",
+            ),
+            (
+                ParseError::Char("xy".parse::<char>().unwrap_err()),
+                "unable to parse as a character",
+            ),
+            (
+                ParseError::Float("x".parse::<f32>().unwrap_err()),
+                "unable to parse as a float",
+            ),
+            (
+                ParseError::TryFromInt(u8::try_from(300_u32).unwrap_err()),
+                "integer out of range for the target type",
+            ),
+            (
+                ParseError::Utf8(core::str::from_utf8(&invalid_utf8).unwrap_err()),
+                "invalid UTF-8",
+            ),
+            (ParseError::Literal, "invalid literal match"),
+            (ParseError::BackReference, "invalid back-reference match"),
+            (
+                ParseError::Array {
+                    expected: 2,
+                    found: 3,
+                },
+                "invalid number of items (expected to find 2, found 3)",
+            ),
+            (
+                ParseError::Unbalanced {
+                    open: '(',
+                    close: ')',
+                },
+                "unbalanced brackets (expected to find ')' closing '(')",
+            ),
+            (
+                ParseError::TooManyElements { max: 10 },
+                "too many items (expected at most 10)",
+            ),
+            (ParseError::Other, "unable to parse into type"),
+            (
+                ParseError::Coded { code: 7 },
+                "unable to parse into type (code 7)",
+            ),
+        ];
+        for (error, message) in errors {
+            assert_eq!(Buffer::format(&error).as_str(), message);
+        }
+    }
+
     #[test]
     #[should_panic]
     fn check_parse_unwrap() {
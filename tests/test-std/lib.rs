@@ -1,6 +1,18 @@
 #[cfg(test)]
 mod tests {
-    use prse::{parse, try_parse, Parse, ParseChars, ParseError};
+    use prse::{
+        parse, parse_for_each, parse_map, parse_padded, parse_records, try_parse, try_parse_map,
+        try_parse_padded, try_parse_records, try_parse_validated, which_matches,
+    };
+    use prse::{
+        BitFlags, CaseInsensitive, Epoch, FlagNames, Grouped, Hex, Hms, Interned, LatLon, Length,
+        LocaleBool, Mass, Nfc, Nfkc, Normalized, Normalizer, Overflow, Parse, ParseChars,
+        ParseError, ParseErrorExt, ParseRadix, Percent, Quantity, Ratio, Rgb, Rgba, ScopedIpv6,
+        SignedDuration, SortedVec, SteppedRange, ThreadLocalInterner, TryFromStr, Unescaped,
+    };
+    use std::collections::BTreeMap;
+    use std::net::Ipv6Addr;
+    use std::sync::Arc;
 
     #[test]
     fn ui() {
@@ -86,6 +98,1656 @@ mod tests {
                 found: 3
             })
         );
+
+        let _tag: &str;
+        let case: Result<(), _> = try_parse!("<<b>>text<<i>>", "<<{_tag}>>text<<{_tag}>>");
+        assert_eq!(
+            case,
+            Err(ParseError::BackReference {
+                expected: String::from("b"),
+                found: String::from("i")
+            })
+        );
+    }
+
+    #[derive(Debug, Parse, PartialEq)]
+    #[prse = "<{tag}>{body}</{tag}>"]
+    struct Element<'a> {
+        tag: &'a str,
+        body: &'a str,
+    }
+
+    #[test]
+    fn derive_back_references() {
+        let element: Element = parse!("<b>bold</b>", "{}");
+        assert_eq!(
+            element,
+            Element {
+                tag: "b",
+                body: "bold"
+            }
+        );
+
+        // A field named twice is a back-reference, so it is only checked once the input is parsed.
+        assert_eq!(
+            Element::from_str("<b>bold</i>").unwrap_err(),
+            ParseError::BackReference {
+                expected: String::from("b"),
+                found: String::from("i")
+            }
+        );
+    }
+
+    #[allow(dead_code)]
+    #[derive(Debug, Parse)]
+    #[prse = "{name} is {age}"]
+    struct Person<'a> {
+        name: &'a str,
+        age: u32,
+    }
+
+    #[test]
+    fn error_field_names() {
+        let case: Result<Person, _> = try_parse!("Bob is eleven", "{}");
+        let error = case.unwrap_err();
+        assert!(error.to_string().contains("while parsing field `age`"));
+        assert!(matches!(
+            error,
+            ParseError::Context { error, .. } if matches!(
+                &*error,
+                ParseError::Context { field_name: Some(name), .. } if name == "age"
+            )
+        ));
+
+        let case: Result<u32, _> = try_parse!("Bob is eleven", "Bob is {}");
+        assert!(matches!(
+            case,
+            Err(ParseError::Context {
+                field_name: None,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn single_line_errors() {
+        let case: Result<Person, _> = try_parse!("Bob is eleven", "{}");
+        let error = case.unwrap_err();
+        assert_eq!(
+            error.to_single_line(),
+            "unable to parse \"Bob is eleven\" when parsing \"Bob is eleven\": \
+             unable to parse \"eleven\" when parsing \"Bob is eleven\" while parsing field `age`: \
+             unable to parse as an integer"
+        );
+        assert!(!error.to_single_line().contains('\n'));
+        assert_eq!(
+            error.to_string().replace(":\n\t", ": "),
+            error.to_single_line()
+        );
+
+        let error = ParseError::Array {
+            expected: 2,
+            found: 3,
+        };
+        assert_eq!(error.to_single_line(), error.to_string());
+    }
+
+    #[test]
+    fn grouped_radix_errors() {
+        let n: Result<u32, _> = try_parse!("0xFF__FF", "{:x,_}");
+        assert!(n
+            .unwrap_err()
+            .to_single_line()
+            .ends_with("misplaced digit separator in \"0xFF__FF\"."));
+
+        let n: Result<u8, _> = try_parse!("1_0000_0000", "{:b,_}");
+        assert_eq!(
+            n.unwrap_err().int_error_kind(),
+            Some(&std::num::IntErrorKind::PosOverflow)
+        );
+    }
+
+    #[test]
+    fn bit_flags_errors() {
+        struct Mode;
+
+        impl FlagNames for Mode {
+            const FLAGS: &'static [(&'static str, u64)] = &[("read", 4), ("write", 2), ("exec", 1)];
+        }
+
+        let mode: Result<BitFlags<Mode>, _> = BitFlags::from_str("read|delete");
+        assert_eq!(
+            mode.unwrap_err().to_string(),
+            r#"unknown flag "delete", expected one of read, write or exec."#
+        );
+    }
+
+    #[test]
+    fn multibyte_mismatch() {
+        let literal = |expected: &str, found: &str| ParseError::Literal {
+            expected: expected.into(),
+            found: found.into(),
+        };
+
+        let result: Result<&str, _> = try_parse!("日本x", "日本語{}");
+        assert_eq!(result, Err(literal("日本語", "日本x")));
+        let result: Result<&str, _> = try_parse!("é!", "é?{}");
+        assert_eq!(result, Err(literal("é?", "é!")));
+        let result: Result<&str, _> = try_parse!("🦀🦀", "{}🦀!");
+        assert_eq!(result, Err(literal("🦀!", "🦀🦀")));
+        let result: Result<&str, _> = try_parse!("ÉCRIRE éx", "(?iw)écrire éy{}");
+        assert_eq!(result, Err(literal("écrire éy", "ÉCRIRE éx")));
+        let result: Result<(), _> = try_parse!("añob", "año");
+        assert_eq!(result, Err(literal("", "b")));
+
+        let long = "ü".repeat(ParseError::LITERAL_WINDOW + 1);
+        let result: Result<&str, _> = try_parse!(long, "{}.");
+        let found = format!("{}...", "ü".repeat(ParseError::LITERAL_WINDOW));
+        assert_eq!(result, Err(literal(".", &found)));
+
+        let mut a = "unset";
+        let result: Result<(), _> = try_parse!("é-è", "{a}-{a}");
+        assert_eq!(a, "unset");
+        assert_eq!(
+            result,
+            Err(ParseError::BackReference {
+                expected: "é".into(),
+                found: "è".into()
+            })
+        );
+        let result: Result<u8, _> = try_parse!("x=ünf", "x={}");
+        assert!(result.unwrap_err().to_string().contains("\"ünf\""));
+    }
+
+    #[test]
+    fn parse_hex() {
+        let spaced: Hex = parse!("bytes: de ad be ef", "bytes: {}");
+        let unspaced: Hex = parse!("bytes: DEADBEEF", "bytes: {}");
+        assert_eq!(spaced.0, vec![0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(spaced, unspaced);
+
+        let hashes: Vec<Hex> = parse!("0a0b, 0c:0d", "{:, :}");
+        assert_eq!(hashes, vec![Hex(vec![10, 11]), Hex(vec![12, 13])]);
+
+        assert!(try_parse!("abc", "{}").map(|h: Hex| h).is_err());
+        assert!(try_parse!("a b", "{}").map(|h: Hex| h).is_err());
+        assert!(try_parse!("0g", "{}").map(|h: Hex| h).is_err());
+        assert_eq!(Hex(vec![]), parse!("", "{}"));
+    }
+
+    #[test]
+    fn signed_duration_errors() {
+        let unknown = SignedDuration::from_str("-30 s");
+        assert_eq!(
+            unknown.unwrap_err(),
+            ParseError::Other(
+                "unknown unit \" s\" in \"-30 s\", expected one of ns, us, ms, s, m, h or d."
+                    .to_string()
+            )
+        );
+        let overflow = SignedDuration::from_str("200000d");
+        assert_eq!(
+            overflow.unwrap_err(),
+            ParseError::Other("\"200000d\" does not fit into a duration.".to_string())
+        );
+        let overflow = SignedDuration::from_str("-99999999999999999999ns");
+        assert!(matches!(overflow, Err(ParseError::Other(_))));
+        let invalid = SignedDuration::from_str("-s");
+        assert!(matches!(invalid, Err(ParseError::Int(_))));
+    }
+
+    #[test]
+    fn for_each_vec_pattern() {
+        let mut seen = vec![];
+        let result = parse_for_each!("ids: 1;2;x;4", "ids: {:;:}", |n: u8| seen.push(n));
+        assert_eq!(seen, [1, 2]);
+        assert!(matches!(
+            result,
+            Err(ParseError::MultiContext { failed_string, .. }) if failed_string == "x"
+        ));
+    }
+
+    #[test]
+    fn parse_escaped_quotes() {
+        let said: String = parse!(r#"said: "he said \"hi\"""#, "said: {:qe}");
+        assert_eq!(said, r#"he said "hi""#);
+        let path: String = parse!(r#"path="C:\\temp\\new" end"#, "path={:qe} end");
+        assert_eq!(path, r"C:\temp\new");
+        let newline: String = parse!(r#""a\nb""#, "{:qe}");
+        assert_eq!(newline, r"a\nb");
+
+        let (name, id): (String, u32) = parse!(r#""Smith, John",42"#, "{:qe},{}");
+        assert_eq!((name, id), ("Smith, John".to_string(), 42));
+        let (quoted, id): (String, u32) = parse!(r#""a \",\" b",7"#, "{:qe},{}");
+        assert_eq!((quoted, id), (r#"a "," b"#.to_string(), 7));
+        let (plain, id): (String, u32) = parse!("plain,3", "{:qe},{}");
+        assert_eq!((plain, id), ("plain".to_string(), 3));
+        let (empty, level): (String, u8) = parse!(r#"NAME "" LEVEL 2"#, "(?i)name {:qe} level {}");
+        assert_eq!((empty, level), (String::new(), 2));
+        let (text, count): (String, u8) = parse!(r#""3 [items]" [5]"#, "{:qe} {:[]}");
+        assert_eq!((text, count), ("3 [items]".to_string(), 5));
+
+        let unterminated: Result<String, _> = try_parse!(r#""oops"#, "{:qe}");
+        assert_eq!(
+            unterminated.unwrap_err(),
+            ParseError::Other(
+                r#"expected the quoted string "\"oops" to end with its closing quote."#.to_string()
+            )
+        );
+        let after: Result<(String, u32), _> = try_parse!(r#""a"b,1"#, "{:qe},{}");
+        assert!(matches!(after, Err(ParseError::Literal { .. })));
+    }
+
+    #[test]
+    fn parse_interned() {
+        let (a, b): (Interned, Interned) = parse!("cat -> cat", "{} -> {}");
+        assert!(Arc::ptr_eq(a.as_arc(), b.as_arc()));
+        assert_eq!((&*a, &*b), ("cat", "cat"));
+
+        let column: Vec<Interned> = parse!("red,blue,red,red", "{:,:}");
+        assert!(Arc::ptr_eq(column[0].as_arc(), column[2].as_arc()));
+        assert!(Arc::ptr_eq(column[0].as_arc(), column[3].as_arc()));
+        assert!(!Arc::ptr_eq(column[0].as_arc(), column[1].as_arc()));
+
+        ThreadLocalInterner::clear();
+        let after: Interned = parse!("red", "{}");
+        assert_eq!(after, column[0]);
+        assert!(!Arc::ptr_eq(after.as_arc(), column[0].as_arc()));
+    }
+
+    #[test]
+    fn parse_normalized() {
+        let (composed, decomposed): (Normalized<String>, Normalized<String>) =
+            parse!("Zo\u{e9} Zoe\u{301}", "{} {}");
+        assert_eq!(composed, decomposed);
+        assert_eq!(decomposed.into_inner(), "Zo\u{e9}");
+
+        let names: Vec<Normalized<String>> = parse!("Bjo\u{308}rk,Bj\u{f6}rk,Bjork", "{:,:}");
+        assert_eq!(names[0], names[1]);
+        assert_ne!(names[1], names[2]);
+        assert_eq!(*names[2], "Bjork");
+
+        // NFC keeps compatibility chars such as ligatures, while NFKC replaces them.
+        let (nfc, nfkc): (Normalized<String, Nfc>, Normalized<String, Nfkc>) =
+            parse!("\u{fb01} \u{fb01}", "{} {}");
+        assert_eq!(*nfc, "\u{fb01}");
+        assert_eq!(*nfkc, "fi");
+
+        // A normalizer is also given ASCII strings.
+        struct Uppercase;
+
+        impl Normalizer for Uppercase {
+            fn normalize(s: &str) -> String {
+                s.to_uppercase()
+            }
+        }
+
+        let name: Normalized<String, Uppercase> = parse!("bjork", "{}");
+        assert_eq!(*name, "BJORK");
+    }
+
+    #[test]
+    fn parse_sorted_vec() {
+        let ids: SortedVec<u32> = parse!("ids: 42,7,19,7,", "ids: {}");
+        assert_eq!(ids, SortedVec(vec![7, 7, 19, 42]));
+        let words: SortedVec<&str, ' '> = parse!("pear apple fig", "{}");
+        assert_eq!(Vec::from(words), ["apple", "fig", "pear"]);
+        let empty: SortedVec<u32> = parse!("[]", "[{}]");
+        assert!(empty.is_empty());
+        let nested: Vec<SortedVec<u8, '-'>> = parse!("3-1-2, 9-8", "{:, :}");
+        assert_eq!(nested, [SortedVec(vec![1, 2, 3]), SortedVec(vec![8, 9])]);
+
+        #[derive(Parse)]
+        #[prse = "{scores} {scores} {scores}"]
+        struct Podium {
+            #[prse(multi)]
+            scores: SortedVec<i32>,
+        }
+        let podium: Podium = parse!("3 -1 2", "{}");
+        assert_eq!(*podium.scores, [-1, 2, 3]);
+
+        let invalid = SortedVec::<u32>::from_str("1,x");
+        assert!(matches!(
+            invalid,
+            Err(ParseError::MultiContext { failed_string, error, .. })
+                if failed_string == "x" && matches!(*error, ParseError::Int(_))
+        ));
+    }
+
+    #[test]
+    fn grouped_errors() {
+        let misgrouped = Grouped::<u32>::from_str("1,23,456");
+        assert_eq!(
+            misgrouped.unwrap_err(),
+            ParseError::Other(
+                "invalid digit grouping in \"1,23,456\", expected groups of 3 digits separated by ','."
+                    .to_string()
+            )
+        );
+        let european = Grouped::<f64, '.', ','>::from_str("12.34,5");
+        assert_eq!(
+            european.unwrap_err(),
+            ParseError::Other(
+                "invalid digit grouping in \"12.34,5\", expected groups of 3 digits separated by '.'."
+                    .to_string()
+            )
+        );
+        let invalid = Grouped::<u32>::from_str("1,2a4");
+        assert!(matches!(invalid, Err(ParseError::Int(_))));
+        let long = format!("1{}", ",000".repeat(22));
+        assert_eq!(
+            Grouped::<f64>::from_str(&long).unwrap_err(),
+            ParseError::Other(format!(
+                "{long:?} has too many digits, expected at most 64 bytes without the group separators."
+            ))
+        );
+        let longest = format!("-{}", "1".repeat(63));
+        assert!(Grouped::<f64>::from_str(&longest).is_ok());
+    }
+
+    #[test]
+    fn hms_errors() {
+        let malformed = Hms::from_str("1:2:3:4");
+        assert_eq!(
+            malformed.unwrap_err(),
+            ParseError::Other(
+                "expected \"1:2:3:4\" to be of the form HH:MM:SS or MM:SS.".to_string()
+            )
+        );
+        let out_of_range = Hms::from_str("00:75:00");
+        assert_eq!(
+            out_of_range.unwrap_err(),
+            ParseError::Other(
+                "the minutes and seconds of \"00:75:00\" must be less than 60.".to_string()
+            )
+        );
+        let precise = Hms::from_str("00:01.0000000001");
+        assert_eq!(
+            precise.unwrap_err(),
+            ParseError::Other(
+                "\"00:01.0000000001\" has more than 9 fractional digits.".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_epoch() {
+        use std::time::{Duration, UNIX_EPOCH};
+
+        let lines = "1700000000 login\n1700000000.5 logout\n+0.000000001 boot\n-1.25 rtc";
+        let events: Vec<(Epoch, &str)> = lines.lines().map(|l| parse!(l, "{} {}")).collect();
+        assert_eq!(
+            events[0].0,
+            Epoch(UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+        );
+        assert_eq!(
+            events[1].0 .0,
+            UNIX_EPOCH + Duration::from_millis(1_700_000_000_500)
+        );
+        assert_eq!(events[2].0 .0, UNIX_EPOCH + Duration::from_nanos(1));
+        assert_eq!(events[3].0 .0, UNIX_EPOCH - Duration::from_millis(1250));
+        assert_eq!(
+            std::time::SystemTime::from(Epoch::from_str(" 0 ").unwrap()),
+            UNIX_EPOCH
+        );
+
+        let malformed = Epoch::from_str("1700000000.");
+        assert_eq!(
+            malformed.unwrap_err(),
+            ParseError::Other(
+                "expected \"1700000000.\" to be a number of seconds since the Unix epoch."
+                    .to_string()
+            )
+        );
+        for invalid in ["", "-", "1.2.3", "0x10", "1_000", "--1", "1e9"] {
+            assert!(Epoch::from_str(invalid).is_err(), "{invalid}");
+        }
+        let precise = Epoch::from_str("1.0000000001");
+        assert_eq!(
+            precise.unwrap_err(),
+            ParseError::Other("\"1.0000000001\" has more than 9 fractional digits.".to_string())
+        );
+        let overflow = Epoch::from_str("99999999999999999999");
+        assert_eq!(
+            overflow.unwrap_err(),
+            ParseError::Other(
+                "\"99999999999999999999\" does not fit into a timestamp.".to_string()
+            )
+        );
+        assert!(Epoch::from_str(&u64::MAX.to_string()).is_err());
+    }
+
+    #[test]
+    fn parse_char_length() {
+        let (bytes, chars): (usize, usize) = parse!("Zoë Zoë", "{:len} {:clen}");
+        assert_eq!((bytes, chars), (4, 3));
+        let crab: usize = parse!("<🦀🦀>", "<{:clen}>");
+        assert_eq!(crab, 2);
+        let names: Vec<usize> = "Zoë,Ana,Łukasz"
+            .split(',')
+            .map(|name| parse!(name, "{:trim,clen}"))
+            .collect();
+        assert_eq!(names, [3, 3, 6]);
+    }
+
+    #[test]
+    fn locale_bool_errors() {
+        let unknown = LocaleBool::<prse::Spanish>::from_str(" quizás ");
+        assert_eq!(
+            unknown.unwrap_err(),
+            ParseError::Other(
+                "\"quizás\" is not a bool, expected one of sí, si, s, verdadero, no, n or falso."
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_scoped_ipv6() {
+        let link_local = Ipv6Addr::new(0xfe80, 0, 0, 0, 0x1ff, 0xfe23, 0x4567, 0x890a);
+        let scoped: ScopedIpv6 = parse!("fe80::1ff:fe23:4567:890a%eth0", "{}");
+        assert_eq!(scoped.addr, link_local);
+        assert_eq!(scoped.zone.as_deref(), Some("eth0"));
+
+        let scoped: ScopedIpv6 = parse!("[fe80::1ff:fe23:4567:890a%3]:80", "[{}]:80");
+        assert_eq!(scoped.zone.as_deref(), Some("3"));
+
+        let scoped: ScopedIpv6 = parse!("::1", "{}");
+        let (addr, zone): (Ipv6Addr, Option<String>) = scoped.into();
+        assert_eq!((addr, zone), (Ipv6Addr::LOCALHOST, None));
+
+        let addresses: Vec<ScopedIpv6> = parse!("fe80::1%wlan0 ::1 fe80::2%en0", "{: :}");
+        let zones: Vec<_> = addresses.iter().map(|a| a.zone.as_deref()).collect();
+        assert_eq!(zones, [Some("wlan0"), None, Some("en0")]);
+
+        let malformed = ScopedIpv6::from_str("fe80::g%eth0");
+        assert!(matches!(malformed, Err(ParseError::Addr(_))));
+        let ipv4 = ScopedIpv6::from_str("127.0.0.1");
+        assert!(matches!(ipv4, Err(ParseError::Addr(_))));
+        assert_eq!(
+            ScopedIpv6::from_str("fe80::1%").unwrap_err(),
+            ParseError::Other(
+                "expected a zone identifier after the % of \"fe80::1%\".".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_unescaped_strings() {
+        let escapes = [
+            (r"line\n", "line\n"),
+            (r"cr\r", "cr\r"),
+            (r"a\tb", "a\tb"),
+            (r"back\\slash", "back\\slash"),
+            (r#"say \"hi\""#, "say \"hi\""),
+            (r"\x41\x7f", "A\x7f"),
+            (r"\u{e9}\u{1F600}", "é😀"),
+            ("plain", "plain"),
+            ("", ""),
+        ];
+        for (input, expected) in escapes {
+            let unescaped: Unescaped = parse!(input, "{}");
+            assert_eq!(*unescaped, expected);
+        }
+
+        let env: Vec<(&str, Unescaped)> = parse!(r"A=one\ntwo;B=\ttab", "{:;:map}");
+        assert_eq!(env[0], ("A", Unescaped("one\ntwo".to_string())));
+        assert_eq!(String::from(env[1].1.clone()), "\ttab");
+
+        let invalid = [
+            (
+                r"C:\path",
+                r#"unknown escape \p in "C:\\path", expected one of \n, \r, \t, \\, \", \x or \u."#,
+            ),
+            (
+                r"end\",
+                r#"expected an escape after the trailing \ of "end\\"."#,
+            ),
+            (
+                r"\x80",
+                r#"expected two hex digits up to 7f after \x in "\\x80"."#,
+            ),
+            (
+                r"\x4",
+                r#"expected two hex digits up to 7f after \x in "\\x4"."#,
+            ),
+            (
+                r"\x+1",
+                r#"expected two hex digits up to 7f after \x in "\\x+1"."#,
+            ),
+        ];
+        for (input, message) in invalid {
+            assert_eq!(
+                Unescaped::from_str(input).unwrap_err(),
+                ParseError::Other(message.to_string())
+            );
+        }
+        for input in [r"\u41", r"\u{}", r"\u{1234567}", r"\u{d800}", r"\u{41"] {
+            assert_eq!(
+                Unescaped::from_str(input).unwrap_err(),
+                ParseError::Other(format!(
+                    "expected 1 to 6 hex digits of a unicode char in braces after \\u in {input:?}."
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn which_matches_repetitions() {
+        let line = String::from(r#"tags: a,,b,c "quoted \"name\"" k=v;x=y"#);
+        let kind = which_matches!(
+            line,
+            [
+                "tags: {:,:} {:qe} {:;:map}",
+                "tags: {:,:dd} {:qe} {:;:map}",
+                "tags: {} {}"
+            ]
+        );
+        assert_eq!(kind, Some(0));
+        let kind = which_matches!(
+            line,
+            [r"tags: {:,::~\w} {}", r"tags: {:,::dd,~.+} {}", "{}"]
+        );
+        assert_eq!(kind, Some(1));
+        let kind = which_matches!("abc", ["{::0}", "{::3}"]);
+        assert_eq!(kind, Some(0));
+        let kind = which_matches!("1,2;3", ["{:;:[,:2]}", "{:;:[,:!1]}", "{:;:}"]);
+        assert_eq!(kind, Some(2));
+    }
+
+    #[test]
+    fn stepped_range_errors() {
+        let malformed = SteppedRange::<i32>::from_str("0..10");
+        assert_eq!(
+            malformed.unwrap_err(),
+            ParseError::Other("expected \"0..10\" to be of the form start..end..step.".to_string())
+        );
+        let zero = SteppedRange::<i32>::from_str("0..10..0");
+        assert_eq!(
+            zero.unwrap_err(),
+            ParseError::Other("the step of \"0..10..0\" cannot be zero.".to_string())
+        );
+        let backwards = SteppedRange::<i32>::from_str("-1..-3..1");
+        assert_eq!(
+            backwards.unwrap_err(),
+            ParseError::Other("the step of \"-1..-3..1\" goes away from its end.".to_string())
+        );
+        let invalid = SteppedRange::<i32>::from_str("0..ten..1");
+        assert!(matches!(invalid, Err(ParseError::Int(_))));
+        for nan in ["0..1..NaN", "NaN..1..0.5", "0..nan..-1"] {
+            assert_eq!(
+                SteppedRange::<f64>::from_str(nan).unwrap_err(),
+                ParseError::Other(format!("{nan:?} cannot have a NaN start, end or step."))
+            );
+        }
+    }
+
+    #[test]
+    fn ratio_errors() {
+        assert_eq!(
+            Ratio::<false>::from_str("2.5").unwrap_err(),
+            ParseError::Other("expected \"2.5\" to end with % or bps.".to_string())
+        );
+        assert_eq!(
+            Ratio::<true>::from_str(" -3 bp").unwrap_err(),
+            ParseError::Other(
+                "unknown suffix \"bp\" in \" -3 bp\", expected % or bps.".to_string()
+            )
+        );
+        let invalid = Ratio::<false>::from_str("1.2.3%");
+        assert!(matches!(invalid, Err(ParseError::Float(_))));
+        assert_eq!(
+            Ratio::<false>::from_str("nan%").unwrap_err(),
+            ParseError::Other("\"nan\" in \"nan%\" is not a finite number.".to_string())
+        );
+        assert_eq!(
+            Ratio::<true>::from_str("-inf").unwrap_err(),
+            ParseError::Other("\"-inf\" in \"-inf\" is not a finite number.".to_string())
+        );
+        assert_eq!(
+            Ratio::<false>::from_str("inf").unwrap_err(),
+            ParseError::Other("expected \"inf\" to end with % or bps.".to_string())
+        );
+        assert_eq!(
+            Percent::<false>::from_str(" inf %").unwrap_err(),
+            ParseError::Other("\"inf\" in \" inf %\" is not a finite number.".to_string())
+        );
+        assert_eq!(
+            Percent::<true>::from_str("1e400").unwrap_err(),
+            ParseError::Other("\"1e400\" in \"1e400\" is not a finite number.".to_string())
+        );
+    }
+
+    #[test]
+    fn shape_errors() {
+        let mismatch: Result<u32, _> = try_parse!("id: 12a", r"id: {:~\d+}");
+        assert_eq!(
+            mismatch.unwrap_err(),
+            ParseError::Context {
+                full_string: "id: 12a".into(),
+                failed_item: "12a".into(),
+                field_name: None,
+                error: Box::new(ParseError::Other(
+                    r#""12a" does not match the shape `\d+`."#.to_string()
+                )),
+            }
+        );
+    }
+
+    #[test]
+    fn map_modifier_errors() {
+        let level: Result<u8, _> = try_parse!(
+            "level: debug",
+            "level: {:map(info => 1, warn => 2, error => 3)}"
+        );
+        assert_eq!(
+            level.unwrap_err().root_cause(),
+            &ParseError::Other(
+                r#""debug" is not a mapped key, expected one of info, warn or error."#.to_string()
+            )
+        );
+        let level: Result<u8, _> = try_parse!("debug", "{:map(info => 1)}");
+        assert_eq!(
+            level.unwrap_err().root_cause(),
+            &ParseError::Other(r#""debug" is not a mapped key, expected one of info."#.to_string())
+        );
+    }
+
+    #[test]
+    fn quantity_errors() {
+        let unknown: Result<Quantity<Mass>, _> = try_parse!("5 stone", "{}");
+        assert_eq!(
+            unknown.unwrap_err().root_cause(),
+            &ParseError::Other(
+                r#"unknown unit "stone", expected one of mg, g, kg, t, oz or lb."#.to_string()
+            )
+        );
+        let incompatible: Result<Quantity<Length>, _> = try_parse!("height: 3 kg", "height: {}");
+        assert_eq!(
+            incompatible.unwrap_err().root_cause(),
+            &ParseError::Other(r#""kg" is a unit of mass, expected a unit of length."#.to_string())
+        );
+        let missing: Result<Quantity<Mass>, _> = try_parse!("5", "{}");
+        assert_eq!(
+            missing.unwrap_err().root_cause(),
+            &ParseError::Other(
+                r#""5" has no unit, expected one of mg, g, kg, t, oz or lb."#.to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn expected_value_errors() {
+        let greeting: Result<&str, _> = try_parse!("HELO world", "{:== HELLO} world");
+        assert_eq!(
+            greeting.unwrap_err().root_cause(),
+            &ParseError::Other(r#"expected "HELLO" but found "HELO"."#.to_string())
+        );
+        let version: Result<u8, _> = try_parse!("v1", "v{:== two}");
+        assert_eq!(
+            version.unwrap_err().root_cause(),
+            &ParseError::Other(
+                r#"the expected value "two" cannot be parsed into the type of the capture."#
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn host_and_email_errors() {
+        let host: Result<String, _> = try_parse!("a_b.org", "{:host}");
+        assert_eq!(
+            host.unwrap_err().root_cause(),
+            &ParseError::Other(r#""a_b.org" is not a valid host name."#.to_string())
+        );
+        let email: Result<String, _> = try_parse!("to: a@b@c", "to: {:email}");
+        assert_eq!(
+            email.unwrap_err(),
+            ParseError::Context {
+                full_string: "to: a@b@c".into(),
+                failed_item: "a@b@c".into(),
+                field_name: None,
+                error: Box::new(ParseError::Other(
+                    r#""a@b@c" is not a valid email address."#.to_string()
+                )),
+            }
+        );
+    }
+
+    #[test]
+    fn alternative_pattern_errors() {
+        #[derive(Parse, Debug)]
+        #[prse = "x={x},y={y}"]
+        #[prse = "{x},{y}"]
+        struct Point {
+            #[allow(dead_code)]
+            x: i32,
+            #[allow(dead_code)]
+            y: i32,
+        }
+
+        let error = Point::from_str("x=1,y=a").unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::Other(
+                r#""x=1,y=a" does not match any pattern of Point (unable to parse "a" when parsing "x=1,y=a" while parsing field `y`: unable to parse as an integer; unable to parse "x=1" when parsing "x=1,y=a" while parsing field `x`: unable to parse as an integer)."#.to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn colour_errors() {
+        let long = Rgb::from_str("#ff8800aa");
+        assert_eq!(
+            long.unwrap_err(),
+            ParseError::Other("expected 3 or 6 hex digits in \"#ff8800aa\".".to_string())
+        );
+        let short = Rgba::from_str("#ff");
+        assert_eq!(
+            short.unwrap_err(),
+            ParseError::Other("expected 3, 4, 6 or 8 hex digits in \"#ff\".".to_string())
+        );
+        let invalid = Rgba::from_str("#f8x");
+        assert_eq!(
+            invalid.unwrap_err(),
+            ParseError::Other("invalid hex digit 'x' in \"#f8x\".".to_string())
+        );
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Celsius(f64);
+
+    impl TryFrom<&str> for Celsius {
+        type Error = Box<dyn std::error::Error + Send + Sync>;
+
+        fn try_from(s: &str) -> Result<Self, Self::Error> {
+            let degrees = s.strip_suffix("°C").ok_or("missing unit")?;
+            Ok(Celsius(degrees.parse()?))
+        }
+    }
+
+    #[test]
+    fn parse_try_from() {
+        let temp: TryFromStr<Celsius> = parse!("It is 21.5°C outside.", "It is {} outside.");
+        assert_eq!(*temp, Celsius(21.5));
+
+        let temps: Vec<TryFromStr<Celsius>> = parse!("1°C,2°C", "{:,:}");
+        assert_eq!(temps[1].0, Celsius(2.0));
+
+        let temp: Result<TryFromStr<Celsius>, _> = try_parse!("21.5°F", "{}");
+        assert_eq!(
+            temp.unwrap_err().root_cause(),
+            &ParseError::Other("missing unit".to_string())
+        );
+    }
+
+    fn luhn(digits: &Vec<u32>) -> Result<(), ParseError> {
+        let sum: u32 = digits
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(i, &d)| match i % 2 {
+                0 => d,
+                _ if d * 2 > 9 => d * 2 - 9,
+                _ => d * 2,
+            })
+            .sum();
+        if sum % 10 == 0 {
+            Ok(())
+        } else {
+            Err(ParseError::new(format!("invalid checksum {sum}")))
+        }
+    }
+
+    #[test]
+    fn parse_validated() {
+        let card: Result<Vec<u32>, _> =
+            try_parse_validated!("Card: 79927398713", "Card: {::}", luhn);
+        assert_eq!(card.unwrap(), vec![7, 9, 9, 2, 7, 3, 9, 8, 7, 1, 3]);
+
+        let card: Result<Vec<u32>, _> =
+            try_parse_validated!("Card: 79927398710", "Card: {::}", luhn);
+        assert_eq!(card, Err(ParseError::new("invalid checksum 67")));
+
+        // Structural errors are returned without calling the validator.
+        let card: Result<Vec<u32>, _> =
+            try_parse_validated!("Card: 7992a", "Card: {::}", |_| { unreachable!() });
+        assert!(matches!(card, Err(ParseError::MultiContext { .. })));
+
+        let start: u32;
+        let end: u32;
+        let range = try_parse_validated!("5..10", "{start}..{end}", |_| {
+            if start <= end {
+                Ok(())
+            } else {
+                Err(ParseError::new("empty range"))
+            }
+        });
+        assert_eq!(range, Ok(()));
+
+        let start: u32;
+        let end: u32;
+        let range = try_parse_validated!("10..5", "{start}..{end}", |_| {
+            if start <= end {
+                Ok(())
+            } else {
+                Err(ParseError::new("empty range"))
+            }
+        });
+        assert_eq!(range, Err(ParseError::new("empty range")));
+    }
+
+    #[test]
+    fn parse_lat_lon() {
+        let pos: LatLon = parse!("pos: 40.7128,-74.0060", "pos: {}");
+        assert_eq!(
+            pos,
+            LatLon {
+                lat: 40.7128,
+                lon: -74.006
+            }
+        );
+
+        let pos: LatLon = parse!("33.8688° S, 151.2093° E", "{}");
+        assert_eq!(
+            pos,
+            LatLon {
+                lat: -33.8688,
+                lon: 151.2093
+            }
+        );
+
+        let path: Vec<LatLon> = parse!("0,0;90,180;-90,-180", "{:;:}");
+        assert_eq!(
+            path[2],
+            LatLon {
+                lat: -90.0,
+                lon: -180.0
+            }
+        );
+
+        let pos: Result<LatLon, _> = try_parse!("1,180.5", "{}");
+        assert_eq!(
+            pos,
+            Err(ParseError::Context {
+                failed_item: "1,180.5".into(),
+                full_string: "1,180.5".into(),
+                field_name: None,
+                error: Box::new(ParseError::new(
+                    "180.5 is out of range, expected a value between -180 and 180."
+                ))
+            })
+        );
+
+        let pos: Result<LatLon, _> = try_parse!("91N,0", "{}");
+        assert!(pos.is_err());
+        let pos: Result<LatLon, _> = try_parse!("40.7128", "{}");
+        assert!(pos.is_err());
+        let pos: Result<LatLon, _> = try_parse!("-40°S, +10 E", "{}");
+        assert_eq!(
+            pos.unwrap_err().root_cause(),
+            &ParseError::new(
+                r#""-40°S" has both a sign and a hemisphere, expected only one of them."#
+            )
+        );
+    }
+
+    #[derive(Parse, Debug, PartialEq)]
+    #[prse = "Host: {host}\nSet-Cookie: {cookies}\nSet-Cookie: {cookies}\nSet-Cookie: {cookies}"]
+    struct Headers {
+        host: String,
+        #[prse(multi)]
+        cookies: Vec<String>,
+    }
+
+    #[derive(Parse, Debug, PartialEq)]
+    enum Command<'a> {
+        #[prse = "move x={x} y={y}"]
+        Move { x: i32, y: i32 },
+        #[prse = "say {}"]
+        Say(&'a str),
+        #[prse = "quit"]
+        Quit,
+    }
+
+    #[test]
+    fn tagged_enum_errors() {
+        let unknown = Command::from_str("jump 3");
+        assert_eq!(
+            unknown.unwrap_err().to_string(),
+            r#""jump 3" does not match any variant of Command."#
+        );
+
+        let unknown = Command::from_str("quit now");
+        assert!(matches!(unknown, Err(ParseError::Other(_))));
+
+        // The error of the variant with the matching tag is kept.
+        let invalid = Command::from_str("move x=1 y=up");
+        assert!(matches!(
+            invalid,
+            Err(ParseError::Context { field_name: Some(name), error, .. })
+                if name == "y" && matches!(*error, ParseError::Int(_))
+        ));
+    }
+
+    #[derive(Parse, Debug, PartialEq)]
+    enum Range {
+        #[prse = "{bounds}..{bounds}"]
+        Bounded {
+            #[prse(multi)]
+            bounds: std::collections::BTreeSet<u32>,
+        },
+        #[prse = "{bounds}.."]
+        From {
+            #[prse(multi)]
+            bounds: Vec<u32>,
+        },
+    }
+
+    #[test]
+    fn parse_multi_fields() {
+        let headers: Headers = parse!(
+            "Host: example.com\nSet-Cookie: a=1\nSet-Cookie: b=2\nSet-Cookie: c=3",
+            "{}"
+        );
+        assert_eq!(
+            headers,
+            Headers {
+                host: "example.com".into(),
+                cookies: vec!["a=1".into(), "b=2".into(), "c=3".into()]
+            }
+        );
+
+        let range: Range = parse!("9..2", "{}");
+        assert_eq!(
+            range,
+            Range::Bounded {
+                bounds: [2, 9].into()
+            }
+        );
+        let range: Range = parse!("4..", "{}");
+        assert_eq!(range, Range::From { bounds: vec![4] });
+    }
+
+    #[test]
+    fn from_utf8_error() {
+        let bytes = vec![b'a', 0xff];
+        let e: ParseError = String::from_utf8(bytes.clone()).unwrap_err().into();
+        assert_eq!(
+            e,
+            ParseError::Utf8(std::str::from_utf8(&bytes).unwrap_err())
+        );
+        assert_eq!(e.to_string(), "invalid UTF-8");
+        assert!(std::error::Error::source(&e).is_some());
+    }
+
+    #[test]
+    fn literal_error_is_truncated() {
+        let input = format!("header: {}", "0123456789".repeat(100_000));
+        let result: Result<&str, _> = try_parse!(input, "header: {};");
+        let ParseError::Literal { expected, found } = result.unwrap_err() else {
+            panic!("expected a literal error");
+        };
+        assert_eq!(expected, ";");
+        assert_eq!(found, format!("{}...", &"0123456789".repeat(7)[..64]));
+        // The window starts at the mismatch, so the kept chars are at its offset in the untruncated input.
+        let offset = "header: ".len();
+        let kept = found.strip_suffix("...").unwrap();
+        assert_eq!(&input[offset..offset + kept.len()], kept);
+
+        let error = ParseError::literal_with_window(";", &input[offset..], 3);
+        assert_eq!(
+            error,
+            ParseError::Literal {
+                expected: ";".into(),
+                found: "012...".into()
+            }
+        );
+        let error = ParseError::literal_with_window(";", "éèê", 2);
+        assert_eq!(
+            error,
+            ParseError::Literal {
+                expected: ";".into(),
+                found: "éè...".into()
+            }
+        );
+        let error = ParseError::literal_with_window(";", &input[offset..], usize::MAX);
+        assert_eq!(
+            error,
+            ParseError::Literal {
+                expected: ";".into(),
+                found: input[offset..].into()
+            }
+        );
+
+        let result: Result<&str, _> = try_parse!("header: é", "header: {};");
+        assert_eq!(
+            result.unwrap_err(),
+            ParseError::Literal {
+                expected: ";".into(),
+                found: "é".into()
+            }
+        );
+
+        let result: Result<(), _> = try_parse!(input, "header");
+        let ParseError::Literal { found, .. } = result.unwrap_err() else {
+            panic!("expected a literal error");
+        };
+        assert_eq!(found.chars().count(), ParseError::LITERAL_WINDOW + 3);
+        assert!(found.starts_with(": 0123"));
+    }
+
+    #[test]
+    fn literal_error_escapes_control_chars() {
+        let input = "key\0\nvalue\r\n\u{1b}[31m";
+        let result: Result<&str, _> = try_parse!(input, "key\n{}");
+        let error = result.unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::Literal {
+                expected: "key\n".into(),
+                found: input.into()
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            r#"invalid literal match (expected to find "key\n", found "key\0\nvalue\r\n\u{1b}[31m")"#
+        );
+        assert!(!error.to_string().contains(char::is_control));
+
+        let result: Result<&str, _> = try_parse!("a\tb\0", "a\t{}\n");
+        let message = result.unwrap_err().to_string();
+        assert!(
+            message.ends_with(r#"(expected to find "\n", found "b\0")"#),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn doubled_separators() {
+        let elements: Vec<String> = parse!("a,,b,c", "{:,:dd}");
+        assert_eq!(elements, ["a,b", "c"]);
+
+        let elements: Vec<String> = parse!(",,a,b,,", "{:,:dd}");
+        assert_eq!(elements, [",a", "b,"]);
+
+        let elements: Vec<String> = parse!("a;;;b;;;;c", "{:;::dd}");
+        assert_eq!(elements, ["a;", "b;;c"]);
+
+        let elements: [String; 3] = parse!("x | y || z | ||", "{:|:3:dd,trim}");
+        assert_eq!(elements, ["x", "y | z", "|"]);
+
+        let numbers: [u32; 2] = parse!("1,2", "{:,:2:dd}");
+        assert_eq!(numbers, [1, 2]);
+
+        let error = try_parse!("1,2,,x", "{:,:dd}")
+            .map(|v: Vec<u32>| v)
+            .unwrap_err();
+        assert!(
+            matches!(error, ParseError::MultiContext { failed_string, .. } if failed_string == "2,x")
+        );
+    }
+
+    #[test]
+    fn separator_class_collections() {
+        let numbers: Vec<u32> = parse!("1,2, 3\t4 ,\t5", r"{:[,\s]+:}");
+        assert_eq!(numbers, [1, 2, 3, 4, 5]);
+
+        let numbers: Vec<u32> = parse!(", \t,", r"{:[,\s]+:}");
+        assert!(numbers.is_empty());
+
+        let words: Vec<String> = parse!("  a b,c  ", "{:[, ]+::trim}");
+        assert_eq!(words, ["a", "b", "c"]);
+
+        let settings: BTreeMap<&str, u32> = parse!("a=1, b=2\tc=3", r"{:[,\s]+:map}");
+        assert_eq!(settings, BTreeMap::from([("a", 1), ("b", 2), ("c", 3)]));
+    }
+
+    #[test]
+    fn parse_map() {
+        let input = "[12:30] alice -> #general: hello, world";
+        let captures = parse_map!(input, "[{time}] {} -> #{channel}: {}");
+        let expected = BTreeMap::from([
+            ("time", "12:30"),
+            ("0", "alice"),
+            ("channel", "general"),
+            ("1", "hello, world"),
+        ]);
+        assert_eq!(captures, expected);
+
+        let captures = parse_map!("1, 2, 3 | x", "{1:, :3} | {0}");
+        assert_eq!(captures, BTreeMap::from([("0", "x"), ("1", "1, 2, 3")]));
+
+        let captures = try_parse_map!("<b>text</b>", "<{tag}>{}</{tag}>").unwrap();
+        assert_eq!(captures, BTreeMap::from([("tag", "b"), ("0", "text")]));
+
+        let captures = parse_map!("GET /", "{method} {path}{[ {version}]}");
+        assert_eq!(captures, BTreeMap::from([("method", "GET"), ("path", "/")]));
+
+        assert!(try_parse_map!("<b>text</i>", "<{tag}>{}</{tag}>").is_err());
+        assert!(try_parse_map!("12:30", "{hour}-{minute}").is_err());
+    }
+
+    #[test]
+    fn windows_line_endings() {
+        let input = "x=1\r\ny=-2\r\nz=3";
+        let lines: Vec<&str> = parse!(input, "{:\n:}");
+        assert_eq!(lines, ["x=1", "y=-2", "z=3"]);
+
+        let pairs: Vec<(String, i32)> = input.lines().map(|line| parse!(line, "{}={}")).collect();
+        assert_eq!(pairs, [("x".into(), 1), ("y".into(), -2), ("z".into(), 3)]);
+    }
+
+    #[test]
+    fn vec_element_modifiers() {
+        let values: Vec<u32> = parse!(" 0x1 , 0x2 ,0x3", "{:,::x,trim}");
+        assert_eq!(values, [1, 2, 3]);
+
+        let (names, rest): (Vec<&str>, &str) = parse!("a ,  b,c ; rest", "{:,::trim};{}");
+        assert_eq!(names, ["a", "b", "c"]);
+        assert_eq!(rest, " rest");
+
+        let result: Result<Vec<u8>, _> = try_parse!("ff,1g", "{:,::x}");
+        assert!(matches!(
+            result.unwrap_err(),
+            ParseError::MultiContext { failed_string, .. } if failed_string == "1g"
+        ));
+    }
+
+    #[test]
+    fn repetition_before_literal() {
+        let (items, rest): (Vec<u32>, &str) = parse!("1,2,3;rest", "{:,:};{}");
+        assert_eq!(items, [1, 2, 3]);
+        assert_eq!(rest, "rest");
+
+        let (items, rest): (Vec<u32>, &str) = parse!(";rest", "{:,:};{}");
+        assert!(items.is_empty());
+        assert_eq!(rest, "rest");
+
+        let (items, rest): (Vec<u32>, &str) = parse!("7;rest;more", "{:,:};{}");
+        assert_eq!(items, [7]);
+        assert_eq!(rest, "rest;more");
+
+        let (items, rest): (Vec<&str>, Vec<u8>) = parse!("a b; 1;2", "{: :};{:;:}");
+        assert_eq!(items, ["a", "b"]);
+        assert_eq!(rest, [1, 2]);
+
+        let result: Result<(Vec<u32>, &str), _> = try_parse!("1,2,3", "{:,:};{}");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn serialize_errors() {
+        use serde_json::json;
+
+        let literal: Result<(u32, u32), _> = try_parse!("1 + 2", "{} - {}");
+        assert_eq!(
+            serde_json::to_value(literal.unwrap_err()).unwrap(),
+            json!({"kind": "literal", "expected": " - ", "found": "1 + 2"})
+        );
+
+        let array: Result<[u32; 3], _> = try_parse!("1 2", "{: :3}");
+        assert_eq!(
+            serde_json::to_value(array.unwrap_err()).unwrap(),
+            json!({"kind": "array", "expected": 3, "found": 2})
+        );
+
+        let int: Result<(&str, u8), _> = try_parse!("x=300", "{}={}");
+        assert_eq!(
+            serde_json::to_value(int.unwrap_err()).unwrap(),
+            json!({
+                "kind": "context",
+                "full_string": "x=300",
+                "failed_item": "300",
+                "field_name": null,
+                "error": {"kind": "int", "message": "number too large to fit in target type"}
+            })
+        );
+
+        let error = ParseError::Dyn("not a cookie".into());
+        assert_eq!(
+            serde_json::to_string(&error).unwrap(),
+            r#"{"kind":"dyn","message":"not a cookie"}"#
+        );
+
+        let error = ParseError::with_code(404, "no such page");
+        assert_eq!(
+            serde_json::to_string(&error).unwrap(),
+            r#"{"kind":"coded","code":404,"message":"no such page"}"#
+        );
+    }
+
+    #[test]
+    fn io_errors() {
+        use std::io::ErrorKind;
+
+        let error: Result<u8, _> = try_parse!("level: 300", "level: {}");
+        let error = error.unwrap_err().into_io_error();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert_eq!(
+            error.to_string(),
+            "unable to parse \"300\" when parsing \"level: 300\":\n\tunable to parse as an integer"
+        );
+        let inner = error
+            .into_inner()
+            .unwrap()
+            .downcast::<ParseError>()
+            .unwrap();
+        assert_eq!(
+            inner.int_error_kind(),
+            Some(&std::num::IntErrorKind::PosOverflow)
+        );
+
+        fn read_level(line: &str) -> std::io::Result<u8> {
+            Ok(try_parse!(line, "level: {}")?)
+        }
+        assert_eq!(read_level("level: 3").unwrap(), 3);
+        let error = read_level("level: x").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        let inner = error.get_ref().unwrap().downcast_ref::<ParseError>();
+        assert!(matches!(inner, Some(ParseError::Context { .. })));
+
+        let error: Result<u8, _> = try_parse!("level: x", "level: {}");
+        let error = error.unwrap_err();
+        let io_error = error.as_io_error();
+        assert_eq!(io_error.kind(), ErrorKind::InvalidData);
+        assert_eq!(io_error.to_string(), error.to_string());
+    }
+
+    #[test]
+    fn anyhow_errors() {
+        fn read_level(line: &str) -> anyhow::Result<u8> {
+            try_parse!(line, "level: {}").map_err(ParseErrorExt::into_anyhow)
+        }
+        assert_eq!(read_level("level: 3").unwrap(), 3);
+        let error = read_level("level: 300").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "unable to parse \"300\" when parsing \"level: 300\":\n\tunable to parse as an integer"
+        );
+        let inner = error.downcast::<ParseError>().unwrap();
+        assert_eq!(
+            inner.int_error_kind(),
+            Some(&std::num::IntErrorKind::PosOverflow)
+        );
+    }
+
+    #[test]
+    fn coded_errors() {
+        let error = ParseError::with_code(12, format_args!("{} is reserved.", "root"));
+        assert_eq!(
+            error,
+            ParseError::Coded {
+                code: 12,
+                message: "root is reserved.".to_string()
+            }
+        );
+        assert_eq!(error.to_string(), "root is reserved.");
+        assert_ne!(error, ParseError::with_code(13, "root is reserved."));
+        assert_ne!(error, ParseError::new("root is reserved."));
+
+        let context = ParseError::Context {
+            full_string: "user: root".into(),
+            failed_item: "root".into(),
+            field_name: Some("name".into()),
+            error: Box::new(ParseError::with_code(12, "root is reserved.")),
+        };
+        assert_eq!(context.error_code(), Some(12));
+        assert_eq!(context.root_cause(), &error);
+        assert_eq!(
+            context.to_single_line(),
+            "unable to parse \"root\" when parsing \"user: root\" while parsing field `name`: root is reserved."
+        );
+    }
+
+    #[test]
+    fn nested_derives() {
+        #[derive(Debug, PartialEq, Parse)]
+        #[prse = "({x}, {y})"]
+        struct Point {
+            x: i32,
+            y: i32,
+        }
+
+        #[derive(Debug, PartialEq, Parse)]
+        #[prse = "{start} -> {end}"]
+        struct Segment {
+            start: Point,
+            end: Point,
+        }
+
+        #[derive(Debug, PartialEq, Parse)]
+        #[prse = "id={id} at {segment}"]
+        struct Outer {
+            id: u32,
+            segment: Segment,
+        }
+
+        let outer: Outer = parse!("id=7 at (0, 1) -> (2, -3)", "{}");
+        assert_eq!(
+            outer,
+            Outer {
+                id: 7,
+                segment: Segment {
+                    start: Point { x: 0, y: 1 },
+                    end: Point { x: 2, y: -3 },
+                },
+            }
+        );
+
+        let result: Result<Outer, _> = try_parse!("id=7 at (0, 1) -> (2, z)", "{}");
+        let ParseError::Context {
+            field_name, error, ..
+        } = result.unwrap_err()
+        else {
+            panic!("expected the error to have context");
+        };
+        assert_eq!(field_name.as_deref(), None);
+        let ParseError::Context {
+            field_name,
+            failed_item,
+            error,
+            ..
+        } = *error
+        else {
+            panic!("expected the error to name the outer field");
+        };
+        assert_eq!(field_name.as_deref(), Some("segment"));
+        assert_eq!(failed_item, "(0, 1) -> (2, z)");
+        let ParseError::Context { field_name, .. } = *error else {
+            panic!("expected the error to name the inner field");
+        };
+        assert_eq!(field_name.as_deref(), Some("end"));
+    }
+
+    #[test]
+    fn from_repr_error() {
+        #[derive(Debug, Parse)]
+        #[prse(from_repr)]
+        enum Opcode {
+            Nop = 0x00,
+            Halt = 0x76,
+        }
+
+        let codes: Vec<Opcode> = parse!("0 118", "{: :}");
+        assert!(matches!(codes[..], [Opcode::Nop, Opcode::Halt]));
+        assert_eq!(
+            Opcode::from_str("3").unwrap_err(),
+            ParseError::Other(
+                "3 does not match the discriminant of any variant of Opcode.".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn range_error() {
+        #[derive(Debug, Parse)]
+        #[prse(range)]
+        #[repr(u16)]
+        enum StatusClass {
+            #[prse(range = "200..300")]
+            Success,
+            #[prse(range = "400..=599")]
+            Error,
+        }
+
+        let classes: Vec<StatusClass> = parse!("204 599", "{: :}");
+        assert!(matches!(
+            classes[..],
+            [StatusClass::Success, StatusClass::Error]
+        ));
+        assert_eq!(
+            StatusClass::from_str("301").unwrap_err(),
+            ParseError::Other("301 is not in the range of any variant of StatusClass.".to_string())
+        );
+    }
+
+    #[test]
+    fn max_elements() {
+        let (allowed, crafted) = ("x,".repeat(999) + "x", "x,".repeat(1000) + "x");
+        let (entries, chars) = ("a=1;".repeat(1001), "x".repeat(1001));
+        let too_many = ParseError::TooManyElements { max: 1000 };
+
+        let elements: Vec<&str> = parse!(allowed, "{:,::max=1000}");
+        assert_eq!(elements.len(), 1000);
+        let elements: Result<Vec<&str>, _> = try_parse!(crafted, "{:,::max=1000}");
+        assert_eq!(elements.unwrap_err(), too_many);
+        let elements: Result<Vec<String>, _> = try_parse!(crafted, "{:,::dd,max=1000}");
+        assert_eq!(elements.unwrap_err(), too_many);
+        let map: Result<BTreeMap<&str, &str>, _> = try_parse!(entries, "{:;:map:max=1000}");
+        assert_eq!(map.unwrap_err(), too_many);
+        let chars: Result<Vec<char>, _> = try_parse!(chars, "{:::max=1000}");
+        assert_eq!(chars.unwrap_err(), too_many);
+        let rows: Result<Vec<[u8; 1]>, _> = try_parse!("1;2;3", "{:;:[,:1]:max=2}");
+        assert_eq!(rows.unwrap_err(), ParseError::TooManyElements { max: 2 });
+
+        // Without the modifier there is no limit.
+        let elements: Vec<&str> = parse!(crafted, "{:,:}");
+        assert_eq!(elements.len(), 1001);
+    }
+
+    #[test]
+    fn flags_error() {
+        #[derive(Debug, Parse)]
+        #[prse(flags)]
+        struct Permissions {
+            read: bool,
+            write: bool,
+        }
+
+        let all: Vec<Permissions> = parse!("read write|write|", "{:|::trailing}");
+        assert!(all[0].read && all[0].write);
+        assert!(!all[1].read && all[1].write);
+        assert!(!all[2].read && !all[2].write);
+        assert_eq!(
+            Permissions::from_str("read Execute").unwrap_err(),
+            ParseError::Other("\"Execute\" is not a flag of Permissions.".to_string())
+        );
+    }
+
+    #[test]
+    fn case_insensitive_map_key() {
+        use std::collections::HashMap;
+
+        let mut counts: HashMap<CaseInsensitive, u32> = HashMap::new();
+        let words: Vec<CaseInsensitive> = parse!("Let fn LET let FN match", "{: :}");
+        for w in words {
+            *counts.entry(w).or_default() += 1;
+        }
+        assert_eq!(counts[&CaseInsensitive("let")], 3);
+        assert_eq!(counts[&CaseInsensitive("fn")], 2);
+        assert_eq!(counts.len(), 3);
+    }
+
+    #[test]
+    fn parse_into_map() {
+        use std::collections::HashMap;
+
+        let map: HashMap<String, u32> = parse!("a=1 b=2 c=3", "{: :map}");
+        assert_eq!(
+            map,
+            HashMap::from([("a".into(), 1), ("b".into(), 2), ("c".into(), 3)])
+        );
+
+        let map: HashMap<&str, u32> = parse!("a=1 b=2 a=3", "{: :map}");
+        assert_eq!(map, HashMap::from([("a", 3), ("b", 2)]));
+
+        let map: HashMap<String, u32> = parse!("values: ", "values: {: :map}");
+        assert!(map.is_empty());
+
+        let map: BTreeMap<u8, bool> = parse!("{ 1 =true,,2=false }", "{{ {:,:!map} }}");
+        assert_eq!(map, BTreeMap::from([(1, true), (2, false)]));
+
+        let map: BTreeMap<&str, u16> = parse!("http = 80;https= 443 ", "{:;:map}");
+        assert_eq!(map, BTreeMap::from([("http", 80), ("https", 443)]));
+
+        let (name, map): (&str, HashMap<&str, i32>) = parse!("origin: x=-1 y=2", "{}: {: :map}");
+        assert_eq!(name, "origin");
+        assert_eq!(map, HashMap::from([("x", -1), ("y", 2)]));
+
+        let missing: Result<HashMap<&str, u32>, _> = try_parse!("a=1 b", "{: :map}");
+        assert_eq!(
+            missing.unwrap_err(),
+            ParseError::MultiContext {
+                multi_string: "a=1 b".into(),
+                failed_string: "b".into(),
+                error: Box::new(ParseError::Literal {
+                    expected: "=".into(),
+                    found: "b".into()
+                }),
+            }
+        );
+        let invalid: Result<HashMap<&str, u32>, _> = try_parse!("a=1 b=x", "{: :map}");
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn parse_padded_vectors() {
+        let shorter: Vec<f64> = parse_padded!("0.5, 1.5", "{:, :}", 4, 0.0);
+        assert_eq!(shorter, [0.5, 1.5, 0.0, 0.0]);
+        let equal: Vec<f64> = parse_padded!("1,2,3,4", "{:,:}", 4, f64::NAN);
+        assert_eq!(equal, [1.0, 2.0, 3.0, 4.0]);
+        let longer: Vec<f64> = parse_padded!("1,2,3,4,5,x", "{:,:}", 4, 0.0);
+        assert_eq!(longer, [1.0, 2.0, 3.0, 4.0]);
+        let empty: Vec<u8> = parse_padded!("[]", "[{:,:0}]", 3, 7);
+        assert_eq!(empty, [7, 7, 7]);
+        let none: Vec<u8> = parse_padded!("1,2", "{:,:}", 0, 0);
+        assert!(none.is_empty());
+
+        let names: Vec<String> = parse_padded!("v: a b", "v: {: :}", 3, "-".to_string());
+        assert_eq!(names, ["a", "b", "-"]);
+        let hex: Vec<u8> = parse_padded!("ff,0a", "{:,::x}", 3, 0);
+        assert_eq!(hex, [255, 10, 0]);
+
+        let exact: Result<Vec<u8>, _> = try_parse_padded!("1,2", "{:,:}", 2, 0, Overflow::Error);
+        assert_eq!(exact.unwrap(), [1, 2]);
+        let padded: Result<Vec<u8>, _> = try_parse_padded!("1", "{:,:}", 2, 0, Overflow::Error);
+        assert_eq!(padded.unwrap(), [1, 0]);
+        let overflow: Result<Vec<u8>, _> =
+            try_parse_padded!("1,2,3,4", "{:,:}", 2, 0, Overflow::Error);
+        assert_eq!(
+            overflow.unwrap_err(),
+            ParseError::Other("expected at most 2 elements, found 4.".to_string())
+        );
+        let invalid_extra: Result<Vec<u8>, _> =
+            try_parse_padded!("1,2,x", "{:,:}", 2, 0, Overflow::Error);
+        assert!(matches!(
+            invalid_extra,
+            Err(ParseError::MultiContext { .. })
+        ));
+        let policy = Overflow::default();
+        let truncated: Result<Vec<u8>, _> = try_parse_padded!("1,2,x", "{:,:}", 2, 0, policy);
+        assert_eq!(truncated.unwrap(), [1, 2]);
+
+        let invalid: Result<Vec<u8>, _> = try_parse_padded!("1,x", "{:,:}", 3, 0);
+        assert!(invalid.is_err());
+        let mismatch: Result<Vec<u8>, _> = try_parse_padded!("(1,2", "({:,:})", 3, 0);
+        assert!(mismatch.is_err());
+    }
+
+    #[test]
+    fn parse_blank_line_records() {
+        let input = "\nname: Alice\nage: 30\n\n\nname: Bob\nage: 25\n\n";
+        let people: Vec<(&str, u8)> = parse_records!(input, "name: {}\nage: {}");
+        assert_eq!(people, [("Alice", 30), ("Bob", 25)]);
+        let windows: Vec<(String, u8)> = parse_records!(
+            "name: Alice\r\nage: 30\r\n  \r\nname: Bob\r\nage: 25",
+            "name: {}\nage: {}"
+        );
+        assert_eq!(
+            windows,
+            [("Alice".to_string(), 30), ("Bob".to_string(), 25)]
+        );
+        let lines: Vec<Vec<u32>> = parse_records!("1\n2\n\n3", "{:\n:}");
+        assert_eq!(lines, [vec![1, 2], vec![3]]);
+        let empty: Vec<u32> = parse_records!("\n \n", "{}");
+        assert!(empty.is_empty());
+
+        let malformed: Result<Vec<(&str, u8)>, _> = try_parse_records!(
+            "name: Alice\nage: 30\n\nname: Bob\nage: old",
+            "name: {}\nage: {}"
+        );
+        assert_eq!(
+            malformed.unwrap_err(),
+            ParseError::Context {
+                full_string: "name: Alice\nage: 30\n\nname: Bob\nage: old".into(),
+                failed_item: "name: Bob\nage: old".into(),
+                field_name: Some("record 1".into()),
+                error: Box::new(ParseError::Context {
+                    full_string: "name: Bob\nage: old".into(),
+                    failed_item: "old".into(),
+                    field_name: None,
+                    error: Box::new(ParseError::Int("old".parse::<u8>().unwrap_err())),
+                }),
+            }
+        );
+        let missing: Result<Vec<(&str, u8)>, _> =
+            try_parse_records!("name: Alice\n\nage: 30", "name: {}\nage: {}");
+        assert!(matches!(
+            missing,
+            Err(ParseError::Context { field_name: Some(name), .. }) if name == "record 0"
+        ));
+    }
+
+    #[test]
+    fn parse_matrix_rows() {
+        let matrix: Vec<[i32; 3]> = parse!("1,2,3;4,5,6", "{:;:[,:3]}");
+        assert_eq!(matrix, [[1, 2, 3], [4, 5, 6]]);
+        let matrix: Vec<[String; 2]> = parse!("a  b\nc d\r", "{:\n:[ :!2]:trim}");
+        assert_eq!(matrix, [["a", "b"], ["c", "d"]]);
+        let matrix: Vec<[u8; 2]> = parse!("1,2 : 3,4", "{: : ::[,:2]}");
+        assert_eq!(matrix, [[1, 2], [3, 4]]);
+
+        let short_row: Result<Vec<[i32; 3]>, _> = try_parse!("1,2,3;4,5", "{:;:[,:3]}");
+        assert_eq!(
+            short_row.unwrap_err(),
+            ParseError::Context {
+                full_string: "1,2,3;4,5".into(),
+                failed_item: "4,5".into(),
+                field_name: Some("row 1".into()),
+                error: Box::new(ParseError::Array {
+                    expected: 3,
+                    found: 2
+                }),
+            }
+        );
+    }
+
+    #[derive(Parse, Debug, PartialEq)]
+    #[prse = "[{name}]\n{settings:\n:map}"]
+    struct Section<'a> {
+        name: &'a str,
+        settings: Vec<(&'a str, &'a str)>,
+    }
+
+    #[test]
+    fn parse_ordered_pairs() {
+        let pairs: Vec<(&str, u32)> = parse!("b=2, a=1, c=3", "{:, :map}");
+        assert_eq!(pairs, [("b", 2), ("a", 1), ("c", 3)]);
+
+        let pairs: Vec<(String, u32)> = parse!("x=1 y=2 x=3 x=1", "{: :map}");
+        assert_eq!(
+            pairs,
+            [
+                ("x".into(), 1),
+                ("y".into(), 2),
+                ("x".into(), 3),
+                ("x".into(), 1)
+            ]
+        );
+
+        let pairs: Vec<(&str, &str)> = parse!("", "{:;:map}");
+        assert!(pairs.is_empty());
+
+        let section: Section = parse!("[core]\nzeta = 1\nalpha =2\nzeta=3", "{}");
+        assert_eq!(section.name, "core");
+        assert_eq!(
+            section.settings,
+            [("zeta", "1"), ("alpha", "2"), ("zeta", "3")]
+        );
+
+        let invalid: Result<Vec<(&str, u8)>, _> = try_parse!("a=1 b=300", "{: :map}");
+        assert!(invalid.is_err());
     }
 
     #[test]
@@ -209,5 +1871,55 @@ mod tests {
         assert_eq!(Ok(77), try_macro_call(7));
     }
 
+    #[derive(Debug, PartialEq)]
+    struct U192([u64; 3]);
+
+    impl ParseRadix for U192 {
+        fn from_radix_str(s: &str, radix: u32) -> Result<Self, ParseError> {
+            let digits = s.strip_prefix('+').unwrap_or(s);
+            if digits.is_empty() {
+                return Err(ParseError::new(format!("{s:?} has no digits.")));
+            }
+            let mut limbs = [0_u64; 3];
+            for c in digits.chars() {
+                let Some(digit) = c.to_digit(radix) else {
+                    return Err(ParseError::new(format!("{c:?} is not a digit in {s:?}.")));
+                };
+                let mut carry = u128::from(digit);
+                for limb in limbs.iter_mut() {
+                    let wide = u128::from(*limb) * u128::from(radix) + carry;
+                    *limb = wide as u64;
+                    carry = wide >> 64;
+                }
+                if carry != 0 {
+                    return Err(ParseError::new(format!("{s:?} does not fit in 192 bits.")));
+                }
+            }
+            Ok(U192(limbs))
+        }
+    }
+
+    #[test]
+    fn parse_custom_radix() {
+        let small: U192 = parse!("0xff", "{:auto}");
+        assert_eq!(small, U192([255, 0, 0]));
+        let wide: U192 = parse!("1_0000_0000_0000_0000_0000_0000_0000_0000", "{:x,_}");
+        assert_eq!(wide, U192([0, 0, 1]));
+        let max: U192 = parse!(&"f".repeat(48), "{:x}");
+        assert_eq!(max, U192([u64::MAX; 3]));
+        let binary: U192 = parse!("+0b11", "{:b}");
+        assert_eq!(binary, U192([3, 0, 0]));
+        let decimal: (U192, U192) = parse!("12 0o17", "{:_} {:auto}");
+        assert_eq!(decimal, (U192([12, 0, 0]), U192([15, 0, 0])));
+
+        assert!(U192::from_radix_str("1", 2).is_ok());
+        let too_wide: Result<U192, _> = try_parse!(&"f".repeat(49), "{:x}");
+        assert!(too_wide.is_err());
+        let negative: Result<U192, _> = try_parse!("-0x1", "{:auto}");
+        assert!(negative.is_err());
+        let invalid: Result<U192, _> = try_parse!("0b12", "{:auto}");
+        assert!(invalid.is_err());
+    }
+
     include!("../common.rs");
 }
@@ -0,0 +1,21 @@
+use prse::{parse, Parse};
+
+#[derive(Parse)]
+#[prse = "{x}{y}"]
+struct Position {
+    x: i32,
+    y: i32,
+}
+
+fn main() {
+    let l = "12";
+
+    parse!(l, "{}{}");
+    parse!(l, "{0}{1}");
+    parse!(l, "test: {}{} end");
+    parse!(l, "{:,:}{}");
+    parse!(l, "{}{:,:}");
+    parse!(l, "{:len}{}");
+    parse!(l, "{}{:line}");
+    parse!(l, "{:line}{}");
+}
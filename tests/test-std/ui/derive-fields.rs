@@ -0,0 +1,123 @@
+use prse::Parse;
+
+#[derive(Parse)]
+#[prse = "{x} - {foo}"]
+struct A {
+    x: u32,
+}
+
+#[derive(Parse)]
+#[prse = "{x}"]
+struct B {
+    x: u32,
+    bar: u32,
+}
+
+#[derive(Parse)]
+enum C {
+    #[prse = "{x}"]
+    V { x: u32, bar: u32 },
+}
+
+#[derive(Parse)]
+#[prse = "{x}"]
+struct D {
+    x: u32,
+    #[prse(default)]
+    #[prse(skip)]
+    bar: u32,
+}
+
+#[derive(Parse)]
+#[prse = "{x:,:}"]
+struct E {
+    #[prse(multi)]
+    x: Vec<u32>,
+}
+
+#[derive(Parse)]
+#[prse = "{date} {date}"]
+#[prse(sub(date = "{y}-{m}"))]
+struct F {
+    y: u32,
+    m: u32,
+}
+
+#[derive(Parse)]
+#[prse = "{[{date}]}"]
+#[prse(sub(date = "{y}-{m}"))]
+struct G {
+    y: u32,
+    m: u32,
+}
+
+#[derive(Parse)]
+#[prse = "{date} {y}"]
+#[prse(sub(date = "{y}-{m}"))]
+struct H {
+    y: u32,
+    m: u32,
+}
+
+#[derive(Parse)]
+#[prse = "{date}"]
+#[prse(sub(date = "{y}-{z}"))]
+struct I {
+    y: u32,
+}
+
+#[derive(Parse)]
+#[prse = "{y}"]
+#[prse(sub(y = "{y}"))]
+struct J {
+    y: u32,
+}
+
+#[derive(Parse)]
+#[prse(sub(date = "{y}"))]
+struct K {
+    y: u32,
+}
+
+#[derive(Parse)]
+#[prse = "{0}"]
+#[prse(sub(date = "{y}"))]
+struct L(u32);
+
+#[derive(Parse)]
+#[prse = "{date}"]
+#[prse(sub(date = "{y}", date = "{y}"))]
+struct M {
+    y: u32,
+}
+
+#[derive(Parse)]
+#[prse = "{name}"]
+struct N {
+    #[prse(radix = 16)]
+    name: String,
+}
+
+#[derive(Parse)]
+#[prse = "{x:x}"]
+struct O {
+    #[prse(radix = 16)]
+    x: u32,
+}
+
+#[derive(Parse)]
+#[prse = "{x}"]
+struct P {
+    #[prse(radix = 10)]
+    x: u32,
+}
+
+#[derive(Parse)]
+#[prse = "{:#:count}"]
+struct Q(#[prse(radix = 2)] usize);
+
+#[derive(Parse)]
+#[prse = "{}"]
+struct R(#[prse(default)] u32);
+
+fn main() {}
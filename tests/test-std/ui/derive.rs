@@ -87,7 +87,7 @@ enum M {
 
 #[derive(Parse)]
 enum N {
-    #[prse("N")]
+    #[prse(N)]
     N,
 }
 
@@ -97,4 +97,89 @@ enum O {
     O,
 }
 
+#[derive(Parse)]
+#[prse("{} {}")]
+struct P(u32);
+
+#[derive(Parse)]
+#[prse("{}")]
+struct Q(u32, u32);
+
+#[derive(Parse)]
+enum R {
+    #[prse("{0}")]
+    R(u32, u32),
+}
+
+#[derive(Parse)]
+#[prse(from_repr)]
+enum S {
+    S,
+    T(u32),
+}
+
+#[derive(Parse)]
+#[prse(from_repr)]
+enum U {
+    #[prse("u")]
+    U,
+}
+
+#[derive(Parse)]
+#[prse("(?i)V")]
+struct V;
+
+#[derive(Parse)]
+#[prse(flags)]
+struct W(bool);
+
+#[derive(Parse)]
+#[prse(flags(strict))]
+struct X {
+    x: bool,
+}
+
+#[derive(Parse)]
+#[prse(flags)]
+struct Y {
+    #[prse(default)]
+    y: bool,
+}
+
+#[derive(Parse)]
+#[prse(range)]
+enum Z {
+    #[prse(range = "0..10")]
+    Z,
+    Z2,
+}
+
+#[derive(Parse)]
+#[prse(range)]
+enum Z3 {
+    #[prse(range = "10")]
+    Z3,
+}
+
+#[derive(Parse)]
+#[prse(range)]
+enum Z5 {
+    #[prse(range = "0..")]
+    Z5(u32),
+}
+
+#[derive(Parse)]
+#[prse = "Z6: {a}"]
+#[prse = "Z6"]
+struct Z6 {
+    a: usize,
+}
+
+// A repeated field is a back-reference, which cannot be a repetition.
+#[derive(Parse)]
+#[prse = "Z7: {a:,:} {a}"]
+struct Z7 {
+    a: Vec<usize>,
+}
+
 fn main() {}
@@ -1,4 +1,4 @@
-use prse::parse;
+use prse::{parse, pattern_literals, which_matches};
 
 fn main() {
     let l = "test: 5";
@@ -9,4 +9,22 @@ fn main() {
     parse!("test: {x}",);
     parse!("test: {x}", l);
     parse!(l, 2);
+
+    const PATTERN: &str = "test: {x}";
+    parse!(l, PATTERN);
+    parse!(l, self::PATTERN);
+}
+
+fn literals() {
+    pattern_literals!("(?w)a {} b");
+    pattern_literals!("(?c){} {}");
+    pattern_literals!(2);
+}
+
+fn which() {
+    which_matches!("a", []);
+    which_matches!("a", "{}");
+    which_matches!("a", ["{}" "{}"]);
+    which_matches!("a", [PATTERN]);
+    which_matches!("a", ["{"]);
 }
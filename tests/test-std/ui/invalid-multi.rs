@@ -1,4 +1,4 @@
-use prse::parse;
+use prse::{parse, parse_for_each, parse_padded, parse_records};
 
 fn main() {
     let l = "test: 5";
@@ -10,4 +10,74 @@ fn main() {
     parse!(l, "test: {:,:999}");
     parse!(l, "test: {:,:-1}");
     parse!(l, "test: {:,:,}");
+    parse!(l, "test: {:hex}");
+    parse!(l, "test: {:x,b}");
+    parse!(l, "test: {:trim,ltrim}");
+    parse!(l, "test: {:dd}");
+    parse!(l, "test: {:,:0:dd}");
+    parse!(l, "test: {:,:!:dd}");
+    parse!(l, "test: {:::dd}");
+    parse!(l, "test: {:,::dd,dd}");
+    parse!(l, "test: {:x,_,_}");
+    parse!(l, "test: {::count}");
+    parse!(l, "test: {:#:count:x}");
+    parse!(l, "test: {:#:!count}");
+    parse!(l, "test: {}{:#:count}");
+    parse!(l, "test: {}{:c}");
+    parse!(l, "test: {::map}");
+    parse!(l, "test: {: :map:x}");
+    parse!(l, "test: {:~}");
+    parse!(l, r"test: {:~+\d}");
+    parse!(l, r"test: {:~\d**}");
+    parse!(l, r"test: {:~\d\}");
+    parse!(l, "test: {:len,clen}");
+    parse!(l, "test: {:x,len}");
+    parse!(l, "test: {:,::len}");
+    parse!(l, "test: {:host,email}");
+    parse!(l, "test: {:[, ]+:!}");
+    parse!(l, "test: {:[,]+:dd}");
+    parse!(l, "test: {:[,;]:dd}");
+    parse!(l, "test: {:map(a => 1, b => 2, a => 3)}");
+    parse!(l, "test: {:map(a => 1, b)}");
+    parse!(l, "test: {:map(a => 1}");
+    parse!(l, "test: {:map()}");
+    parse!(l, "test: {:x,map(a => 1)}");
+    parse!(l, "test: {:,::map(a => 1)}");
+    parse!(l, "test: {:==}");
+    parse!(l, "test: {:len, == 2}");
+    parse!(l, "test: {:,:2:== 1}");
+    parse!(l, "test: {:map(a => 1), == a}");
+    parse!(l, "test: {:trailing}");
+    parse!(l, "test: {:::trailing}");
+    parse!(l, "test: {:,:!:trailing}");
+    parse!(l, "test: {:[, ]+::trailing}");
+    parse!(l, "test: {:,::dd,trailing}");
+    parse!(l, "test: {:[]:}");
+    parse!(l, "test: {:map(a => 1) x}");
+    parse!(l, "test: {:,:0:x}");
+    parse!(l, "test: {::0:trim}");
+    parse!(l, "test: {:max=2}");
+    parse!(l, "test: {:,::max=two}");
+    parse!(l, "test: {:,::max=2,max=3}");
+    parse!(l, "test: {:,:map:x}");
+    parse!(l, "test: {:;:0:[,:3]}");
+    parse!(l, "test: {:;:count:[,:3]}");
+    parse!(l, "test: {:;:[,:0]}");
+    parse!(l, "test: {:;:[,:x]}");
+    parse!(l, "test: {:;:[:!3]}");
+    parse!(l, "test: {:;:[,:3]:dd}");
+    parse!(l, "test: {:a:b:[,:2]}");
+    parse!(l, "(?ix)test: {}");
+    parse!(l, "(?ii)test: {}");
+    parse_for_each!(l, "test: {}", |_: u32| {});
+    parse_for_each!(l, "test: {:,:} {:,:}", |_: u32| {});
+    parse_for_each!(l, "test: {:,:3}", |_: u32| {});
+    parse_for_each!(l, "test: {:,:dd}", |_: u32| {});
+    parse_for_each!(l, "test: {:;:[,:3]}", |_: [u32; 3]| {});
+    parse_for_each!(l, "test: {:,:}");
+    parse_padded!(l, "test: {}", 2, 0);
+    parse_padded!(l, "test: {:,:dd}", 2, String::new());
+    parse_padded!(l, "test: {:,:}", 2);
+    let x: u32;
+    parse_records!(l, "test: {x}");
 }
@@ -0,0 +1,52 @@
+use prse::{parse, parse_located, pattern_literals, Parse};
+
+#[derive(Parse)]
+#[prse = "{x}{[ {y}]}"]
+struct A {
+    x: u32,
+    y: u32,
+}
+
+#[derive(Parse)]
+#[prse = "{x}{[ {y}]}"]
+struct B {
+    x: u32,
+    #[prse(default, multi)]
+    y: Vec<u32>,
+}
+
+#[derive(Parse)]
+#[prse = "{[a]}"]
+struct C;
+
+#[derive(Parse)]
+#[prse = "{}{( {})}"]
+struct D(u32, Option<u32>);
+
+fn main() {
+    let l = "test: 5";
+    let x: u32;
+
+    parse!(l, "test: {[{[ {}]}]}");
+    parse!(l, "test: {[]}");
+    parse!(l, "test: {[ {}");
+    parse!(l, "{[a]}{[b]}{[c]}{[d]}{[e]}");
+    parse!(l, "{x}{[ {x}]}");
+    parse!(l, "{}{[{}]}");
+    parse!(l, "{}{[ ]}{}");
+    parse!(l, "test: {( {}");
+    parse!(l, "test: {( {}]}");
+    parse!(l, "test: {( )}");
+    parse!(l, "test: {( {x})}");
+    parse!(l, "test: {( {0})}");
+    parse!(l, "test: {:default=1}");
+    parse!(l, "test: {( {:default=1})}");
+    parse!(l, "test: {[{:,::default=1}]}");
+    parse!(l, "test: {[{:default=1,default=2}]}");
+    parse_located!(l, "test: {[{:default=1}]}");
+}
+
+fn literals() {
+    pattern_literals!("GET {}{[ {}]}");
+    pattern_literals!("pt={(({},{}))}");
+}
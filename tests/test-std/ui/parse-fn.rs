@@ -0,0 +1,8 @@
+use prse::parse_fn;
+
+parse_fn!(parse_named, "{x}: {}", (i32, i32));
+parse_fn!(parse_missing_type, "{}");
+parse_fn!(parse_const, PATTERN, (i32, i32));
+parse_fn!(parse_no_name "{}", (i32,));
+
+fn main() {}